@@ -12,6 +12,8 @@ use crate::{CobaltFileSystemProxy, LocalizedFileSystem};
 pub struct AtlasSystem {
     pub atlases: HashMap<String, SpriteAtlasWrapper>,
     pub cobalt_icons: HashMap<String, HashMap<String, DynamicImage>>,
+    /// Rendered on first use and kept for [map rendering](crate::Astra::render_chapter_map).
+    units: Option<HashMap<String, DynamicImage>>,
 }
 
 struct AtlasInfo {
@@ -143,40 +145,55 @@ impl AtlasSystem {
         Ok(Self {
             atlases,
             cobalt_icons,
+            units: None,
         })
     }
 
-    pub fn take_sprites(&mut self, atlas_id: &str) -> Option<HashMap<String, DynamicImage>> {
-        if atlas_id == "units" {
-            let cobalt_sprites = self.cobalt_icons.remove("unit_indexes").unwrap_or_default();
-            let indexes = self.take_sprites("unit_indexes")?;
-            let palettes = self.take_sprites("unit_palettes")?;
-            let mut rendered_sprites = HashMap::new();
-            for (unit_name, index) in indexes {
-                if let Some(palette_name) = unit_name.split('_').next() {
-                    if let Some(palette) = palettes.get(palette_name) {
-                        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(
-                            index.width(),
-                            index.height(),
-                            |x, y| {
-                                palette
-                                    .get_pixel(index.get_pixel(x, y).0[0] as u32 * 2, 0)
-                                    .to_owned()
-                            },
-                        ));
-                        rendered_sprites.insert(unit_name, image);
-                    }
+    /// Unit sprites keyed by `{unit}_{job}_{weapon}` icon IDs.
+    pub fn unit_sprites(&mut self) -> &HashMap<String, DynamicImage> {
+        let units = match self.units.take() {
+            Some(units) => units,
+            None => self.render_unit_sprites(),
+        };
+        self.units.insert(units)
+    }
+
+    fn render_unit_sprites(&mut self) -> HashMap<String, DynamicImage> {
+        let cobalt_sprites = self.cobalt_icons.remove("unit_indexes").unwrap_or_default();
+        let (indexes, palettes) = match (
+            self.take_sprites("unit_indexes"),
+            self.take_sprites("unit_palettes"),
+        ) {
+            (Some(indexes), Some(palettes)) => (indexes, palettes),
+            _ => return HashMap::new(),
+        };
+        let mut rendered_sprites = HashMap::new();
+        for (unit_name, index) in indexes {
+            if let Some(palette_name) = unit_name.split('_').next() {
+                if let Some(palette) = palettes.get(palette_name) {
+                    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(
+                        index.width(),
+                        index.height(),
+                        |x, y| {
+                            palette
+                                .get_pixel(index.get_pixel(x, y).0[0] as u32 * 2, 0)
+                                .to_owned()
+                        },
+                    ));
+                    rendered_sprites.insert(unit_name, image);
                 }
             }
-            rendered_sprites.extend(cobalt_sprites);
-            Some(rendered_sprites)
-        } else {
-            self.atlases.remove(atlas_id).map(|atlas| {
-                let mut sprites = atlas.unwrap_sprites();
-                sprites.extend(self.cobalt_icons.remove(atlas_id).unwrap_or_default());
-                sprites
-            })
         }
+        rendered_sprites.extend(cobalt_sprites);
+        rendered_sprites
+    }
+
+    pub fn take_sprites(&mut self, atlas_id: &str) -> Option<HashMap<String, DynamicImage>> {
+        self.atlases.remove(atlas_id).map(|atlas| {
+            let mut sprites = atlas.unwrap_sprites();
+            sprites.extend(self.cobalt_icons.remove(atlas_id).unwrap_or_default());
+            sprites
+        })
     }
 }
 
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use astra_formats::Book;
 use astra_types::{
     AchievementBook, AiBook, AmiiboBook, AnimSetBook, AnimalBook, ArenaBook, AssetTableBook,
@@ -16,11 +16,93 @@ use astra_types::{
     RelianceBook, RingBook, RingCleaningVoiceBook, ShopBook, SkillBook, SoundEventBook,
     TerrainBook, TitleBook, TutorialBook, VibrationBook,
 };
+use astra_types::{DiffableBook, PatchNode, PatchableBook, SheetDiff};
 use parking_lot::RwLock;
 use tracing::info;
 
 use crate::{BundlePersistFormat, CobaltFileSystemProxy};
 
+/// Invoke `$callback` with the field name of every book owned by the [BookSystem].
+macro_rules! with_books {
+    ($callback:ident!($($args:tt)*)) => {
+        $callback!($($args)*;
+            achieve, ai, amiibo, anim_set, animal, arena, asset_table, calculator, chapter, chart,
+            cook, dragon_ride_preset_param, dragon_ride_prize_list, dragon_ride_target_pattern,
+            effect, encount, end_roll, fishing, friend_list, god, ground_attribute, hub_area,
+            hub_demo, hub_dispos, hub_fortune_telling, hub_investment, hub_map_icon, hub_my_room,
+            hub_resource, hub_talk, item, job, jukebox, key_help, kill_bonus, later_talk,
+            map_editor, map_history, mascot, movie, music, muscle_exercise, param, person,
+            photograph, profile_card, range, relay, reliance, ring, ring_cleaning_voice, shop,
+            skill, sound_event, terrain, title, tutorial, vibration,
+        )
+    };
+}
+
+macro_rules! diff_books {
+    ($ours:expr, $theirs:expr; $($book:ident,)+) => {
+        vec![$(BookDiff {
+            book: stringify!($book).to_string(),
+            sheets: $ours.$book.diff(&$theirs.$book),
+        },)+]
+    };
+}
+
+macro_rules! pull_book_row {
+    ($ours:expr, $theirs:expr, $name:expr, $sheet:expr, $key:expr; $($book:ident,)+) => {
+        match $name {
+            $(stringify!($book) => $ours.$book.pull_row(&$theirs.$book, $sheet, $key),)+
+            _ => Err(anyhow!("unknown book '{}'", $name)),
+        }
+    };
+}
+
+macro_rules! find_patch_sheet {
+    ($system:expr, $sheet:expr; $($book:ident,)+) => {{
+        let mut books = vec![];
+        $(if $system.$book.read(|book| book.patch_sheet($sheet).is_some()) {
+            books.push(stringify!($book));
+        })+
+        books
+    }};
+}
+
+macro_rules! patch_book {
+    ($system:expr, $name:expr, $sheet:expr, $patch:expr; $($book:ident,)+) => {
+        match $name {
+            $(stringify!($book) => $system.$book.patch($sheet, $patch),)+
+            _ => Err(anyhow!("unknown book '{}'", $name)),
+        }
+    };
+}
+
+/// Differences between the same book in two projects.
+#[derive(Debug, Clone)]
+pub struct BookDiff {
+    pub book: String,
+    pub sheets: Vec<SheetDiff>,
+}
+
+impl BookDiff {
+    pub fn is_empty(&self) -> bool {
+        self.sheets.iter().all(|sheet| sheet.rows.is_empty())
+    }
+}
+
+/// Resolve a chapter's dispos, terrain or script name to a file name by replacing `*` with the
+/// CID minus its `CID_` prefix.
+pub fn chapter_file_name(cid: &str, name: &str) -> String {
+    name.replace('*', cid.trim_start_matches("CID_"))
+        .to_lowercase()
+}
+
+/// A dispos book along with the chapter that uses it.
+pub(crate) struct ChapterDispos {
+    pub cid: String,
+    pub progress: u8,
+    pub name: String,
+    pub book: OpenBook<DisposBook>,
+}
+
 pub struct BookSystem {
     file_system: Arc<CobaltFileSystemProxy>,
     dispos: HashMap<String, OpenBook<DisposBook>>,
@@ -244,6 +326,156 @@ impl BookSystem {
         }
     }
 
+    /// Copy a dispos book, including unsaved edits, to a new name.
+    /// The copy is written on the next save.
+    pub fn copy_dispos(&mut self, from: &str, to: &str) -> Result<()> {
+        if self.dispos_exists(to)? {
+            bail!("dispos '{}' already exists", to);
+        }
+        let source = self.open_dispos(from)?;
+        let copy: OpenBook<DisposBook> = self.file_system.copy_book(
+            Path::new("dispos").join(from.to_lowercase()),
+            &from.to_uppercase(),
+            Path::new("dispos").join(to.to_lowercase()),
+            &to.to_uppercase(),
+        )?;
+        source.read(|source| copy.write(|copy| copy.spawns.data = source.spawns.data.clone()));
+        self.dispos.insert(to.to_string(), copy);
+        Ok(())
+    }
+
+    /// The CID, progress and possible dispos names of every chapter, in chapter order.
+    /// Each chapter lists its encounter dispos first, then its main dispos.
+    fn chapter_dispos_names(&self) -> Vec<(String, u8, String)> {
+        self.chapter.read(|book| {
+            book.chapters
+                .data
+                .values()
+                .filter(|chapter| !chapter.dispos.is_empty())
+                .flat_map(|chapter| {
+                    let stem = chapter_file_name(&chapter.cid, &chapter.dispos);
+                    [format!("{}e", stem), stem]
+                        .map(|name| (chapter.cid.clone(), chapter.progress, name))
+                })
+                .collect()
+        })
+    }
+
+    /// Open the main and encounter dispos of every chapter, in chapter order.
+    /// Books that don't exist (most chapters have no encounter dispos) are skipped.
+    pub(crate) fn open_chapter_dispos(&mut self) -> Result<Vec<ChapterDispos>> {
+        let mut dispos: Vec<ChapterDispos> = vec![];
+        for (cid, progress, name) in self.chapter_dispos_names() {
+            if dispos.iter().any(|dispos| dispos.name == name) || !self.dispos_exists(&name)? {
+                continue;
+            }
+            let book = self
+                .open_dispos(&name)
+                .with_context(|| format!("failed to open dispos '{}'", name))?;
+            dispos.push(ChapterDispos {
+                cid,
+                progress,
+                name,
+                book,
+            });
+        }
+        Ok(dispos)
+    }
+
+    /// Whether a dispos book is open or exists on disk, without loading it.
+    pub fn dispos_exists(&self, dispos_name: &str) -> Result<bool> {
+        Ok(self.dispos.contains_key(dispos_name)
+            || self.file_system.book_exists(
+                Path::new("dispos").join(dispos_name.to_lowercase()),
+                &dispos_name.to_uppercase(),
+            )?)
+    }
+
+    /// Compare every book against the same book in another project.
+    /// Books without differences are left out.
+    pub fn diff(&self, theirs: &BookSystem) -> Vec<BookDiff> {
+        let mut diffs: Vec<BookDiff> = with_books!(diff_books!(self, theirs));
+        diffs.retain(|diff| !diff.is_empty());
+        diffs
+    }
+
+    /// Compare the dispos books used by chapters in either project.
+    /// Books that only one project has can't be pulled row by row, so they are left out,
+    /// along with books without differences.
+    pub fn diff_chapter_dispos(&mut self, theirs: &mut BookSystem) -> Result<Vec<BookDiff>> {
+        let mut names: Vec<String> = vec![];
+        for (_, _, name) in self
+            .chapter_dispos_names()
+            .into_iter()
+            .chain(theirs.chapter_dispos_names())
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let mut diffs = vec![];
+        for name in names {
+            if !self.dispos_exists(&name)? || !theirs.dispos_exists(&name)? {
+                continue;
+            }
+            let diff = self
+                .diff_dispos(theirs, &name)
+                .with_context(|| format!("Failed to compare dispos '{}'", name))?;
+            if !diff.is_empty() {
+                diffs.push(diff);
+            }
+        }
+        Ok(diffs)
+    }
+
+    pub fn diff_dispos(&mut self, theirs: &mut BookSystem, dispos_name: &str) -> Result<BookDiff> {
+        let ours = self.open_dispos(dispos_name)?;
+        let theirs = theirs.open_dispos(dispos_name)?;
+        Ok(BookDiff {
+            book: format!("dispos/{}", dispos_name),
+            sheets: ours.diff(&theirs),
+        })
+    }
+
+    /// Copy a row from another project's copy of a book into this one.
+    /// Dispos books are addressed as `dispos/<name>`.
+    pub fn pull_row(
+        &mut self,
+        theirs: &mut BookSystem,
+        book: &str,
+        sheet: &str,
+        key: &str,
+    ) -> Result<bool> {
+        if let Some(dispos_name) = book.strip_prefix("dispos/") {
+            let ours = self.open_dispos(dispos_name)?;
+            let theirs = theirs.open_dispos(dispos_name)?;
+            return ours.pull_row(&theirs, sheet, key);
+        }
+        with_books!(pull_book_row!(self, theirs, book, sheet, key))
+    }
+
+    /// Edit a sheet by name. When `book` is omitted the sheet name must be unique across books.
+    pub fn patch_sheet<R>(
+        &self,
+        book: Option<&str>,
+        sheet: &str,
+        patch: impl FnOnce(&mut dyn PatchNode) -> Result<R>,
+    ) -> Result<R> {
+        let book = match book {
+            Some(book) => book,
+            None => match with_books!(find_patch_sheet!(self, sheet)).as_slice() {
+                [book] => *book,
+                [] => bail!("unknown sheet '{}'", sheet),
+                books => bail!(
+                    "sheet '{}' is ambiguous, prefix it with one of these books: {}",
+                    sheet,
+                    books.join(", ")
+                ),
+            },
+        };
+        with_books!(patch_book!(self, book, sheet, patch))
+    }
+
     pub fn save(&self, backup_root: &Path) -> Result<()> {
         self.achieve.save(&self.file_system, backup_root)?;
         self.ai.save(&self.file_system, backup_root)?;
@@ -346,6 +578,41 @@ impl<T> OpenBook<T> {
     }
 }
 
+impl<T> OpenBook<T>
+where
+    T: DiffableBook,
+{
+    pub fn diff(&self, theirs: &OpenBook<T>) -> Vec<SheetDiff> {
+        self.read(|ours| theirs.read(|theirs| ours.diff(theirs)))
+    }
+
+    pub fn pull_row(&self, theirs: &OpenBook<T>, sheet: &str, key: &str) -> Result<bool> {
+        let changed = theirs.read(|theirs| self.write(|ours| ours.pull_row(theirs, sheet, key)))?;
+        if changed {
+            self.mark_dirty();
+        }
+        Ok(changed)
+    }
+}
+
+impl<T> OpenBook<T>
+where
+    T: PatchableBook,
+{
+    pub fn patch<R>(
+        &self,
+        sheet: &str,
+        patch: impl FnOnce(&mut dyn PatchNode) -> Result<R>,
+    ) -> Result<R> {
+        let result = self.write(|book| match book.patch_sheet_mut(sheet) {
+            Some(sheet) => patch(sheet),
+            None => Err(anyhow!("unknown sheet '{}'", sheet)),
+        })?;
+        self.mark_dirty();
+        Ok(result)
+    }
+}
+
 impl<T> OpenBook<T>
 where
     T: TryFrom<Book, Error = anyhow::Error>,
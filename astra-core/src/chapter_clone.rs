@@ -0,0 +1,212 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use astra_types::Chapter;
+
+use crate::book_system::{chapter_file_name, BookSystem};
+use crate::message_system::MessageSystem;
+use crate::script_system::ScriptSystem;
+use crate::terrain_system::TerrainSystem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResourceKind {
+    Dispos,
+    Terrain,
+    Script,
+    Message,
+}
+
+impl ResourceKind {
+    fn label(self) -> &'static str {
+        match self {
+            ResourceKind::Dispos => "dispos",
+            ResourceKind::Terrain => "terrain",
+            ResourceKind::Script => "script",
+            ResourceKind::Message => "message script",
+        }
+    }
+}
+
+/// One file the new chapter needs its own copy of.
+struct ResourceCopy {
+    kind: ResourceKind,
+    from: String,
+    to: String,
+    /// Encounter dispos only exist for some chapters, so a missing source is skipped.
+    optional: bool,
+}
+
+/// Point a chapter reference at the new chapter.
+/// References with `*` follow the CID on their own. Otherwise the old CID (minus the `CID_` prefix)
+/// is swapped for the new one, keeping lowercase references lowercase.
+/// Returns `None` if the reference doesn't mention the chapter.
+fn rewrite_reference(reference: &str, old_part: &str, new_part: &str) -> Option<String> {
+    if reference.contains('*') {
+        return Some(reference.to_string());
+    }
+    let start = reference
+        .to_ascii_lowercase()
+        .find(&old_part.to_ascii_lowercase())?;
+    let end = start + old_part.len();
+    let replacement = if reference[start..end] == old_part.to_ascii_lowercase() {
+        new_part.to_ascii_lowercase()
+    } else {
+        new_part.to_string()
+    };
+    Some(format!(
+        "{}{}{}",
+        &reference[..start],
+        replacement,
+        &reference[end..]
+    ))
+}
+
+fn resource_exists(
+    books: &BookSystem,
+    terrain: &TerrainSystem,
+    scripts: &ScriptSystem,
+    messages: &MessageSystem,
+    kind: ResourceKind,
+    name: &str,
+) -> Result<bool> {
+    Ok(match kind {
+        ResourceKind::Dispos => books.dispos_exists(name)?,
+        ResourceKind::Terrain => terrain.exists(name)?,
+        ResourceKind::Script => scripts.exists(name),
+        ResourceKind::Message => messages.script_exists(name)?,
+    })
+}
+
+/// Build a copy of `source` under `new_cid` along with copies of its dispos, terrain,
+/// scripts and message script. Returns the new chapter row and a note for every reference
+/// that was copied or left pointing at the original.
+///
+/// Nothing is copied unless every destination is free.
+pub(crate) fn clone_chapter(
+    books: &mut BookSystem,
+    terrain: &mut TerrainSystem,
+    scripts: &ScriptSystem,
+    messages: &mut MessageSystem,
+    message_dir: &Path,
+    source: &Chapter,
+    new_cid: &str,
+) -> Result<(Chapter, Vec<String>)> {
+    let new_part = match new_cid.strip_prefix("CID_") {
+        Some(part) if !part.is_empty() => part,
+        _ => bail!("chapter IDs must start with CID_"),
+    };
+    if books
+        .chapter
+        .read(|book| book.chapters.data.contains_key(new_cid))
+    {
+        bail!("chapter '{}' already exists", new_cid);
+    }
+    let old_part = source.cid.trim_start_matches("CID_");
+
+    let mut chapter = source.clone();
+    chapter.cid = new_cid.to_string();
+    let mut notes = vec![];
+    let mut copies: Vec<ResourceCopy> = vec![];
+    for (label, reference, kind) in [
+        ("Dispos", &mut chapter.dispos, ResourceKind::Dispos),
+        ("Terrain", &mut chapter.terrain, ResourceKind::Terrain),
+        ("Message", &mut chapter.mess, ResourceKind::Message),
+        ("Event", &mut chapter.event, ResourceKind::Script),
+        (
+            "BMap Script",
+            &mut chapter.script_bmap,
+            ResourceKind::Script,
+        ),
+        (
+            "Encount Script",
+            &mut chapter.script_encount,
+            ResourceKind::Script,
+        ),
+        (
+            "Kizuna Script",
+            &mut chapter.script_kizuna,
+            ResourceKind::Script,
+        ),
+    ] {
+        if reference.is_empty() {
+            continue;
+        }
+        let Some(rewritten) = rewrite_reference(reference, old_part, new_part) else {
+            notes.push(format!(
+                "{} '{}' doesn't mention {}, so it is shared with the original",
+                label, reference, old_part
+            ));
+            continue;
+        };
+        let from = chapter_file_name(&source.cid, reference);
+        let to = chapter_file_name(new_cid, &rewritten);
+        *reference = rewritten;
+        let resolve = |name: &str| match kind {
+            ResourceKind::Message => message_dir.join(name).to_string_lossy().replace('\\', "/"),
+            _ => name.to_string(),
+        };
+        if kind == ResourceKind::Dispos {
+            copies.push(ResourceCopy {
+                kind,
+                from: format!("{}e", from),
+                to: format!("{}e", to),
+                optional: true,
+            });
+        }
+        if !copies
+            .iter()
+            .any(|copy| copy.kind == kind && copy.from == resolve(&from))
+        {
+            copies.push(ResourceCopy {
+                kind,
+                from: resolve(&from),
+                to: resolve(&to),
+                optional: false,
+            });
+        }
+    }
+
+    // Check everything up front so a taken name can't leave the chapter half copied.
+    let mut planned = vec![];
+    for copy in copies {
+        if !resource_exists(books, terrain, scripts, messages, copy.kind, &copy.from)? {
+            if !copy.optional {
+                notes.push(format!(
+                    "No {} '{}' to copy, the new chapter will point at '{}'",
+                    copy.kind.label(),
+                    copy.from,
+                    copy.to
+                ));
+            }
+            continue;
+        }
+        if resource_exists(books, terrain, scripts, messages, copy.kind, &copy.to)? {
+            bail!("{} '{}' already exists", copy.kind.label(), copy.to);
+        }
+        planned.push(copy);
+    }
+
+    for copy in planned {
+        match copy.kind {
+            ResourceKind::Dispos => books.copy_dispos(&copy.from, &copy.to),
+            ResourceKind::Terrain => terrain.copy(&copy.from, &copy.to),
+            ResourceKind::Script => scripts.copy(&copy.from, &copy.to),
+            ResourceKind::Message => messages.copy_script(&copy.from, &copy.to),
+        }
+        .with_context(|| {
+            format!(
+                "Failed to copy {} '{}' to '{}'",
+                copy.kind.label(),
+                copy.from,
+                copy.to
+            )
+        })?;
+        notes.push(format!(
+            "Copied {} '{}' to '{}'",
+            copy.kind.label(),
+            copy.from,
+            copy.to
+        ));
+    }
+    Ok((chapter, notes))
+}
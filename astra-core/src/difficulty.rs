@@ -0,0 +1,48 @@
+use astra_types::Spawn;
+
+/// Spawn flag for the chapter's boss.
+pub const SPAWN_BOSS_FLAG: u16 = 0x10;
+/// Spawn flag for units that walk in from the edge of the map.
+pub const SPAWN_EDGE_FLAG: u16 = 0x40;
+/// Spawn flag for slots the player fills with a unit of their choice.
+pub const SPAWN_DEPLOYMENT_SLOT_FLAG: u16 = 0x80;
+/// Spawn flag for units the player can't leave behind.
+pub const SPAWN_MUST_DEPLOY_FLAG: u16 = 0x100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    All,
+    Normal,
+    Hard,
+    Lunatic,
+}
+
+impl Difficulty {
+    /// The difficulties a spawn can be flagged for, from easiest to hardest.
+    pub const FLAGGED: [Difficulty; 3] =
+        [Difficulty::Normal, Difficulty::Hard, Difficulty::Lunatic];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::All => "All Difficulties",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Lunatic => "Lunatic",
+        }
+    }
+
+    /// The spawn flag for this difficulty, or 0 for [Difficulty::All].
+    pub fn flag(&self) -> u16 {
+        match self {
+            Difficulty::All => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Lunatic => 4,
+        }
+    }
+
+    /// Whether the spawn appears on this difficulty.
+    pub fn includes(&self, spawn: &Spawn) -> bool {
+        *self == Difficulty::All || spawn.flag & self.flag() != 0
+    }
+}
@@ -353,6 +353,7 @@ impl NetworkFileSystemLayer {
 #[derive(Debug)]
 pub struct LayeredFileSystem {
     layers: Vec<FileSystemLayer>,
+    vanilla_reads: bool,
 }
 
 impl LayeredFileSystem {
@@ -360,12 +361,30 @@ impl LayeredFileSystem {
         if layers.is_empty() {
             bail!("file system must have at least one layer");
         }
-        Ok(LayeredFileSystem { layers })
+        Ok(LayeredFileSystem {
+            layers,
+            vanilla_reads: false,
+        })
+    }
+
+    /// Ignore the output layer when reading so files come from the untouched ROM.
+    /// Writes still go to the output layer.
+    pub fn with_vanilla_reads(mut self) -> Self {
+        self.vanilla_reads = true;
+        self
+    }
+
+    fn read_layers(&self) -> &[FileSystemLayer] {
+        if self.vanilla_reads {
+            &self.layers[1..]
+        } else {
+            &self.layers
+        }
     }
 
     pub fn read<T: AsRef<Path>>(&self, path_in_rom: T) -> Result<Vec<u8>> {
         let path = path_in_rom.as_ref();
-        for layer in &self.layers {
+        for layer in self.read_layers() {
             if layer.exists(path)? {
                 return layer.read(path);
             }
@@ -384,7 +403,7 @@ impl LayeredFileSystem {
     ) -> Result<HashSet<PathBuf>> {
         let path = path_in_rom.as_ref();
         let mut all_layers = HashSet::new();
-        for layer in &self.layers {
+        for layer in self.read_layers() {
             if layer.exists(path)? {
                 all_layers.extend(layer.list_files(path, glob)?);
             } else {
@@ -418,7 +437,7 @@ impl LayeredFileSystem {
 
     pub fn exists<T: AsRef<Path>>(&self, path_in_rom: T) -> Result<bool> {
         let path = path_in_rom.as_ref();
-        for layer in &self.layers {
+        for layer in self.read_layers() {
             if layer.exists(path)? {
                 return Ok(true);
             }
@@ -557,6 +576,7 @@ pub struct CobaltFileSystemProxy {
     main_file_system: Arc<LocalizedFileSystem>,
     cobalt_file_system: Option<DirectoryFileSystemLayer>,
     path_localizer: PathLocalizer,
+    vanilla_reads: bool,
 }
 
 impl CobaltFileSystemProxy {
@@ -572,9 +592,17 @@ impl CobaltFileSystemProxy {
             },
             path_localizer: main_file_system.path_localizer.clone(),
             main_file_system,
+            vanilla_reads: false,
         })
     }
 
+    /// Skip books and messages in the Cobalt folder when reading.
+    /// Saving still targets the Cobalt folder.
+    pub fn with_vanilla_reads(mut self) -> Self {
+        self.vanilla_reads = true;
+        self
+    }
+
     pub fn list_scripts(&self) -> Result<BTreeSet<String>> {
         let mut scripts = self.main_file_system.list_files(
             r"StreamingAssets\aa\Switch\fe_assets_scripts",
@@ -684,6 +712,30 @@ impl CobaltFileSystemProxy {
         ))
     }
 
+    /// Read a script's source without unpacking it anywhere.
+    /// Looks in the same places as [CobaltFileSystemProxy::read_script], in the same order.
+    pub fn read_script_source(&self, script_file_name: &str) -> Result<Vec<u8>> {
+        let path_in_cobalt = Path::new("scripts")
+            .join(script_file_name)
+            .with_extension("txt");
+        let base_path =
+            Path::new(r"StreamingAssets\aa\Switch\fe_assets_scripts").join(script_file_name);
+        let script_path = base_path.with_extension("lua");
+
+        if let Some(cobalt) = &self.cobalt_file_system {
+            if cobalt.exists(&path_in_cobalt)? {
+                return cobalt.read(&path_in_cobalt);
+            }
+        }
+        if self.main_file_system.exists(&script_path, false)? {
+            return self.main_file_system.read(&script_path, false);
+        }
+        let raw_bundle = self
+            .main_file_system
+            .read(base_path.with_extension("txt.bundle"), false)?;
+        TextBundle::from_slice(&raw_bundle)?.take_raw()
+    }
+
     pub fn save_script<P: AsRef<Path>, P2: AsRef<Path>>(
         &self,
         absolute_script_path: P,
@@ -707,17 +759,110 @@ impl CobaltFileSystemProxy {
         Ok(())
     }
 
+    /// Write a copy of a script under a new name, next to wherever the original is kept.
+    /// Unsaved edits to an open script are included since they live in its unpacked file.
+    pub fn copy_script(&self, from: &str, to: &str) -> Result<()> {
+        let script_contents = self.read_script_source(from)?;
+        if let Some(cobalt) = &self.cobalt_file_system {
+            let path_in_cobalt = Path::new("scripts").join(to).with_extension("txt");
+            info!(
+                "Copying script to Cobalt folder at {}",
+                path_in_cobalt.display()
+            );
+            cobalt.write(&path_in_cobalt, &script_contents)?;
+        } else {
+            // The source bundle serves as the template for the new one.
+            let scripts_path = Path::new(r"StreamingAssets\aa\Switch\fe_assets_scripts");
+            let raw_bundle = self
+                .main_file_system
+                .read(scripts_path.join(from).with_extension("txt.bundle"), false)?;
+            let mut bundle = TextBundle::from_slice(&raw_bundle)?;
+            let base_path = scripts_path.join(to);
+            let bundle_path = base_path.with_extension("txt.bundle");
+            info!("Copying script to bundle at {}", bundle_path.display());
+            self.main_file_system.write(
+                base_path.with_extension("lua"),
+                &script_contents,
+                false,
+            )?;
+            bundle.replace_raw(script_contents)?;
+            self.main_file_system
+                .write(bundle_path, &bundle.serialize()?, false)?;
+        }
+        Ok(())
+    }
+
     pub fn read_book<PathType, DataType>(
         &self,
         path: PathType,
         xml_name: &str,
     ) -> Result<OpenBook<DataType>>
+    where
+        PathType: AsRef<Path>,
+        DataType: TryFrom<Book, Error = anyhow::Error>,
+    {
+        self.read_book_data(path, xml_name)
+            .map(|(data, persist_format)| OpenBook::new(data, persist_format))
+    }
+
+    /// Read a book and point its persist format at `to` instead of where it was read from.
+    /// The copy is marked as modified so the next save writes it out.
+    pub fn copy_book<PathType, DataType>(
+        &self,
+        from: PathType,
+        from_xml_name: &str,
+        to: PathType,
+        to_xml_name: &str,
+    ) -> Result<OpenBook<DataType>>
+    where
+        PathType: AsRef<Path>,
+        DataType: TryFrom<Book, Error = anyhow::Error>,
+    {
+        let (data, persist_format) = self.read_book_data(from, from_xml_name)?;
+        let persist_format = match persist_format {
+            BundlePersistFormat::Cobalt { .. } => BundlePersistFormat::Cobalt {
+                path: Self::format_cobalt_xml_path(&to, Some(to_xml_name)),
+            },
+            BundlePersistFormat::Vanilla { bundle, .. } => BundlePersistFormat::Vanilla {
+                bundle_path: Path::new(r"StreamingAssets/aa/Switch/fe_assets_gamedata/")
+                    .join(&to)
+                    .with_extension("xml.bundle"),
+                bundle,
+            },
+        };
+        let book = OpenBook::new(data, persist_format);
+        book.mark_dirty();
+        Ok(book)
+    }
+
+    /// Whether a book exists in the Cobalt folder or as a bundle, without reading it.
+    pub fn book_exists<PathType: AsRef<Path>>(
+        &self,
+        path: PathType,
+        xml_name: &str,
+    ) -> Result<bool> {
+        if let (Some(cobalt), false) = (&self.cobalt_file_system, self.vanilla_reads) {
+            if cobalt.exists(Self::format_cobalt_xml_path(&path, Some(xml_name)))? {
+                return Ok(true);
+            }
+        }
+        let path_in_rom = Path::new(r"StreamingAssets/aa/Switch/fe_assets_gamedata/")
+            .join(&path)
+            .with_extension("xml.bundle");
+        self.main_file_system.exists(path_in_rom, false)
+    }
+
+    fn read_book_data<PathType, DataType>(
+        &self,
+        path: PathType,
+        xml_name: &str,
+    ) -> Result<(DataType, BundlePersistFormat)>
     where
         PathType: AsRef<Path>,
         DataType: TryFrom<Book, Error = anyhow::Error>,
     {
         // Try to read a Cobalt XML.
-        if let Some(cobalt) = &self.cobalt_file_system {
+        if let (Some(cobalt), false) = (&self.cobalt_file_system, self.vanilla_reads) {
             let path_in_cobalt = Self::format_cobalt_xml_path(&path, Some(xml_name));
             if cobalt.exists(&path_in_cobalt)? {
                 info!(
@@ -729,7 +874,7 @@ impl CobaltFileSystemProxy {
                     .and_then(|raw| Book::from_string(&String::from_utf8_lossy(&raw)))
                     .and_then(|book| DataType::try_from(book))
                     .map(|data| {
-                        OpenBook::new(
+                        (
                             data,
                             BundlePersistFormat::Cobalt {
                                 path: path_in_cobalt,
@@ -749,7 +894,7 @@ impl CobaltFileSystemProxy {
         let mut bundle = TextBundle::from_slice(&raw)?;
         let book = Book::from_string(&bundle.take_string()?)?;
         let data = DataType::try_from(book)?;
-        Ok(OpenBook::new(
+        Ok((
             data,
             if self.cobalt_file_system.is_some() {
                 BundlePersistFormat::Cobalt {
@@ -843,7 +988,7 @@ impl CobaltFileSystemProxy {
         path: P,
     ) -> Result<Option<IndexMap<String, String>>> {
         let path: &Path = path.as_ref();
-        if let Some(fs) = &self.cobalt_file_system {
+        if let (Some(fs), false) = (&self.cobalt_file_system, self.vanilla_reads) {
             let cobalt_path = self.to_cobalt_msbt_path(path)?;
             if fs.exists(cobalt_path.as_path())? {
                 info!("Loading Cobalt MSBT from path {}", cobalt_path.display());
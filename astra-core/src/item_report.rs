@@ -0,0 +1,164 @@
+use anyhow::Result;
+use astra_types::Spawn;
+
+use crate::book_system::BookSystem;
+use crate::{spawn_items, Difficulty};
+
+/// Where an [ItemSource] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemSourceKind {
+    Drop,
+    ChapterReward,
+    RewardTable,
+    Shop,
+}
+
+impl ItemSourceKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ItemSourceKind::Drop => "Drop",
+            ItemSourceKind::ChapterReward => "Chapter Reward",
+            ItemSourceKind::RewardTable => "Reward Table",
+            ItemSourceKind::Shop => "Shop",
+        }
+    }
+}
+
+/// One way for the player to get an item, as listed by [item_report](crate::Astra::item_report).
+#[derive(Debug, Clone)]
+pub struct ItemSource {
+    /// IID, or AID for accessory shop listings.
+    pub iid: String,
+    pub kind: ItemSourceKind,
+    /// CID of the chapter the item comes from or unlocks in. Empty if it isn't tied to one.
+    pub chapter: String,
+    /// Empty if the item is available on every difficulty.
+    pub difficulty: String,
+    pub source: String,
+    pub detail: String,
+}
+
+fn spawn_drops(spawn: &Spawn) -> impl Iterator<Item = &str> {
+    spawn_items(spawn)
+        .into_iter()
+        .filter(|(iid, drop)| !iid.is_empty() && *drop != 0)
+        .map(|(iid, _)| iid.as_str())
+}
+
+pub(crate) fn item_report(books: &mut BookSystem) -> Result<Vec<ItemSource>> {
+    let mut sources = vec![];
+    for dispos in books.open_chapter_dispos()? {
+        dispos.book.read(|book| {
+            for (group, spawns) in &book.spawns.data {
+                for (index, spawn) in spawns.iter().enumerate() {
+                    for iid in spawn_drops(spawn) {
+                        for difficulty in Difficulty::FLAGGED {
+                            if !difficulty.includes(spawn) {
+                                continue;
+                            }
+                            sources.push(ItemSource {
+                                iid: iid.to_string(),
+                                kind: ItemSourceKind::Drop,
+                                chapter: dispos.cid.clone(),
+                                difficulty: difficulty.label().to_string(),
+                                source: format!("{}/{}[{}]", dispos.name, group, index),
+                                detail: spawn.pid.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    let (chapter_rewards, cids): (Vec<(String, String)>, Vec<String>) =
+        books.chapter.read(|book| {
+            (
+                book.chapters
+                    .data
+                    .values()
+                    .filter(|chapter| !chapter.reward.is_empty())
+                    .map(|chapter| (chapter.cid.clone(), chapter.reward.clone()))
+                    .collect(),
+                book.chapters.data.keys().cloned().collect(),
+            )
+        });
+    books.item.read(|book| {
+        let reward_tables = &book.reward_data.data;
+        for (cid, reward) in chapter_rewards {
+            match reward_tables.get(&reward) {
+                Some(entries) => sources.extend(entries.iter().map(|entry| ItemSource {
+                    iid: entry.iid.clone(),
+                    kind: ItemSourceKind::ChapterReward,
+                    chapter: cid.clone(),
+                    difficulty: String::new(),
+                    source: reward.clone(),
+                    detail: format!("Ratio {}", entry.ratio),
+                })),
+                // Some chapters name the item directly instead of a reward table.
+                None => sources.push(ItemSource {
+                    iid: reward.clone(),
+                    kind: ItemSourceKind::ChapterReward,
+                    chapter: cid,
+                    difficulty: String::new(),
+                    source: String::new(),
+                    detail: String::new(),
+                }),
+            }
+        }
+        for (key, entries) in reward_tables {
+            sources.extend(entries.iter().map(|entry| ItemSource {
+                iid: entry.iid.clone(),
+                kind: ItemSourceKind::RewardTable,
+                chapter: String::new(),
+                difficulty: String::new(),
+                source: key.clone(),
+                detail: format!(
+                    "Ratio {}, Factor {}, {}-{}",
+                    entry.ratio, entry.factor, entry.min, entry.max
+                ),
+            }));
+        }
+    });
+
+    // Shop groups are keyed by the chapter that unlocks them, with or without the CID_ prefix.
+    let unlock_chapter = |key: &str| {
+        [key.to_string(), format!("CID_{}", key)]
+            .into_iter()
+            .find(|cid| cids.contains(cid))
+            .unwrap_or_default()
+    };
+    books.shop.read(|book| {
+        for (label, shop) in [
+            ("Armory", &book.armory_shop_inventory.data),
+            ("Item Shop", &book.item_shop_inventory.data),
+            ("Flea Market", &book.flea_market_shop_inventory.data),
+        ] {
+            for (key, inventory) in shop {
+                sources.extend(inventory.iter().map(|entry| ItemSource {
+                    iid: entry.iid.clone(),
+                    kind: ItemSourceKind::Shop,
+                    chapter: unlock_chapter(key),
+                    difficulty: String::new(),
+                    source: format!("{} ({})", label, key),
+                    detail: if entry.stock > 0 {
+                        format!("Stock {}", entry.stock)
+                    } else {
+                        String::new()
+                    },
+                }));
+            }
+        }
+        for (key, inventory) in &book.accessory_shop_inventory.data {
+            sources.extend(inventory.iter().map(|entry| ItemSource {
+                iid: entry.aid.clone(),
+                kind: ItemSourceKind::Shop,
+                chapter: unlock_chapter(key),
+                difficulty: String::new(),
+                source: format!("Accessory Shop ({})", key),
+                detail: String::new(),
+            }));
+        }
+    });
+    Ok(sources)
+}
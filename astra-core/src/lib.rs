@@ -1,29 +1,41 @@
 mod atlas_system;
 mod book_system;
+mod chapter_clone;
+mod difficulty;
 mod file_system;
+mod item_report;
+mod map_render;
 mod message_script;
 mod message_system;
+mod randomizer;
+mod recipe;
+mod reference_project;
 mod script_system;
+mod spawn_items;
+mod spawn_tuning;
 mod terrain_system;
+mod terrain_tools;
+mod weapons;
 
 use std::collections::{BTreeSet, HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 pub use anyhow as error;
 use astra_types::{
     AchievementBook, AiBook, AmiiboBook, AnimSetBook, AnimalBook, ArenaBook, AssetTableBook,
-    CalculatorBook, ChapterBook, ChartBook, CookBook, DisposBook, DragonRidePresetParamDataBook,
-    DragonRidePrizeListBook, DragonRideTargetPatternBook, EffectBook, EncountBook, EndRollBook,
-    FishingFishBook, FriendListBook, GodBook, GroundAttributeBook, HubAreaBook, HubDemoBook,
-    HubDisposBook, HubFortuneTellingBook, HubInvestmentBook, HubMapIconBook, HubMyRoomBook,
-    HubResourceBook, HubTalkBook, ItemBook, JobBook, JukeboxBook, KeyHelpDataBook, KillBonusBook,
-    LaterTalkBook, MapEditorBook, MapHistoryBook, MascotBook, MovieBook, MuscleExerciseDataBook,
-    MusicBook, ParamsBook, PersonBook, PhotographSpotBook, ProfileCardBook, RangeBook, RelayBook,
-    RelianceBook, RingBook, RingCleaningVoiceBook, ShopBook, SkillBook, SoundEventBook,
-    TerrainBook, TitleBook, TutorialBook, VibrationBook,
+    CalculatorBook, Chapter, ChapterBook, ChartBook, CookBook, DisposBook,
+    DragonRidePresetParamDataBook, DragonRidePrizeListBook, DragonRideTargetPatternBook,
+    EffectBook, EncountBook, EndRollBook, FishingFishBook, FriendListBook, GodBook,
+    GroundAttributeBook, HubAreaBook, HubDemoBook, HubDisposBook, HubFortuneTellingBook,
+    HubInvestmentBook, HubMapIconBook, HubMyRoomBook, HubResourceBook, HubTalkBook, ItemBook,
+    JobBook, JukeboxBook, KeyHelpDataBook, KillBonusBook, LaterTalkBook, MapEditorBook,
+    MapHistoryBook, MascotBook, MovieBook, MuscleExerciseDataBook, MusicBook, ParamsBook,
+    PersonBook, PhotographSpotBook, ProfileCardBook, RangeBook, RelayBook, RelianceBook, RingBook,
+    RingCleaningVoiceBook, ShopBook, SkillBook, SoundEventBook, TerrainBook, TitleBook,
+    TutorialBook, VibrationBook,
 };
 use error::Context;
 pub use image;
@@ -31,15 +43,31 @@ pub use parking_lot;
 
 use atlas_system::AtlasSystem;
 use book_system::BookSystem;
-pub use book_system::OpenBook;
+pub use book_system::{chapter_file_name, BookDiff, OpenBook};
+pub use difficulty::{
+    Difficulty, SPAWN_BOSS_FLAG, SPAWN_DEPLOYMENT_SLOT_FLAG, SPAWN_EDGE_FLAG,
+    SPAWN_MUST_DEPLOY_FLAG,
+};
 pub use file_system::*;
-use image::DynamicImage;
-use message_system::MessageSystem;
+use image::{DynamicImage, RgbaImage};
+pub use item_report::{ItemSource, ItemSourceKind};
+pub use map_render::{MapCoordinates, MapRenderOptions};
 pub use message_script::OpenMessageScript;
-pub use message_system::OpenMessageArchive;
+use message_system::MessageSystem;
+pub use message_system::{MessageDiff, OpenMessageArchive};
+pub use randomizer::{randomize, RandomizerOptions, RandomizerReport};
+pub use recipe::{apply_recipes, Recipe, RecipeOperation, RecipeTarget, RecipeValue};
+pub use reference_project::ReferenceProject;
 use script_system::ScriptSystem;
+pub use spawn_items::{spawn_items, spawn_items_mut};
+pub use spawn_tuning::{Expression, Operator, SpawnChange, SpawnFilter, SpawnRule};
 pub use terrain_system::OpenTerrain;
 use terrain_system::TerrainSystem;
+pub use terrain_tools::{
+    move_spawns, resize_terrain, shift_terrain, terrain_from_csv, terrain_from_png, terrain_to_csv,
+    terrain_to_png, TERRAIN_STRIDE,
+};
+pub use weapons::{is_attack_kind, weapon_rank, SPECIAL_KIND, STAFF_KIND, WEAPON_KINDS};
 
 #[derive(Debug)]
 pub enum RomSource {
@@ -66,22 +94,38 @@ pub struct Astra {
     terrain_system: TerrainSystem,
 }
 
-impl Astra {
-    pub fn load(project: AstraProject) -> Result<Self> {
+impl AstraProject {
+    /// With `vanilla_reads` set, files come from the ROM only but writes still go to the project.
+    pub(crate) fn open_file_systems(
+        &self,
+        vanilla_reads: bool,
+    ) -> Result<(Arc<LocalizedFileSystem>, Arc<CobaltFileSystemProxy>)> {
+        let mut layered_file_system = LayeredFileSystem::new(vec![
+            FileSystemLayer::directory(self.output_dir.clone())?,
+            match &self.rom_source {
+                RomSource::Directory(directory) => FileSystemLayer::directory(directory)?,
+                RomSource::Network(ip) => FileSystemLayer::network(ip)?,
+            },
+        ])?;
+        if vanilla_reads {
+            layered_file_system = layered_file_system.with_vanilla_reads();
+        }
         let file_system = Arc::new(LocalizedFileSystem::new(
-            LayeredFileSystem::new(vec![
-                FileSystemLayer::directory(project.output_dir.clone())?,
-                match &project.rom_source {
-                    RomSource::Directory(directory) => FileSystemLayer::directory(directory)?,
-                    RomSource::Network(ip) => FileSystemLayer::network(ip)?,
-                },
-            ])?,
-            project.localization.clone(),
+            layered_file_system,
+            self.localization.clone(),
         ));
-        let cobalt_proxy = Arc::new(CobaltFileSystemProxy::new(
-            file_system.clone(),
-            project.cobalt_dir.clone(),
-        )?);
+        let mut cobalt_proxy =
+            CobaltFileSystemProxy::new(file_system.clone(), self.cobalt_dir.clone())?;
+        if vanilla_reads {
+            cobalt_proxy = cobalt_proxy.with_vanilla_reads();
+        }
+        Ok((file_system, Arc::new(cobalt_proxy)))
+    }
+}
+
+impl Astra {
+    pub fn load(project: AstraProject) -> Result<Self> {
+        let (file_system, cobalt_proxy) = project.open_file_systems(false)?;
         Ok(Self {
             backup_root: project.backup_dir.clone(),
             atlas_system: AtlasSystem::load(&file_system, &cobalt_proxy)
@@ -121,6 +165,10 @@ impl Astra {
             .open(script_name, editor_program, editor_args)
     }
 
+    pub fn read_script(&self, script_name: &str) -> Result<String> {
+        self.script_system.read(script_name)
+    }
+
     pub fn forget_script(&mut self, script_name: &str) {
         self.script_system.forget(script_name)
     }
@@ -156,14 +204,140 @@ impl Astra {
         self.atlas_system.take_sprites(atlas_id)
     }
 
+    /// Unit sprites with their palettes applied. They stay cached since map rendering needs them.
+    pub fn unit_sprites(&mut self) -> &HashMap<String, DynamicImage> {
+        self.atlas_system.unit_sprites()
+    }
+
     pub fn get_chapter_terrain(&mut self, terrain_name: &str) -> Option<OpenTerrain> {
         self.terrain_system.open(terrain_name).ok() // TODO: Log the error
     }
 
+    /// Make a blank terrain map that chapters can point at through `Chapter.terrain`.
+    pub fn create_chapter_terrain(
+        &mut self,
+        terrain_name: &str,
+        width: i32,
+        height: i32,
+        fill_tid: &str,
+    ) -> Result<OpenTerrain> {
+        let known_tile = self
+            .book_system
+            .terrain
+            .read(|book| book.terrain_data.data.contains_key(fill_tid));
+        if !known_tile {
+            bail!("unknown TID '{}'", fill_tid);
+        }
+        self.terrain_system
+            .create(terrain_name, width, height, fill_tid)
+            .with_context(|| format!("Failed to create terrain {}", terrain_name))
+    }
+
     pub fn get_dispos(&mut self, dispos_name: &str) -> Option<OpenBook<DisposBook>> {
         self.book_system.open_dispos(dispos_name).ok() // TODO: Log the error
     }
 
+    /// Draw a chapter's terrain and spawns along with a coordinate ruler and a legend.
+    pub fn render_chapter_map(
+        &mut self,
+        cid: &str,
+        options: &MapRenderOptions,
+    ) -> Result<RgbaImage> {
+        map_render::render_chapter_map(
+            &mut self.book_system,
+            &mut self.terrain_system,
+            &mut self.atlas_system,
+            cid,
+            options,
+        )
+        .with_context(|| format!("Failed to render map for {}", cid))
+    }
+
+    pub fn export_chapter_map(
+        &mut self,
+        cid: &str,
+        options: &MapRenderOptions,
+        path: &Path,
+    ) -> Result<()> {
+        self.render_chapter_map(cid, options)?
+            .save(path)
+            .with_context(|| format!("Failed to write map to {}", path.display()))
+    }
+
+    /// Copy a chapter along with its dispos, terrain, scripts and message script.
+    /// Returns the new chapter row for the caller to add and notes about what was copied.
+    /// Copies are kept in memory until the project is saved.
+    pub fn clone_chapter(
+        &mut self,
+        source: &Chapter,
+        new_cid: &str,
+    ) -> Result<(Chapter, Vec<String>)> {
+        chapter_clone::clone_chapter(
+            &mut self.book_system,
+            &mut self.terrain_system,
+            &self.script_system,
+            &mut self.message_system,
+            &self.project.localization.localization_dir(),
+            source,
+            new_cid,
+        )
+        .with_context(|| format!("Failed to duplicate {}", source.cid))
+    }
+
+    /// Apply `rules` to every matching spawn in every chapter.
+    /// With `apply` unset nothing is changed and the result is a preview.
+    pub fn tune_spawns(
+        &mut self,
+        filter: &SpawnFilter,
+        rules: &[SpawnRule],
+        apply: bool,
+    ) -> Result<Vec<SpawnChange>> {
+        spawn_tuning::tune_spawns(&mut self.book_system, filter, rules, apply)
+    }
+
+    /// List every way to obtain an item: spawn drops, chapter rewards, reward tables and shops.
+    pub fn item_report(&mut self) -> Result<Vec<ItemSource>> {
+        item_report::item_report(&mut self.book_system)
+    }
+
+    pub fn diff_books(&self, reference: &ReferenceProject) -> Vec<BookDiff> {
+        self.book_system.diff(&reference.book_system)
+    }
+
+    pub fn diff_chapter_dispos(
+        &mut self,
+        reference: &mut ReferenceProject,
+    ) -> Result<Vec<BookDiff>> {
+        self.book_system
+            .diff_chapter_dispos(&mut reference.book_system)
+    }
+
+    pub fn diff_messages(&self, reference: &ReferenceProject) -> Vec<MessageDiff> {
+        self.message_system.diff(&reference.message_system)
+    }
+
+    pub fn pull_row(
+        &mut self,
+        reference: &mut ReferenceProject,
+        book: &str,
+        sheet: &str,
+        key: &str,
+    ) -> Result<bool> {
+        self.book_system
+            .pull_row(&mut reference.book_system, book, sheet, key)
+            .with_context(|| format!("Failed to pull row '{}' from {}/{}", key, book, sheet))
+    }
+
+    pub fn pull_message(
+        &self,
+        reference: &ReferenceProject,
+        archive_id: &str,
+        key: &str,
+    ) -> Result<bool> {
+        self.message_system
+            .pull_message(&reference.message_system, archive_id, key)
+    }
+
     pub fn get_achieve_book(&self) -> OpenBook<AchievementBook> {
         self.book_system.achieve.clone()
     }
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use anyhow::{anyhow, Context, Result};
+use astra_types::Spawn;
+use image::imageops::{self, FilterType};
+use image::{Pixel, Rgba, RgbaImage};
+
+use crate::atlas_system::AtlasSystem;
+use crate::book_system::{chapter_file_name, BookSystem};
+use crate::terrain_system::TerrainSystem;
+use crate::TERRAIN_STRIDE;
+
+const BACKGROUND_COLOR: Rgba<u8> = Rgba([32, 32, 32, 255]);
+const GRID_COLOR: Rgba<u8> = Rgba([0, 0, 0, 64]);
+const TEXT_COLOR: Rgba<u8> = Rgba([230, 230, 230, 255]);
+const MISSING_TILE_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const FORCE_COLORS: [(&str, Rgba<u8>); 3] = [
+    ("Player", Rgba([64, 128, 255, 255])),
+    ("Enemy", Rgba([255, 64, 64, 255])),
+    ("Other", Rgba([64, 200, 64, 255])),
+];
+
+/// Which of a spawn's positions to draw it at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapCoordinates {
+    #[default]
+    Dispos,
+    Appear,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapRenderOptions {
+    pub coordinates: MapCoordinates,
+    /// Draw spawns from the chapter's encounter dispos instead of the main one.
+    pub encounter: bool,
+    /// Only draw spawns with at least one of these difficulty flags. Zero draws every spawn.
+    pub difficulties: u16,
+    /// Width and height of a tile in pixels.
+    pub tile_size: u32,
+    /// Multiplier for terrain colors, same as the editor's tile brightness.
+    pub brightness: f32,
+    /// Colors to use instead of a terrain's own, keyed by TID. Not affected by brightness.
+    pub tile_color_overrides: HashMap<String, [u8; 3]>,
+    /// Names to show in the legend, keyed by TID. Terrain without a name is listed by TID.
+    pub terrain_names: HashMap<String, String>,
+    /// TrueType or OpenType data for the rulers and legend. Rendering fails without one.
+    pub font: &'static [u8],
+}
+
+impl Default for MapRenderOptions {
+    fn default() -> Self {
+        Self {
+            coordinates: MapCoordinates::default(),
+            encounter: false,
+            difficulties: 0,
+            tile_size: 48,
+            brightness: 0.7,
+            tile_color_overrides: HashMap::new(),
+            terrain_names: HashMap::new(),
+            font: &[],
+        }
+    }
+}
+
+struct TextRenderer<'a> {
+    font: FontRef<'a>,
+    scale: PxScale,
+}
+
+impl<'a> TextRenderer<'a> {
+    fn new(font: &'a [u8], size: f32) -> Result<Self> {
+        Ok(Self {
+            font: FontRef::try_from_slice(font).context("failed to load the map font")?,
+            scale: PxScale::from(size),
+        })
+    }
+
+    fn width(&self, text: &str) -> u32 {
+        let font = self.font.as_scaled(self.scale);
+        text.chars()
+            .map(|c| font.h_advance(font.glyph_id(c)))
+            .sum::<f32>()
+            .ceil() as u32
+    }
+
+    fn height(&self) -> u32 {
+        self.font.as_scaled(self.scale).height().ceil() as u32
+    }
+
+    fn draw(&self, image: &mut RgbaImage, x: i64, y: i64, text: &str) {
+        let font = self.font.as_scaled(self.scale);
+        let mut caret = x as f32;
+        for c in text.chars() {
+            let id = font.glyph_id(c);
+            let glyph =
+                id.with_scale_and_position(self.scale, point(caret, y as f32 + font.ascent()));
+            caret += font.h_advance(id);
+            let Some(outline) = self.font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                let (px, py) = (
+                    bounds.min.x as i64 + gx as i64,
+                    bounds.min.y as i64 + gy as i64,
+                );
+                if (0..image.width() as i64).contains(&px)
+                    && (0..image.height() as i64).contains(&py)
+                {
+                    let mut color = TEXT_COLOR;
+                    color.0[3] = (coverage.clamp(0., 1.) * 255.) as u8;
+                    image.get_pixel_mut(px as u32, py as u32).blend(&color);
+                }
+            });
+        }
+    }
+
+    fn draw_centered(&self, image: &mut RgbaImage, center_x: i64, center_y: i64, text: &str) {
+        let x = center_x - self.width(text) as i64 / 2;
+        let y = center_y - self.height() as i64 / 2;
+        self.draw(image, x, y, text);
+    }
+}
+
+fn fill_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.get_pixel_mut(px, py).blend(&color);
+        }
+    }
+}
+
+fn outline_rect(image: &mut RgbaImage, x: u32, y: u32, size: u32, thickness: u32, color: Rgba<u8>) {
+    fill_rect(image, x, y, size, thickness, color);
+    fill_rect(image, x, y + size - thickness, size, thickness, color);
+    fill_rect(image, x, y, thickness, size, color);
+    fill_rect(image, x + size - thickness, y, thickness, size, color);
+}
+
+/// The `{unit}_{job}_{weapon}` key of a spawn's map sprite, same as the editor's dispos grid.
+fn unit_sprite_key(books: &BookSystem, spawn: &Spawn) -> Option<String> {
+    let person = books
+        .person
+        .read(|book| book.persons.data.get(&spawn.pid).cloned())?;
+    let jid = if spawn.jid.is_empty() {
+        &person.jid
+    } else {
+        &spawn.jid
+    };
+    books.job.read(|book| {
+        book.jobs.data.get(jid).map(|job| {
+            let job_icon = if person.gender == 2 {
+                &job.unit_icon_id_f
+            } else {
+                &job.unit_icon_id_m
+            };
+            format!(
+                "{}_{}_{}",
+                person.unit_icon_id, job_icon, job.unit_icon_weapon_id
+            )
+        })
+    })
+}
+
+pub(crate) fn render_chapter_map(
+    books: &mut BookSystem,
+    terrain_system: &mut TerrainSystem,
+    atlases: &mut AtlasSystem,
+    cid: &str,
+    options: &MapRenderOptions,
+) -> Result<RgbaImage> {
+    let chapter = books
+        .chapter
+        .read(|book| book.chapters.data.get(cid).cloned())
+        .ok_or_else(|| anyhow!("chapter '{}' does not exist", cid))?;
+    let terrain_name = chapter_file_name(&chapter.cid, &chapter.terrain);
+    let terrain = terrain_system
+        .open(&terrain_name)
+        .with_context(|| format!("failed to open terrain '{}'", terrain_name))?;
+    let spawns: Vec<Spawn> = if chapter.dispos.is_empty() {
+        vec![]
+    } else {
+        let mut dispos_name = chapter_file_name(&chapter.cid, &chapter.dispos);
+        if options.encounter {
+            dispos_name.push('e');
+        }
+        books
+            .open_dispos(&dispos_name)
+            .with_context(|| format!("failed to open dispos '{}'", dispos_name))?
+            .read(|book| {
+                book.spawns
+                    .data
+                    .values()
+                    .flatten()
+                    .filter(|spawn| {
+                        options.difficulties == 0 || spawn.flag & options.difficulties != 0
+                    })
+                    .cloned()
+                    .collect()
+            })
+    };
+
+    // Resolve every tile to its TID and color up front.
+    let (width, height, tiles) = terrain.read(|terrain| {
+        books.terrain.read(|book| {
+            let (width, height) = (terrain.width.max(0) as u32, terrain.height.max(0) as u32);
+            let tiles: Vec<Option<(String, Rgba<u8>)>> = (0..height as usize)
+                .flat_map(|row| (0..width as usize).map(move |col| row * TERRAIN_STRIDE + col))
+                .map(|index| {
+                    let tid = terrain.terrains.get(index)?;
+                    let tile = book.terrain_data.data.get(tid.as_str())?;
+                    let color = match options.tile_color_overrides.get(&tile.tid) {
+                        Some([r, g, b]) => Rgba([*r, *g, *b, 255]),
+                        None => Rgba([
+                            (tile.color_r as f32 * options.brightness) as u8,
+                            (tile.color_g as f32 * options.brightness) as u8,
+                            (tile.color_b as f32 * options.brightness) as u8,
+                            255,
+                        ]),
+                    };
+                    Some((tile.tid.clone(), color))
+                })
+                .collect();
+            (width, height, tiles)
+        })
+    });
+
+    let tile_size = options.tile_size.max(8);
+    let ruler = (tile_size / 2).max(20);
+    let padding = ruler / 2;
+    let text = TextRenderer::new(options.font, ruler as f32 * 0.6)?;
+    let line_height = text.height().max(12) + padding / 2;
+
+    // Legend entries in order of first appearance, starting from the top of the map.
+    let mut legend: Vec<(String, Rgba<u8>)> = vec![];
+    for (tid, color) in (0..height as usize)
+        .rev()
+        .flat_map(|row| tiles[row * width as usize..(row + 1) * width as usize].iter())
+        .flatten()
+    {
+        let name = options.terrain_names.get(tid).unwrap_or(tid);
+        if !legend.iter().any(|(existing, _)| existing == name) {
+            legend.push((name.clone(), *color));
+        }
+    }
+    let legend_width = legend
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .chain(FORCE_COLORS.iter().map(|(name, _)| *name))
+        .chain(["Terrain", "Units"])
+        .map(|name| text.width(name))
+        .max()
+        .unwrap_or_default()
+        + line_height
+        + padding * 3;
+    let legend_height = (legend.len() + FORCE_COLORS.len() + 2) as u32 * line_height + padding * 2;
+
+    let map_x = ruler;
+    let map_y = ruler;
+    let image_width = map_x + width * tile_size + legend_width;
+    let image_height = (map_y + height * tile_size + padding).max(legend_height);
+    let mut image = RgbaImage::from_pixel(image_width, image_height, BACKGROUND_COLOR);
+
+    // Row zero is the bottom of the map, same as the editor.
+    let tile_origin = |col: u32, row: u32| {
+        (
+            map_x + col * tile_size,
+            map_y + (height - 1 - row) * tile_size,
+        )
+    };
+    for row in 0..height {
+        for col in 0..width {
+            let color = tiles[(row * width + col) as usize]
+                .as_ref()
+                .map(|(_, color)| *color)
+                .unwrap_or(MISSING_TILE_COLOR);
+            let (x, y) = tile_origin(col, row);
+            fill_rect(&mut image, x, y, tile_size, tile_size, color);
+            outline_rect(&mut image, x, y, tile_size, 1, GRID_COLOR);
+        }
+    }
+
+    // Coordinate ruler along the top and left edges.
+    for col in 0..width {
+        let (x, _) = tile_origin(col, 0);
+        let center = (x + tile_size / 2) as i64;
+        text.draw_centered(&mut image, center, (ruler / 2) as i64, &col.to_string());
+    }
+    for row in 0..height {
+        let (_, y) = tile_origin(0, row);
+        let center = (y + tile_size / 2) as i64;
+        text.draw_centered(&mut image, (ruler / 2) as i64, center, &row.to_string());
+    }
+
+    let sprites = atlases.unit_sprites();
+    let mut scaled_sprites: HashMap<String, RgbaImage> = HashMap::new();
+    for spawn in &spawns {
+        let (col, row) = match options.coordinates {
+            MapCoordinates::Dispos => (spawn.dispos_x, spawn.dispos_y),
+            MapCoordinates::Appear => (spawn.appear_x, spawn.appear_y),
+        };
+        if col < 0 || row < 0 || col as u32 >= width || row as u32 >= height {
+            continue;
+        }
+        let (x, y) = tile_origin(col as u32, row as u32);
+        let key = unit_sprite_key(books, spawn)
+            .filter(|key| sprites.contains_key(key))
+            .unwrap_or_else(|| "000Dummy_000Dummy_Dummy".to_string());
+        if !scaled_sprites.contains_key(&key) {
+            if let Some(sprite) = sprites.get(&key) {
+                let scaled = imageops::resize(sprite, tile_size, tile_size, FilterType::Nearest);
+                scaled_sprites.insert(key.clone(), scaled);
+            }
+        }
+        if let Some(sprite) = scaled_sprites.get(&key) {
+            imageops::overlay(&mut image, sprite, x as i64, y as i64);
+        }
+        if let Some((_, color)) = FORCE_COLORS.get(spawn.force as usize) {
+            outline_rect(&mut image, x, y, tile_size, 2, *color);
+        }
+    }
+
+    let legend_x = map_x + width * tile_size + padding;
+    let swatch = line_height - padding / 2;
+    let mut legend_y = padding;
+    let mut legend_line = |image: &mut RgbaImage, label: &str, color: Option<Rgba<u8>>| {
+        let text_x = match color {
+            Some(color) => {
+                fill_rect(image, legend_x, legend_y, swatch, swatch, color);
+                legend_x + swatch + padding / 2
+            }
+            None => legend_x,
+        };
+        let text_y = legend_y as i64 + (swatch as i64 - text.height() as i64) / 2;
+        text.draw(image, text_x as i64, text_y, label);
+        legend_y += line_height;
+    };
+    legend_line(&mut image, "Terrain", None);
+    for (name, color) in &legend {
+        legend_line(&mut image, name, Some(*color));
+    }
+    legend_line(&mut image, "Units", None);
+    for (name, color) in FORCE_COLORS {
+        legend_line(&mut image, name, Some(color));
+    }
+
+    Ok(image)
+}
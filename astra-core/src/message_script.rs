@@ -25,6 +25,15 @@ impl OpenMessageScript {
         self.0.write().save(file_system, backup_root)
     }
 
+    pub(crate) fn copy_to(&self, file_system: &LocalizedFileSystem, path: String) -> Result<Self> {
+        let source = self.0.read();
+        let mut copy = OpenMessageScriptInner::load(file_system, source.path.clone())?;
+        copy.script = source.script.clone();
+        copy.path = path;
+        copy.dirty = true;
+        Ok(Self(Arc::new(RwLock::new(copy))))
+    }
+
     pub fn path(&self) -> String {
         self.0.read().path.clone()
     }
@@ -2,7 +2,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use astra_formats::indexmap::IndexMap;
 use astra_formats::MessageBundle;
 use indexmap::IndexSet;
@@ -12,6 +12,52 @@ use tracing::{info, warn};
 use crate::message_script::OpenMessageScript;
 use crate::{CobaltFileSystemProxy, LocalizedFileSystem};
 
+/// A message that differs between two projects.
+#[derive(Debug, Clone)]
+pub struct MessageDiff {
+    pub archive: String,
+    pub key: String,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+fn diff_archive(
+    archive_id: &str,
+    ours: &IndexMap<String, String>,
+    theirs: &IndexMap<String, String>,
+    diffs: &mut Vec<MessageDiff>,
+) {
+    for (key, value) in ours {
+        let other = theirs.get(key);
+        if other != Some(value) {
+            diffs.push(MessageDiff {
+                archive: archive_id.to_string(),
+                key: key.clone(),
+                ours: Some(value.clone()),
+                theirs: other.cloned(),
+            });
+        }
+    }
+    for (key, value) in theirs {
+        if !ours.contains_key(key) {
+            diffs.push(MessageDiff {
+                archive: archive_id.to_string(),
+                key: key.clone(),
+                ours: None,
+                theirs: Some(value.clone()),
+            });
+        }
+    }
+}
+
+fn script_path(archive_name: &str) -> String {
+    Path::new(r"StreamingAssets/aa/Switch/fe_assets_message")
+        .join(archive_name)
+        .with_extension("bytes.bundle")
+        .to_string_lossy()
+        .to_string()
+}
+
 pub struct MessageSystem {
     archives: HashMap<String, OpenMessageArchive>,
     scripts: HashMap<String, OpenMessageScript>,
@@ -214,12 +260,9 @@ impl MessageSystem {
         if let Some(script) = self.scripts.get(archive_name).cloned() {
             Ok(script)
         } else {
-            let path = Path::new(r"StreamingAssets/aa/Switch/fe_assets_message")
-                .join(archive_name)
-                .with_extension("bytes.bundle");
             let script = OpenMessageScript::load(
                 &self.file_system,
-                path.to_string_lossy().to_string(), // TODO: Just take a PathBuf?
+                script_path(archive_name), // TODO: Just take a PathBuf?
             )?;
             self.scripts
                 .insert(archive_name.to_string(), script.clone());
@@ -227,6 +270,23 @@ impl MessageSystem {
         }
     }
 
+    pub fn script_exists(&self, archive_name: &str) -> Result<bool> {
+        Ok(self.scripts.contains_key(archive_name)
+            || self.file_system.exists(script_path(archive_name), false)?)
+    }
+
+    /// Copy a message script, including unsaved edits, to a new name.
+    /// The copy is written on the next save.
+    pub fn copy_script(&mut self, from: &str, to: &str) -> Result<()> {
+        if self.script_exists(to)? {
+            bail!("message script '{}' already exists", to);
+        }
+        let source = self.open_script(from)?;
+        let copy = source.copy_to(&self.file_system, script_path(to))?;
+        self.scripts.insert(to.to_string(), copy);
+        Ok(())
+    }
+
     pub fn save(&self, backup_root: &Path) -> Result<()> {
         for archive in self.archives.values() {
             archive.save(&self.file_system, &self.cobalt, backup_root)?;
@@ -240,6 +300,84 @@ impl MessageSystem {
     pub fn get(&self, archive_id: &str) -> Option<&OpenMessageArchive> {
         self.archives.get(archive_id)
     }
+
+    /// Compare every archive against the same archive in another project.
+    /// An archive that only one project has counts as empty in the other.
+    pub fn diff(&self, theirs: &MessageSystem) -> Vec<MessageDiff> {
+        let archive_ids: BTreeSet<&String> =
+            self.archives.keys().chain(theirs.archives.keys()).collect();
+        let mut diffs = vec![];
+        for archive_id in archive_ids {
+            match (self.get(archive_id), theirs.get(archive_id)) {
+                (Some(ours), Some(theirs)) => ours.read(|ours| {
+                    theirs.read(|theirs| diff_archive(archive_id, ours, theirs, &mut diffs))
+                }),
+                (Some(ours), None) => {
+                    ours.read(|ours| diff_archive(archive_id, ours, &IndexMap::new(), &mut diffs))
+                }
+                (None, Some(theirs)) => theirs
+                    .read(|theirs| diff_archive(archive_id, &IndexMap::new(), theirs, &mut diffs)),
+                (None, None) => {}
+            }
+        }
+        diffs
+    }
+
+    /// Copy a message from another project into this one.
+    /// Messages cannot be deleted, so keys missing from the other project are left alone.
+    pub fn pull_message(
+        &self,
+        theirs: &MessageSystem,
+        archive_id: &str,
+        key: &str,
+    ) -> Result<bool> {
+        let ours = self
+            .get(archive_id)
+            .ok_or_else(|| anyhow!("unknown archive '{}'", archive_id))?;
+        let value = theirs
+            .get(archive_id)
+            .and_then(|theirs| theirs.read(|messages| messages.get(key).cloned()));
+        match value {
+            Some(value) => {
+                ours.put(key.to_string(), value);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Set a message, searching every archive for the key when `archive_id` is omitted.
+    pub fn patch_message(&self, archive_id: Option<&str>, key: &str, value: String) -> Result<()> {
+        let archive = match archive_id {
+            Some(archive_id) => self
+                .get(archive_id)
+                .ok_or_else(|| anyhow!("unknown archive '{}'", archive_id))?,
+            None => {
+                let matches: Vec<&str> = self
+                    .archives
+                    .iter()
+                    .filter(|(_, archive)| archive.read(|messages| messages.contains_key(key)))
+                    .map(|(archive_id, _)| archive_id.as_str())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                match matches.as_slice() {
+                    [archive_id] => &self.archives[*archive_id],
+                    [] => bail!(
+                        "message '{}' does not exist, specify an archive to add it",
+                        key
+                    ),
+                    archive_ids => bail!(
+                        "message '{}' exists in several archives, specify one of: {}",
+                        key,
+                        archive_ids.join(", ")
+                    ),
+                }
+            }
+        };
+        archive.put(key.to_string(), value);
+        Ok(())
+    }
 }
 
 pub struct OpenMessageArchive(Arc<RwLock<OpenMessageArchiveInner>>);
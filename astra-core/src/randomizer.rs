@@ -0,0 +1,561 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use astra_types::{DisposBook, GodLevelData, Item, Job, Person, ShopInventory};
+use indexmap::{IndexMap, IndexSet};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::book_system::{BookSystem, OpenBook};
+use crate::{
+    spawn_items, spawn_items_mut, weapon_rank, AstraProject, SPECIAL_KIND, STAFF_KIND, WEAPON_KINDS,
+};
+
+const PLAYER_FORCE: i8 = 0;
+const FLY_MOVE_TYPE: i8 = 3;
+
+// Each category draws from its own stream so toggling one option leaves the others untouched.
+const CLASS_STREAM: u64 = 1;
+const GROWTH_STREAM: u64 = 2;
+const DISPOS_STREAM: u64 = 3;
+const SHOP_STREAM: u64 = 4;
+const GOD_STREAM: u64 = 5;
+
+/// Settings for a randomizer run. The same seed and options always produce the same output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RandomizerOptions {
+    pub seed: u64,
+    pub classes: bool,
+    pub growths: bool,
+    /// How far each shuffled growth rate may be scaled up or down, in percent.
+    pub growth_variance: u8,
+    pub dispos: bool,
+    pub shops: bool,
+    pub god_skills: bool,
+}
+
+impl Default for RandomizerOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            classes: true,
+            growths: true,
+            growth_variance: 20,
+            dispos: true,
+            shops: true,
+            god_skills: true,
+        }
+    }
+}
+
+/// How many rows each part of the randomizer changed.
+#[derive(Debug, Default, Clone)]
+pub struct RandomizerReport {
+    pub persons: usize,
+    pub spawns: usize,
+    pub shop_items: usize,
+    pub god_levels: usize,
+}
+
+/// What a class is for, as far as keeping the game beatable goes.
+/// A unit only ever trades its class for another with the same role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClassRole {
+    advanced: bool,
+    flying: bool,
+    healer: bool,
+}
+
+impl ClassRole {
+    /// Classes without a promotion path in either direction are special (monsters, royals,
+    /// personal classes) and are left alone.
+    fn of(job: &Job) -> Option<Self> {
+        let advanced = !job.low_job.is_empty();
+        if !advanced && job.high_job_1.is_empty() && job.high_job_2.is_empty() {
+            return None;
+        }
+        Some(Self {
+            advanced,
+            flying: job.move_type == FLY_MOVE_TYPE,
+            healer: job.weapon_rod > 0,
+        })
+    }
+}
+
+/// Weapons the randomizer may hand out. Special weapons (dragonstones, monster attacks) are
+/// tied to their classes and never move.
+fn is_handed_out(kind: i8) -> bool {
+    WEAPON_KINDS.contains(&kind) && kind != SPECIAL_KIND
+}
+
+fn weapon_level(level: &str) -> u8 {
+    match level {
+        "E" => 1,
+        "D" => 2,
+        "C" => 3,
+        "B" => 4,
+        "A" => 5,
+        "S" => 6,
+        _ => 0,
+    }
+}
+
+fn growths(person: &mut Person) -> [&mut u8; 9] {
+    [
+        &mut person.grow_hp,
+        &mut person.grow_str,
+        &mut person.grow_tech,
+        &mut person.grow_quick,
+        &mut person.grow_luck,
+        &mut person.grow_def,
+        &mut person.grow_magic,
+        &mut person.grow_mdef,
+        &mut person.grow_phys,
+    ]
+}
+
+/// Vanilla data the randomizer draws from, gathered before anything is changed.
+struct Randomizer {
+    options: RandomizerOptions,
+    persons: IndexMap<String, Person>,
+    jobs: IndexMap<String, Job>,
+    items: IndexMap<String, Item>,
+    /// Persons that join the player's army, in sheet order.
+    playable: IndexSet<String>,
+    /// Genders seen in each class. Keeps gender locked classes locked.
+    job_genders: HashMap<String, BTreeSet<i8>>,
+    /// Classes that only one character ever has, ex. Alear's.
+    personal_jobs: BTreeSet<String>,
+    /// Weapons that units actually carry, by kind and rank.
+    weapons: BTreeMap<(i8, u8), BTreeSet<String>>,
+}
+
+impl Randomizer {
+    fn new(
+        options: RandomizerOptions,
+        books: &BookSystem,
+        dispos: &[OpenBook<DisposBook>],
+    ) -> Self {
+        let persons = books.person.read(|book| book.persons.data.clone());
+        let jobs = books.job.read(|book| book.jobs.data.clone());
+        let items = books.item.read(|book| book.items.data.clone());
+
+        let mut players = HashSet::new();
+        let mut job_genders: HashMap<String, BTreeSet<i8>> = HashMap::new();
+        let mut job_holders: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut carried = BTreeSet::new();
+        for person in persons.values() {
+            job_genders
+                .entry(person.jid.clone())
+                .or_default()
+                .insert(person.gender);
+            job_holders
+                .entry(person.jid.clone())
+                .or_default()
+                .insert(person.name.clone());
+            carried.extend(person.items.iter().cloned());
+        }
+        for book in dispos {
+            book.read(|book| {
+                for spawn in book.spawns.data.values().flatten() {
+                    if spawn.force == PLAYER_FORCE {
+                        players.insert(spawn.pid.clone());
+                    }
+                    if let (Some(person), false) = (persons.get(&spawn.pid), spawn.jid.is_empty()) {
+                        job_genders
+                            .entry(spawn.jid.clone())
+                            .or_default()
+                            .insert(person.gender);
+                    }
+                    carried.extend(spawn_items(spawn).map(|(iid, _)| iid.clone()));
+                }
+            });
+        }
+        // Keep the sheet order so the output doesn't depend on which dispos came first.
+        let playable = persons
+            .keys()
+            .filter(|pid| players.contains(*pid))
+            .cloned()
+            .collect();
+
+        let personal_jobs = job_holders
+            .into_iter()
+            .filter(|(_, names)| names.len() == 1)
+            .map(|(jid, _)| jid)
+            .collect();
+        let mut weapons: BTreeMap<(i8, u8), BTreeSet<String>> = BTreeMap::new();
+        for iid in carried {
+            if let Some(item) = items.get(&iid) {
+                if is_handed_out(item.kind) {
+                    weapons
+                        .entry((item.kind, weapon_level(&item.weapon_level)))
+                        .or_default()
+                        .insert(iid);
+                }
+            }
+        }
+
+        Self {
+            options,
+            persons,
+            jobs,
+            items,
+            playable,
+            job_genders,
+            personal_jobs,
+            weapons,
+        }
+    }
+
+    fn rng(&self, stream: u64) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.options.seed);
+        rng.set_stream(stream);
+        rng
+    }
+
+    /// Pick a class with the same role as `jid` that someone of `gender` can have.
+    fn pick_class(&self, rng: &mut ChaCha8Rng, jid: &str, gender: i8) -> Option<String> {
+        if self.personal_jobs.contains(jid) {
+            return None;
+        }
+        let role = self.jobs.get(jid).and_then(ClassRole::of)?;
+        let candidates: Vec<&String> = self
+            .jobs
+            .iter()
+            .filter(|(jid, job)| {
+                ClassRole::of(job) == Some(role)
+                    && !self.personal_jobs.contains(*jid)
+                    && self
+                        .job_genders
+                        .get(*jid)
+                        .map(|genders| genders.contains(&gender))
+                        .unwrap_or_default()
+            })
+            .map(|(jid, _)| jid)
+            .collect();
+        candidates.choose(rng).map(|jid| jid.to_string())
+    }
+
+    /// Swap weapons the class can't use for ones it can, keeping the rank where possible.
+    fn fix_weapon(&self, rng: &mut ChaCha8Rng, job: &Job, iid: &mut String) -> bool {
+        let item = match self.items.get(iid.as_str()) {
+            Some(item) if is_handed_out(item.kind) => item,
+            _ => return false,
+        };
+        if weapon_rank(job, item.kind) > 0 {
+            return false;
+        }
+        let usable: Vec<i8> = WEAPON_KINDS
+            .filter(|kind| is_handed_out(*kind) && weapon_rank(job, *kind) > 0)
+            .collect();
+        // Only hand out staves when the class can't use anything else.
+        let attacking: Vec<i8> = usable
+            .iter()
+            .copied()
+            .filter(|kind| *kind != STAFF_KIND)
+            .collect();
+        let kind = match attacking.choose(rng).or_else(|| usable.choose(rng)) {
+            Some(kind) => *kind,
+            None => return false,
+        };
+        let level = weapon_level(&item.weapon_level);
+        let replacement = self
+            .weapons
+            .range((kind, 0)..=(kind, level))
+            .next_back()
+            .or_else(|| self.weapons.range((kind, 0)..=(kind, u8::MAX)).next())
+            .and_then(|(_, iids)| iids.iter().collect::<Vec<_>>().choose(rng).cloned());
+        match replacement {
+            Some(replacement) => {
+                *iid = replacement.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn randomize_persons(&self, books: &BookSystem) -> (usize, HashMap<String, String>) {
+        let mut class_rng = self.rng(CLASS_STREAM);
+        let mut growth_rng = self.rng(GROWTH_STREAM);
+        let variance = self.options.growth_variance.min(100) as i32;
+        let mut classes = HashMap::new();
+        let mut changed = 0;
+        books.person.write(|book| {
+            for pid in &self.playable {
+                let person = match book.persons.data.get_mut(pid) {
+                    Some(person) => person,
+                    None => continue,
+                };
+                let before = (
+                    person.jid.clone(),
+                    person.items.clone(),
+                    growths(person).map(|g| *g),
+                );
+                if self.options.classes {
+                    if let Some(jid) = self.pick_class(&mut class_rng, &person.jid, person.gender) {
+                        if let Some(job) = self.jobs.get(&jid) {
+                            for iid in &mut person.items {
+                                self.fix_weapon(&mut class_rng, job, iid);
+                            }
+                        }
+                        classes.insert(pid.clone(), jid.clone());
+                        person.jid = jid;
+                    }
+                }
+                if self.options.growths {
+                    let mut stats = growths(person);
+                    let mut values: Vec<u8> = stats.iter().map(|growth| **growth).collect();
+                    values.shuffle(&mut growth_rng);
+                    for (growth, value) in stats.iter_mut().zip(values) {
+                        let scale = growth_rng.gen_range(100 - variance..=100 + variance);
+                        **growth = (value as i32 * scale / 100).clamp(0, u8::MAX as i32) as u8;
+                    }
+                }
+                if before
+                    != (
+                        person.jid.clone(),
+                        person.items.clone(),
+                        growths(person).map(|g| *g),
+                    )
+                {
+                    changed += 1;
+                }
+            }
+        });
+        if changed > 0 {
+            books.person.mark_dirty();
+        }
+        (changed, classes)
+    }
+
+    fn randomize_dispos(
+        &self,
+        dispos: &[OpenBook<DisposBook>],
+        classes: &HashMap<String, String>,
+    ) -> usize {
+        let mut rng = self.rng(DISPOS_STREAM);
+        let mut changed = 0;
+        for book in dispos {
+            let (book_changed, loot_moved) = book.write(|book| {
+                let mut book_changed = 0;
+                let mut loot = vec![];
+                for spawn in book.spawns.data.values_mut().flatten() {
+                    let person = match self.persons.get(&spawn.pid) {
+                        Some(person) => person,
+                        None => continue,
+                    };
+                    let enemy = !self.playable.contains(&spawn.pid);
+                    let before = (
+                        spawn.jid.clone(),
+                        spawn_items(spawn).map(|(iid, _)| iid.clone()),
+                    );
+                    let jid = if let Some(jid) = classes.get(&spawn.pid) {
+                        // Playable units follow their new class. Only overrides need updating.
+                        if !spawn.jid.is_empty() {
+                            spawn.jid = jid.clone();
+                        }
+                        Some(jid.clone())
+                    } else if self.options.dispos && enemy {
+                        let original = if spawn.jid.is_empty() {
+                            &person.jid
+                        } else {
+                            &spawn.jid
+                        };
+                        let jid = self.pick_class(&mut rng, original, person.gender);
+                        if let Some(jid) = &jid {
+                            spawn.jid = jid.clone();
+                        }
+                        jid
+                    } else {
+                        None
+                    };
+                    if let Some(job) = jid.as_ref().and_then(|jid| self.jobs.get(jid)) {
+                        for (iid, _) in spawn_items_mut(spawn) {
+                            self.fix_weapon(&mut rng, job, iid);
+                        }
+                    }
+                    if before
+                        != (
+                            spawn.jid.clone(),
+                            spawn_items(spawn).map(|(iid, _)| iid.clone()),
+                        )
+                    {
+                        book_changed += 1;
+                    }
+                    if self.options.dispos && enemy {
+                        loot.extend(
+                            spawn_items_mut(spawn)
+                                .into_iter()
+                                .map(|(iid, _)| iid)
+                                .filter(|iid| {
+                                    self.items
+                                        .get(iid.as_str())
+                                        .map(|item| !is_handed_out(item.kind))
+                                        .unwrap_or_default()
+                                }),
+                        );
+                    }
+                }
+                // Shuffle consumables and treasure between enemies. Drop flags stay with the slot.
+                let mut shuffled: Vec<String> = loot.iter().map(|iid| iid.to_string()).collect();
+                shuffled.shuffle(&mut rng);
+                let mut loot_moved = false;
+                for (iid, replacement) in loot.into_iter().zip(shuffled) {
+                    loot_moved |= *iid != replacement;
+                    *iid = replacement;
+                }
+                (book_changed, loot_moved)
+            });
+            if book_changed > 0 || loot_moved {
+                book.mark_dirty();
+            }
+            changed += book_changed;
+        }
+        changed
+    }
+
+    fn randomize_shops(&self, books: &BookSystem) -> usize {
+        let mut rng = self.rng(SHOP_STREAM);
+        let changed = books.shop.write(|book| {
+            randomize_shop(&mut rng, &mut book.armory_shop_inventory.data)
+                + randomize_shop(&mut rng, &mut book.item_shop_inventory.data)
+                + randomize_shop(&mut rng, &mut book.flea_market_shop_inventory.data)
+        });
+        if changed > 0 {
+            books.shop.mark_dirty();
+        }
+        changed
+    }
+
+    fn randomize_god_skills(&self, books: &BookSystem) -> usize {
+        let mut rng = self.rng(GOD_STREAM);
+        let changed = books.god.write(|book| {
+            remap_skills(&mut rng, &mut book.level_data.data, |level| {
+                &mut level.synchro_skills
+            }) + remap_skills(&mut rng, &mut book.level_data.data, |level| {
+                &mut level.inheritance_skills
+            })
+        });
+        if changed > 0 {
+            books.god.mark_dirty();
+        }
+        changed
+    }
+}
+
+/// Replace items with others the shop has stocked by the same point.
+/// Items sold from the very first group are staples and never move.
+fn randomize_shop(
+    rng: &mut ChaCha8Rng,
+    groups: &mut IndexMap<String, Vec<ShopInventory>>,
+) -> usize {
+    let staples: BTreeSet<String> = groups
+        .values()
+        .next()
+        .map(|rows| rows.iter().map(|row| row.iid.clone()).collect())
+        .unwrap_or_default();
+    let mut unlocked = BTreeSet::new();
+    let mut changed = 0;
+    for rows in groups.values_mut() {
+        unlocked.extend(
+            rows.iter()
+                .map(|row| row.iid.clone())
+                .filter(|iid| !iid.is_empty() && !staples.contains(iid)),
+        );
+        let mut stocked: BTreeSet<String> = rows
+            .iter()
+            .filter(|row| staples.contains(&row.iid))
+            .map(|row| row.iid.clone())
+            .collect();
+        for row in rows
+            .iter_mut()
+            .filter(|row| !row.iid.is_empty() && !staples.contains(&row.iid))
+        {
+            let candidates: Vec<&String> = unlocked
+                .iter()
+                .filter(|iid| !stocked.contains(*iid))
+                .collect();
+            if let Some(iid) = candidates.choose(rng) {
+                if **iid != row.iid {
+                    row.iid = iid.to_string();
+                    changed += 1;
+                }
+            }
+            stocked.insert(row.iid.clone());
+        }
+    }
+    changed
+}
+
+/// Swap every skill for another one from the same column. The mapping is the same for every
+/// emblem and level, so skills that level up together keep doing so.
+fn remap_skills(
+    rng: &mut ChaCha8Rng,
+    levels: &mut IndexMap<String, Vec<GodLevelData>>,
+    skills: fn(&mut GodLevelData) -> &mut Vec<String>,
+) -> usize {
+    let mut original = IndexSet::new();
+    for level in levels.values_mut().flatten() {
+        original.extend(skills(level).iter().filter(|sid| !sid.is_empty()).cloned());
+    }
+    let mut shuffled: Vec<String> = original.iter().cloned().collect();
+    shuffled.shuffle(rng);
+    let mapping: HashMap<String, String> = original.into_iter().zip(shuffled).collect();
+    let mut changed = 0;
+    for level in levels.values_mut().flatten() {
+        let mut level_changed = false;
+        for sid in skills(level) {
+            if let Some(replacement) = mapping.get(sid) {
+                level_changed |= replacement != sid;
+                *sid = replacement.clone();
+            }
+        }
+        if level_changed {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Randomize freshly loaded vanilla data and save the result to the project.
+///
+/// To keep the game beatable, units only trade classes for ones with the same tier,
+/// flying and healing ability, weapons are swapped for ones the new class can use,
+/// and shops only stock items they would have sold by that point anyway.
+pub fn randomize(project: &AstraProject, options: &RandomizerOptions) -> Result<RandomizerReport> {
+    let (_, cobalt_proxy) = project.open_file_systems(true)?;
+    let mut books = BookSystem::load(cobalt_proxy)
+        .context("Failed to load vanilla books (fe_assets_gamedata)")?;
+    // Sorted by name so a seed gives the same result no matter how chapters are ordered.
+    let mut chapter_dispos = books.open_chapter_dispos()?;
+    chapter_dispos.sort_by(|a, b| a.name.cmp(&b.name));
+    let dispos: Vec<OpenBook<DisposBook>> = chapter_dispos
+        .into_iter()
+        .map(|dispos| dispos.book)
+        .collect();
+
+    let randomizer = Randomizer::new(options.clone(), &books, &dispos);
+    let mut report = RandomizerReport::default();
+    let classes = if options.classes || options.growths {
+        let (persons, classes) = randomizer.randomize_persons(&books);
+        report.persons = persons;
+        classes
+    } else {
+        HashMap::new()
+    };
+    report.spawns = randomizer.randomize_dispos(&dispos, &classes);
+    if options.shops {
+        report.shop_items = randomizer.randomize_shops(&books);
+    }
+    if options.god_skills {
+        report.god_levels = randomizer.randomize_god_skills(&books);
+    }
+
+    let time = chrono::offset::Local::now().to_rfc3339().replace(':', "_");
+    let backup_path = project.backup_dir.join(time);
+    books.save(&backup_path)?;
+    Ok(report)
+}
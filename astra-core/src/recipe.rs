@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::book_system::BookSystem;
+use crate::message_system::MessageSystem;
+use crate::AstraProject;
+
+/// A declarative list of edits applied on top of vanilla data.
+///
+/// ```yaml
+/// name: Example
+/// operations:
+///   - set: Person[PID_リュール].grow_hp
+///     value: 60
+///   - add: Item[IID_NEW]
+///     base: IID_鉄の剣
+///     fields: { power: 7 }
+///   - append: Job[JID_ソードマスター].skills
+///     value: SID_NEW
+///   - remove: Item[IID_OLD]
+///   - message: MPID_X
+///     value: Hello
+/// ```
+///
+/// Sheets can be named by their row type (`Person`) or their field name (`persons`).
+/// Prefix the sheet with a book (`item/Item`) if the name is ambiguous.
+/// List sheets are keyed by index and grouped sheets by group then index, ex. `RewardData[GROUP][0]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recipe {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub operations: Vec<RecipeOperation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RecipeOperation {
+    Set {
+        set: String,
+        value: RecipeValue,
+    },
+    Append {
+        append: String,
+        value: RecipeValue,
+    },
+    Add {
+        add: String,
+        #[serde(default)]
+        base: Option<String>,
+        #[serde(default)]
+        fields: BTreeMap<String, RecipeValue>,
+    },
+    Remove {
+        remove: String,
+    },
+    Message {
+        message: String,
+        #[serde(default)]
+        archive: Option<String>,
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RecipeValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Display for RecipeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecipeValue::Bool(value) => write!(f, "{}", value),
+            RecipeValue::Integer(value) => write!(f, "{}", value),
+            RecipeValue::Float(value) => write!(f, "{}", value),
+            RecipeValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A parsed reference to a sheet, row and (optionally) field, ex. `Person[PID_リュール].grow_hp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeTarget {
+    pub book: Option<String>,
+    pub sheet: String,
+    pub keys: Vec<String>,
+    pub field: Option<String>,
+}
+
+impl RecipeTarget {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let sheet_end = text.find(['[', '.']).unwrap_or(text.len());
+        let (book, sheet) = match text[..sheet_end].split_once('/') {
+            Some((book, sheet)) => (Some(book.to_string()), sheet.to_string()),
+            None => (None, text[..sheet_end].to_string()),
+        };
+        if sheet.is_empty() {
+            bail!("missing sheet name in '{}'", text);
+        }
+        let (keys, rest) = parse_keys(&text[sheet_end..])
+            .with_context(|| format!("malformed target '{}'", text))?;
+        let field = match rest {
+            "" => None,
+            rest => match rest.strip_prefix('.') {
+                Some(field) if !field.is_empty() => Some(field.to_string()),
+                _ => bail!("malformed target '{}'", text),
+            },
+        };
+        Ok(Self {
+            book,
+            sheet,
+            keys,
+            field,
+        })
+    }
+
+    fn require_field(&self) -> Result<&str> {
+        self.field
+            .as_deref()
+            .ok_or_else(|| anyhow!("expected a field, ex. {}[KEY].field", self.sheet))
+    }
+}
+
+/// Parse `[a][b]...` from the start of `text`, returning the keys and whatever follows.
+fn parse_keys(mut text: &str) -> Result<(Vec<String>, &str)> {
+    let mut keys = vec![];
+    while let Some(rest) = text.strip_prefix('[') {
+        let end = rest.find(']').ok_or_else(|| anyhow!("unclosed '['"))?;
+        keys.push(rest[..end].trim().to_string());
+        text = &rest[end + 1..];
+    }
+    Ok((keys, text))
+}
+
+impl Recipe {
+    /// Load a recipe from a `.yaml`, `.yml` or `.toml` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read recipe {}", path.display()))?;
+        let is_toml = path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("toml"))
+            .unwrap_or_default();
+        let mut recipe: Recipe = if is_toml {
+            toml::from_str(&contents)
+                .with_context(|| format!("failed to parse recipe {}", path.display()))?
+        } else {
+            serde_yaml::from_str(&contents)
+                .with_context(|| format!("failed to parse recipe {}", path.display()))?
+        };
+        if recipe.name.is_empty() {
+            recipe.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(recipe)
+    }
+
+    fn apply(&self, books: &BookSystem, messages: &MessageSystem) -> Result<()> {
+        for (index, operation) in self.operations.iter().enumerate() {
+            operation
+                .apply(books, messages)
+                .with_context(|| format!("operation #{} in recipe '{}'", index + 1, self.name))?;
+        }
+        Ok(())
+    }
+}
+
+impl RecipeOperation {
+    fn apply(&self, books: &BookSystem, messages: &MessageSystem) -> Result<()> {
+        match self {
+            RecipeOperation::Set { set, value } => {
+                let target = RecipeTarget::parse(set)?;
+                let field = target.require_field()?;
+                books.patch_sheet(target.book.as_deref(), &target.sheet, |sheet| {
+                    sheet
+                        .row_mut(&target.keys)?
+                        .set_field(field, value.to_string())
+                })
+            }
+            RecipeOperation::Append { append, value } => {
+                let target = RecipeTarget::parse(append)?;
+                let field = target.require_field()?;
+                books.patch_sheet(target.book.as_deref(), &target.sheet, |sheet| {
+                    sheet
+                        .row_mut(&target.keys)?
+                        .append_field(field, value.to_string())
+                })
+            }
+            RecipeOperation::Add { add, base, fields } => {
+                let target = RecipeTarget::parse(add)?;
+                if target.field.is_some() {
+                    bail!("'add' takes a row, not a field");
+                }
+                let base = match base {
+                    Some(base) if base.starts_with('[') => Some(parse_keys(base)?.0),
+                    Some(base) => Some(vec![base.clone()]),
+                    None => None,
+                };
+                books.patch_sheet(target.book.as_deref(), &target.sheet, |sheet| {
+                    let base = match &base {
+                        Some(keys) => Some(
+                            sheet
+                                .row(keys)
+                                .context("failed to find the base row")?
+                                .patch_values(),
+                        ),
+                        None => None,
+                    };
+                    let row = sheet.insert_row(&target.keys, base)?;
+                    for (field, value) in fields {
+                        row.set_field(field, value.to_string())?;
+                    }
+                    Ok(())
+                })
+            }
+            RecipeOperation::Remove { remove } => {
+                let target = RecipeTarget::parse(remove)?;
+                if target.field.is_some() {
+                    bail!("'remove' takes a row, not a field");
+                }
+                books.patch_sheet(target.book.as_deref(), &target.sheet, |sheet| {
+                    sheet.remove_row(&target.keys)
+                })
+            }
+            RecipeOperation::Message {
+                message,
+                archive,
+                value,
+            } => messages.patch_message(archive.as_deref(), message, value.clone()),
+        }
+    }
+}
+
+/// Apply recipes to freshly loaded vanilla data and save the result to the project.
+/// Books and archives that no recipe touches are left alone.
+/// Nothing is saved unless every operation applies. Saving writes books, then messages,
+/// one file at a time. If a write fails partway, the files written so far stay in place
+/// and the error names the backup folder holding the versions they replaced.
+pub fn apply_recipes(project: &AstraProject, recipes: &[Recipe]) -> Result<usize> {
+    let (file_system, cobalt_proxy) = project.open_file_systems(true)?;
+    let books = BookSystem::load(cobalt_proxy.clone())
+        .context("Failed to load vanilla books (fe_assets_gamedata)")?;
+    let messages = MessageSystem::load(file_system, cobalt_proxy)
+        .context("Failed to load vanilla text data (MSBT)")?;
+    let mut applied = 0;
+    for recipe in recipes {
+        recipe.apply(&books, &messages)?;
+        applied += recipe.operations.len();
+    }
+    let time = chrono::offset::Local::now().to_rfc3339().replace(':', "_");
+    let backup_path = project.backup_dir.join(time);
+    books.save(&backup_path).with_context(|| {
+        format!(
+            "Failed to save books. Books written before the failure were backed up to {}",
+            backup_path.display()
+        )
+    })?;
+    messages.save(&backup_path).with_context(|| {
+        format!(
+            "Books were saved but text data failed to save. Replaced files were backed up to {}",
+            backup_path.display()
+        )
+    })?;
+    Ok(applied)
+}
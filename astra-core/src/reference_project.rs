@@ -0,0 +1,30 @@
+use anyhow::{Context, Result};
+
+use crate::book_system::BookSystem;
+use crate::message_system::MessageSystem;
+use crate::AstraProject;
+
+/// A second project loaded for comparison. Only books and messages are loaded and
+/// nothing is ever written back, so it is safe to point this at another mod or an old copy.
+pub struct ReferenceProject {
+    project: AstraProject,
+    pub(crate) book_system: BookSystem,
+    pub(crate) message_system: MessageSystem,
+}
+
+impl ReferenceProject {
+    pub fn load(project: AstraProject) -> Result<Self> {
+        let (file_system, cobalt_proxy) = project.open_file_systems(false)?;
+        Ok(Self {
+            book_system: BookSystem::load(cobalt_proxy.clone())
+                .context("Failed to load reference books (fe_assets_gamedata)")?,
+            message_system: MessageSystem::load(file_system, cobalt_proxy)
+                .context("Failed to load reference text data (MSBT)")?,
+            project,
+        })
+    }
+
+    pub fn project(&self) -> &AstraProject {
+        &self.project
+    }
+}
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use parking_lot::Mutex;
 use tracing::{error, info};
 
@@ -12,6 +12,8 @@ use crate::{BundlePersistFormat, CobaltFileSystemProxy};
 pub struct ScriptSystem {
     file_system: Arc<CobaltFileSystemProxy>,
     opened_scripts: HashMap<String, OpenScript>,
+    /// Copies that haven't been written yet, by new name.
+    pending_copies: Mutex<HashMap<String, String>>,
 }
 
 impl ScriptSystem {
@@ -19,6 +21,7 @@ impl ScriptSystem {
         Self {
             file_system,
             opened_scripts: Default::default(),
+            pending_copies: Default::default(),
         }
     }
 
@@ -31,6 +34,7 @@ impl ScriptSystem {
         let script_path = if let Some(script) = self.opened_scripts.get(script_name) {
             script.absolute_script_path.clone()
         } else {
+            self.write_copy(script_name)?;
             let script = OpenScript::load(&self.file_system, script_name)?;
             let path = script.absolute_script_path.clone();
             self.opened_scripts.insert(script_name.to_string(), script);
@@ -48,13 +52,63 @@ impl ScriptSystem {
         Ok(())
     }
 
+    /// Read a script's source without opening it in an editor.
+    /// Scripts that haven't been opened are read where they're kept instead of being unpacked,
+    /// so reading alone never writes anything or causes a save.
+    pub fn read(&self, script_name: &str) -> Result<String> {
+        let pending_source = self.pending_copies.lock().get(script_name).cloned();
+        if let Some(from) = pending_source {
+            return self.read(&from);
+        }
+        let raw = match self.opened_scripts.get(script_name) {
+            Some(script) => std::fs::read(&script.absolute_script_path)?,
+            None => self.file_system.read_script_source(script_name)?,
+        };
+        Ok(String::from_utf8_lossy(&raw).into_owned())
+    }
+
     pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let pending: Vec<String> = self.pending_copies.lock().keys().cloned().collect();
+        for script_name in pending {
+            self.write_copy(&script_name)?;
+        }
         for script in self.opened_scripts.values() {
             script.save(&self.file_system, backup_root)?;
         }
         Ok(())
     }
 
+    /// Copy a script to a new name. Fails if a script with that name already exists.
+    /// The copy is written on the next save, or sooner if it gets opened.
+    pub fn copy(&self, from: &str, to: &str) -> Result<()> {
+        if !self.exists(from) {
+            bail!("script '{}' doesn't exist", from);
+        }
+        if self.exists(to) {
+            bail!("script '{}' already exists", to);
+        }
+        let mut pending_copies = self.pending_copies.lock();
+        let from = pending_copies
+            .get(from)
+            .cloned()
+            .unwrap_or_else(|| from.to_string());
+        pending_copies.insert(to.to_string(), from);
+        Ok(())
+    }
+
+    fn write_copy(&self, script_name: &str) -> Result<()> {
+        let mut pending_copies = self.pending_copies.lock();
+        if let Some(from) = pending_copies.get(script_name) {
+            self.file_system.copy_script(from, script_name)?;
+            pending_copies.remove(script_name);
+        }
+        Ok(())
+    }
+
+    pub fn exists(&self, script_name: &str) -> bool {
+        self.list_all().contains(script_name)
+    }
+
     pub fn forget(&mut self, script_name: &str) {
         self.opened_scripts.remove(script_name);
     }
@@ -65,7 +119,10 @@ impl ScriptSystem {
 
     pub fn list_all(&self) -> BTreeSet<String> {
         match self.file_system.list_scripts() {
-            Ok(scripts) => scripts,
+            Ok(mut scripts) => {
+                scripts.extend(self.pending_copies.lock().keys().cloned());
+                scripts
+            }
             Err(err) => {
                 error!("Failed to list scripts: {:?}", err);
                 BTreeSet::new()
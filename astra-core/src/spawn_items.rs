@@ -0,0 +1,25 @@
+use astra_types::Spawn;
+
+/// The spawn's six item slots as (iid, drop) pairs.
+pub fn spawn_items(spawn: &Spawn) -> [(&String, i8); 6] {
+    [
+        (&spawn.item_1_iid, spawn.item_1_drop),
+        (&spawn.item_2_iid, spawn.item_2_drop),
+        (&spawn.item_3_iid, spawn.item_3_drop),
+        (&spawn.item_4_iid, spawn.item_4_drop),
+        (&spawn.item_5_iid, spawn.item_5_drop),
+        (&spawn.item_6_iid, spawn.item_6_drop),
+    ]
+}
+
+/// Mutable version of [spawn_items].
+pub fn spawn_items_mut(spawn: &mut Spawn) -> [(&mut String, &mut i8); 6] {
+    [
+        (&mut spawn.item_1_iid, &mut spawn.item_1_drop),
+        (&mut spawn.item_2_iid, &mut spawn.item_2_drop),
+        (&mut spawn.item_3_iid, &mut spawn.item_3_drop),
+        (&mut spawn.item_4_iid, &mut spawn.item_4_drop),
+        (&mut spawn.item_5_iid, &mut spawn.item_5_drop),
+        (&mut spawn.item_6_iid, &mut spawn.item_6_drop),
+    ]
+}
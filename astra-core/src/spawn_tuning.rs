@@ -0,0 +1,368 @@
+use anyhow::{anyhow, bail, Context, Result};
+use astra_types::{normalize_patch_name, PatchableRow, Spawn};
+use indexmap::IndexMap;
+
+use crate::book_system::BookSystem;
+use crate::spawn_items_mut;
+
+/// Which spawns a batch of [SpawnRule]s applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnFilter {
+    pub force: Option<i8>,
+    /// Spawns must appear on at least one of these difficulties. Zero matches every difficulty.
+    pub difficulties: u16,
+    /// Flags that must all be set, ex. the boss flag.
+    pub required_flags: u16,
+    /// Inclusive range of `Chapter.progress`.
+    pub min_progress: u8,
+    pub max_progress: u8,
+}
+
+impl Default for SpawnFilter {
+    fn default() -> Self {
+        Self {
+            force: None,
+            difficulties: 0,
+            required_flags: 0,
+            min_progress: 0,
+            max_progress: u8::MAX,
+        }
+    }
+}
+
+impl SpawnFilter {
+    pub fn matches(&self, progress: u8, spawn: &Spawn) -> bool {
+        self.force.map(|force| force == spawn.force).unwrap_or(true)
+            && (self.difficulties == 0 || spawn.flag & self.difficulties != 0)
+            && spawn.flag & self.required_flags == self.required_flags
+            && (self.min_progress..=self.max_progress).contains(&progress)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Integer arithmetic over a spawn's fields, ex. `LevelN + 2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Number(i64),
+    Field(String),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let expression = parser.sum()?;
+        match parser.tokens.get(parser.position) {
+            Some(token) => bail!("unexpected '{}'", token),
+            None => Ok(expression),
+        }
+    }
+
+    fn evaluate(&self, values: &IndexMap<String, String>) -> Result<i64> {
+        match self {
+            Expression::Number(value) => Ok(*value),
+            Expression::Field(field) => {
+                let value = field_value(values, field)
+                    .ok_or_else(|| anyhow!("unknown field '{}'", field))?;
+                value
+                    .parse()
+                    .with_context(|| format!("field '{}' is not a number ('{}')", field, value))
+            }
+            Expression::Negate(expression) => {
+                let value = expression.evaluate(values)?;
+                value
+                    .checked_neg()
+                    .ok_or_else(|| anyhow!("overflow in -{}", value))
+            }
+            Expression::Binary(left, operator, right) => {
+                let left = left.evaluate(values)?;
+                let right = right.evaluate(values)?;
+                let (result, symbol) = match operator {
+                    Operator::Add => (left.checked_add(right), '+'),
+                    Operator::Subtract => (left.checked_sub(right), '-'),
+                    Operator::Multiply => (left.checked_mul(right), '*'),
+                    Operator::Divide if right == 0 => bail!("division by zero"),
+                    Operator::Divide => (left.checked_div(right), '/'),
+                };
+                result.ok_or_else(|| anyhow!("overflow in {} {} {}", left, symbol, right))
+            }
+        }
+    }
+}
+
+/// Look a field up by either its Rust or XML name.
+fn field_value<'a>(values: &'a IndexMap<String, String>, field: &str) -> Option<&'a String> {
+    let field = normalize_patch_name(field).replace('.', "");
+    values
+        .iter()
+        .find(|(key, _)| normalize_patch_name(key).replace('.', "") == field)
+        .map(|(_, value)| value)
+}
+
+fn tokenize(text: &str) -> Result<Vec<String>> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if "+-*/()".contains(c) {
+            tokens.push(c.to_string());
+            continue;
+        }
+        if !(c.is_alphanumeric() || c == '_' || c == '@') {
+            bail!("unexpected '{}'", c);
+        }
+        let mut token = c.to_string();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "_@.".contains(*c)) {
+            token.push(c);
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser {
+    fn next_if(&mut self, token: &str) -> bool {
+        let matches = self.tokens.get(self.position).map(|t| t.as_str()) == Some(token);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn sum(&mut self) -> Result<Expression> {
+        let mut expression = self.product()?;
+        loop {
+            let operator = if self.next_if("+") {
+                Operator::Add
+            } else if self.next_if("-") {
+                Operator::Subtract
+            } else {
+                return Ok(expression);
+            };
+            expression =
+                Expression::Binary(Box::new(expression), operator, Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expression> {
+        let mut expression = self.unary()?;
+        loop {
+            let operator = if self.next_if("*") {
+                Operator::Multiply
+            } else if self.next_if("/") {
+                Operator::Divide
+            } else {
+                return Ok(expression);
+            };
+            expression =
+                Expression::Binary(Box::new(expression), operator, Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        if self.next_if("-") {
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+        if self.next_if("(") {
+            let expression = self.sum()?;
+            if !self.next_if(")") {
+                bail!("missing ')'");
+            }
+            return Ok(expression);
+        }
+        let token = self
+            .tokens
+            .get(self.position)
+            .ok_or_else(|| anyhow!("unexpected end of expression"))?;
+        self.position += 1;
+        if token.starts_with(|c: char| c.is_ascii_digit()) {
+            token
+                .parse()
+                .map(Expression::Number)
+                .map_err(|_| anyhow!("bad number '{}'", token))
+        } else if ")+*/".contains(token.as_str()) {
+            bail!("unexpected '{}'", token)
+        } else {
+            Ok(Expression::Field(token.clone()))
+        }
+    }
+}
+
+/// A single change to apply to every matching spawn.
+///
+/// Written one per line:
+/// ```text
+/// # Comments start with '#'
+/// LevelH = LevelN + 2
+/// HpStockCount = HpStockCount + 1
+/// IID_鉄の剣 -> IID_鋼の剣
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnRule {
+    Assign {
+        field: String,
+        expression: Expression,
+    },
+    /// Swap an item in every item slot.
+    ReplaceItem { from: String, to: String },
+}
+
+impl SpawnRule {
+    pub fn parse(line: &str) -> Result<Self> {
+        if let Some((from, to)) = line.split_once("->") {
+            let (from, to) = (from.trim(), to.trim());
+            if from.is_empty() || to.is_empty() {
+                bail!("expected 'FROM -> TO'");
+            }
+            return Ok(SpawnRule::ReplaceItem {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+        match line.split_once('=') {
+            Some((field, expression)) if !field.trim().is_empty() => Ok(SpawnRule::Assign {
+                field: field.trim().to_string(),
+                expression: Expression::parse(expression)?,
+            }),
+            _ => bail!("expected 'FIELD = EXPRESSION' or 'FROM -> TO'"),
+        }
+    }
+
+    /// Parse one rule per line, skipping blank lines and comments.
+    pub fn parse_script(script: &str) -> Result<Vec<Self>> {
+        script
+            .lines()
+            .enumerate()
+            .map(|(number, line)| (number, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(number, line)| {
+                Self::parse(line).with_context(|| format!("line {}: '{}'", number + 1, line))
+            })
+            .collect()
+    }
+
+    fn apply(&self, spawn: &mut Spawn) -> Result<()> {
+        match self {
+            SpawnRule::Assign { field, expression } => {
+                let value = expression.evaluate(&spawn.patch_values())?;
+                // Write to a copy first so a value the field can't hold is reported, not truncated.
+                let mut updated = spawn.clone();
+                updated.set_field(field, value.to_string())?;
+                let written = field_value(&updated.patch_values(), field)
+                    .and_then(|written| written.parse::<i64>().ok());
+                if written != Some(value) {
+                    bail!("{} is out of range for '{}'", value, field);
+                }
+                *spawn = updated;
+                Ok(())
+            }
+            SpawnRule::ReplaceItem { from, to } => {
+                for (iid, _) in spawn_items_mut(spawn) {
+                    if *iid == *from {
+                        *iid = to.clone();
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// One field of one spawn changed by [tune_spawns](crate::Astra::tune_spawns).
+#[derive(Debug, Clone)]
+pub struct SpawnChange {
+    pub chapter: String,
+    pub dispos: String,
+    pub group: String,
+    pub index: usize,
+    pub pid: String,
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+pub(crate) fn tune_spawns(
+    books: &mut BookSystem,
+    filter: &SpawnFilter,
+    rules: &[SpawnRule],
+    apply: bool,
+) -> Result<Vec<SpawnChange>> {
+    let mut changes = vec![];
+    let mut edits = vec![];
+    for dispos in books.open_chapter_dispos()? {
+        dispos.book.read(|book| -> Result<()> {
+            for (group, spawns) in &book.spawns.data {
+                for (index, spawn) in spawns.iter().enumerate() {
+                    if !filter.matches(dispos.progress, spawn) {
+                        continue;
+                    }
+                    let mut edited = spawn.clone();
+                    for rule in rules {
+                        rule.apply(&mut edited).with_context(|| {
+                            format!("{} in {}/{}[{}]", spawn.pid, dispos.name, group, index)
+                        })?;
+                    }
+                    let before = spawn.patch_values();
+                    let after = edited.patch_values();
+                    let changed = changes.len();
+                    changes.extend(
+                        before
+                            .into_iter()
+                            .zip(after)
+                            .filter(|(old, new)| old != new)
+                            .map(|((field, old), (_, new))| SpawnChange {
+                                chapter: dispos.cid.clone(),
+                                dispos: dispos.name.clone(),
+                                group: group.clone(),
+                                index,
+                                pid: spawn.pid.clone(),
+                                field,
+                                old,
+                                new,
+                            }),
+                    );
+                    if changes.len() > changed {
+                        edits.push((dispos.book.clone(), group.clone(), index, edited));
+                    }
+                }
+            }
+            Ok(())
+        })?;
+    }
+    // Only write once every spawn succeeded so a bad rule can't leave things half edited.
+    if apply {
+        for (book, group, index, spawn) in edits {
+            book.write(|book| {
+                if let Some(slot) = book
+                    .spawns
+                    .data
+                    .get_mut(&group)
+                    .and_then(|spawns| spawns.get_mut(index))
+                {
+                    *slot = spawn;
+                }
+            });
+            book.mark_dirty();
+        }
+    }
+    Ok(changes)
+}
@@ -2,11 +2,19 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use astra_formats::{MonoBehavior, TerrainBundle, TerrainData};
 use parking_lot::RwLock;
 
-use crate::LocalizedFileSystem;
+use crate::{resize_terrain, LocalizedFileSystem};
+
+const TERRAIN_DIR: &str = r"StreamingAssets\aa\Switch\fe_assets_gamedata\terrains";
+
+fn terrain_path(terrain_name: &str) -> PathBuf {
+    Path::new(TERRAIN_DIR)
+        .join(terrain_name.to_lowercase())
+        .with_extension("bundle")
+}
 
 pub struct TerrainSystem {
     file_system: Arc<LocalizedFileSystem>,
@@ -25,16 +33,65 @@ impl TerrainSystem {
         if let Some(terrain) = self.terrain.get(terrain_name).cloned() {
             Ok(terrain)
         } else {
-            let path = Path::new(r"StreamingAssets\aa\Switch\fe_assets_gamedata\terrains")
-                .join(terrain_name.to_lowercase())
-                .with_extension("bundle");
-            let open_bundle = OpenTerrain::load(&self.file_system, path)?;
+            let open_bundle = OpenTerrain::load(&self.file_system, terrain_path(terrain_name))?;
             let terrain = open_bundle.clone();
             self.terrain.insert(terrain_name.to_string(), open_bundle);
             Ok(terrain)
         }
     }
 
+    pub fn exists(&self, terrain_name: &str) -> Result<bool> {
+        Ok(self.terrain.contains_key(terrain_name)
+            || self.file_system.exists(terrain_path(terrain_name), false)?)
+    }
+
+    /// Copy a terrain, including unsaved edits, to a new bundle that is written on the next save.
+    pub fn copy(&mut self, from: &str, to: &str) -> Result<()> {
+        if self.exists(to)? {
+            bail!("terrain '{}' already exists", to);
+        }
+        let source = self.open(from)?;
+        let copy = source.copy_to(&self.file_system, terrain_path(to))?;
+        self.terrain.insert(to.to_string(), copy);
+        Ok(())
+    }
+
+    /// Make a new `width` x `height` map filled with `fill_tid`. It is written on the next save.
+    /// Bundles can't be built from nothing, so the first terrain bundle in the ROM is reused
+    /// as a template with its grid replaced.
+    pub fn create(
+        &mut self,
+        terrain_name: &str,
+        width: i32,
+        height: i32,
+        fill_tid: &str,
+    ) -> Result<OpenTerrain> {
+        let terrain_name = terrain_name.to_lowercase();
+        if terrain_name.is_empty() {
+            bail!("terrain names can't be empty");
+        }
+        if self.exists(&terrain_name)? {
+            bail!("terrain '{}' already exists", terrain_name);
+        }
+        let template = self
+            .file_system
+            .list_files(TERRAIN_DIR, "*.bundle", false)?
+            .into_iter()
+            .min()
+            .ok_or_else(|| anyhow!("there is no terrain bundle to use as a template"))?;
+        let mut terrain = OpenTerrainInner::load(&self.file_system, template)?;
+        let data = &mut terrain.data.data;
+        data.terrains.clear();
+        data.width = 0;
+        data.height = 0;
+        resize_terrain(data, width, height, fill_tid)?;
+        terrain.path = terrain_path(&terrain_name);
+        terrain.dirty = true;
+        let terrain = OpenTerrain(Arc::new(RwLock::new(terrain)));
+        self.terrain.insert(terrain_name, terrain.clone());
+        Ok(terrain)
+    }
+
     pub fn save(&self, backup_root: &Path) -> Result<()> {
         for terrain in self.terrain.values() {
             terrain.save(&self.file_system, backup_root)?;
@@ -61,6 +118,15 @@ impl OpenTerrain {
         self.0.write().save(file_system, backup_root)
     }
 
+    fn copy_to(&self, file_system: &LocalizedFileSystem, path: PathBuf) -> Result<Self> {
+        let source = self.0.read();
+        let mut copy = OpenTerrainInner::load(file_system, source.path.clone())?;
+        copy.data = source.data.clone();
+        copy.path = path;
+        copy.dirty = true;
+        Ok(Self(Arc::new(RwLock::new(copy))))
+    }
+
     pub fn read<R>(&self, consumer: impl FnOnce(&TerrainData) -> R) -> R {
         consumer(&self.0.read().data.data)
     }
@@ -69,8 +135,15 @@ impl OpenTerrain {
         let mut terrain = self.0.write();
         if consumer(&mut terrain.data.data) {
             terrain.dirty = true;
+            terrain.revision_number += 1;
         }
     }
+
+    /// Retrieve the revision number for the terrain.
+    /// This is incremented every time a write operation modifies it.
+    pub fn revision_number(&self) -> usize {
+        self.0.read().revision_number
+    }
 }
 
 struct OpenTerrainInner {
@@ -78,6 +151,7 @@ struct OpenTerrainInner {
     path: PathBuf,
     pub dirty: bool,
     pub data: MonoBehavior<TerrainData>,
+    revision_number: usize,
 }
 
 impl OpenTerrainInner {
@@ -90,6 +164,7 @@ impl OpenTerrainInner {
             bundle,
             path,
             dirty: false,
+            revision_number: 0,
         })
     }
 
@@ -0,0 +1,334 @@
+use anyhow::{anyhow, bail, Context, Result};
+use astra_formats::{TerrainData, UString};
+use astra_types::{Spawn, TerrainData as Tile};
+use indexmap::IndexMap;
+
+/// Terrain grids are always stored 32 tiles wide, no matter the size of the map.
+pub const TERRAIN_STRIDE: usize = 32;
+
+fn tile_index(col: usize, row: usize) -> usize {
+    row * TERRAIN_STRIDE + col
+}
+
+/// Change the playable size of a map. Tiles uncovered by growing it are set to `fill_tid`.
+pub fn resize_terrain(
+    terrain: &mut TerrainData,
+    width: i32,
+    height: i32,
+    fill_tid: &str,
+) -> Result<()> {
+    if !(1..=TERRAIN_STRIDE as i32).contains(&width) || height < 1 {
+        bail!(
+            "maps must be 1 to {} tiles wide and at least 1 tile tall",
+            TERRAIN_STRIDE
+        );
+    }
+    let (old_width, old_height) = (
+        terrain.width.max(0) as usize,
+        terrain.height.max(0) as usize,
+    );
+    let (width, height) = (width as usize, height as usize);
+    if terrain.terrains.len() < height * TERRAIN_STRIDE {
+        terrain
+            .terrains
+            .resize(height * TERRAIN_STRIDE, UString(fill_tid.to_string()));
+    }
+    for row in 0..height {
+        for col in 0..width {
+            if row >= old_height || col >= old_width {
+                terrain.terrains[tile_index(col, row)] = UString(fill_tid.to_string());
+            }
+        }
+    }
+    terrain.width = width as i32;
+    terrain.height = height as i32;
+    Ok(())
+}
+
+/// Move every tile of the map by `(dx, dy)`.
+/// Tiles shifted in from outside the map are set to `fill_tid`.
+pub fn shift_terrain(terrain: &mut TerrainData, dx: i32, dy: i32, fill_tid: &str) {
+    let (width, height) = (terrain.width.max(0) as i64, terrain.height.max(0) as i64);
+    let original = terrain.terrains.clone();
+    for row in 0..height {
+        for col in 0..width {
+            let (source_col, source_row) = (col - dx as i64, row - dy as i64);
+            let tile = if (0..width).contains(&source_col) && (0..height).contains(&source_row) {
+                original.get(tile_index(source_col as usize, source_row as usize))
+            } else {
+                None
+            };
+            if let Some(slot) = terrain
+                .terrains
+                .get_mut(tile_index(col as usize, row as usize))
+            {
+                *slot = tile
+                    .cloned()
+                    .unwrap_or_else(|| UString(fill_tid.to_string()));
+            }
+        }
+    }
+}
+
+/// Move spawns by `(dx, dy)` to follow their map, ex. after [shift_terrain].
+/// Spawns with a position that would end up outside a `width` x `height` map are left where
+/// they are and reported so they can be fixed by hand.
+/// Returns how many spawns moved along with the warnings.
+pub fn move_spawns(
+    dispos: &mut IndexMap<String, Vec<Spawn>>,
+    dx: i32,
+    dy: i32,
+    width: i32,
+    height: i32,
+) -> (usize, Vec<String>) {
+    let mut moved = 0;
+    let mut warnings = vec![];
+    for (group, spawns) in dispos {
+        for (index, spawn) in spawns.iter_mut().enumerate() {
+            let positions = [
+                (
+                    "dispos",
+                    spawn.dispos_x as i32 + dx,
+                    spawn.dispos_y as i32 + dy,
+                ),
+                (
+                    "appear",
+                    spawn.appear_x as i32 + dx,
+                    spawn.appear_y as i32 + dy,
+                ),
+            ];
+            let off_map: Vec<String> = positions
+                .iter()
+                .filter(|(_, x, y)| !(0..width).contains(x) || !(0..height).contains(y))
+                .map(|(label, x, y)| format!("{} position ({}, {})", label, x, y))
+                .collect();
+            if !off_map.is_empty() {
+                warnings.push(format!(
+                    "{}[{}] {}: left in place since its {} would be off the map",
+                    group,
+                    index,
+                    spawn.pid,
+                    off_map.join(" and ")
+                ));
+                continue;
+            }
+            if dx != 0 || dy != 0 {
+                let [(_, dispos_x, dispos_y), (_, appear_x, appear_y)] = positions;
+                spawn.dispos_x = dispos_x as i8;
+                spawn.dispos_y = dispos_y as i8;
+                spawn.appear_x = appear_x as i8;
+                spawn.appear_y = appear_y as i8;
+                moved += 1;
+            }
+        }
+    }
+    (moved, warnings)
+}
+
+/// Replace the playable area with a grid of TIDs where the first row is the top of the map.
+/// The map takes on the size of the grid.
+fn set_grid(terrain: &mut TerrainData, rows: Vec<Vec<String>>) -> Result<()> {
+    let height = rows.len();
+    let width = rows.first().map(|row| row.len()).unwrap_or_default();
+    if height == 0 || width == 0 {
+        bail!("the map is empty");
+    }
+    if width > TERRAIN_STRIDE {
+        bail!("maps can't be wider than {} tiles", TERRAIN_STRIDE);
+    }
+    if let Some(row) = rows.iter().position(|row| row.len() != width) {
+        bail!(
+            "row {} has {} tiles but the first row has {}",
+            row + 1,
+            rows[row].len(),
+            width
+        );
+    }
+    if terrain.terrains.len() < height * TERRAIN_STRIDE {
+        terrain
+            .terrains
+            .resize(height * TERRAIN_STRIDE, UString(rows[0][0].clone()));
+    }
+    for (line, tids) in rows.into_iter().enumerate() {
+        let row = height - 1 - line;
+        for (col, tid) in tids.into_iter().enumerate() {
+            terrain.terrains[tile_index(col, row)] = UString(tid);
+        }
+    }
+    terrain.width = width as i32;
+    terrain.height = height as i32;
+    Ok(())
+}
+
+/// The playable area as CSV, one row of TIDs per line.
+/// The first line is the top of the map, same as the editor.
+pub fn terrain_to_csv(terrain: &TerrainData) -> String {
+    let (width, height) = (
+        terrain.width.max(0) as usize,
+        terrain.height.max(0) as usize,
+    );
+    let mut csv = String::new();
+    for row in (0..height).rev() {
+        let line: Vec<&str> = (0..width)
+            .map(|col| {
+                terrain
+                    .terrains
+                    .get(tile_index(col, row))
+                    .map(|tid| tid.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Replace the map with a CSV of TIDs like the ones written by [terrain_to_csv].
+/// The map takes on the size of the CSV and every TID must exist in `tiles`.
+/// The map is left untouched if the CSV is invalid.
+pub fn terrain_from_csv(
+    terrain: &mut TerrainData,
+    csv: &str,
+    tiles: &IndexMap<String, Tile>,
+) -> Result<()> {
+    let mut rows = vec![];
+    for (number, line) in csv.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut row = vec![];
+        for (col, tid) in line.split(',').enumerate() {
+            let tid = tid.trim().trim_matches('"');
+            if !tiles.contains_key(tid) {
+                bail!(
+                    "line {}, column {}: unknown TID '{}'",
+                    number + 1,
+                    col + 1,
+                    tid
+                );
+            }
+            row.push(tid.to_string());
+        }
+        rows.push(row);
+    }
+    set_grid(terrain, rows)
+}
+
+fn tile_color(tile: &Tile) -> [u8; 3] {
+    [tile.color_r, tile.color_g, tile.color_b]
+}
+
+/// The playable area as an indexed PNG with one pixel per tile. The top row is the top of the map.
+/// Palette entry N is the color of the Nth tile in `tiles`, so drawing with the palette
+/// and reading the image back with [terrain_from_png] maps every color to its TID.
+pub fn terrain_to_png(terrain: &TerrainData, tiles: &IndexMap<String, Tile>) -> Result<Vec<u8>> {
+    if tiles.len() > 256 {
+        bail!(
+            "indexed images only support 256 colors but there are {} tiles",
+            tiles.len()
+        );
+    }
+    let (width, height) = (
+        terrain.width.max(0) as usize,
+        terrain.height.max(0) as usize,
+    );
+    let mut indexes = Vec::with_capacity(width * height);
+    for row in (0..height).rev() {
+        for col in 0..width {
+            let tid = terrain
+                .terrains
+                .get(tile_index(col, row))
+                .map(|tid| tid.as_str())
+                .unwrap_or_default();
+            let index = tiles
+                .get_index_of(tid)
+                .ok_or_else(|| anyhow!("unknown TID '{}' at ({}, {})", tid, col, row))?;
+            indexes.push(index as u8);
+        }
+    }
+    let palette: Vec<u8> = tiles.values().flat_map(tile_color).collect();
+
+    let mut output = vec![];
+    let mut encoder = png::Encoder::new(&mut output, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&indexes)?;
+    writer.finish()?;
+    Ok(output)
+}
+
+/// Replace the map with an image like the ones written by [terrain_to_png].
+/// Indexed images that kept the exported palette map by palette entry, so tiles that share
+/// a color stay distinct. Anything else maps by color to the first tile in `tiles` with that color.
+/// The map is left untouched if any pixel doesn't match a tile.
+pub fn terrain_from_png(
+    terrain: &mut TerrainData,
+    png: &[u8],
+    tiles: &IndexMap<String, Tile>,
+) -> Result<()> {
+    let mut decoder = png::Decoder::new(png);
+    decoder.set_transformations(png::Transformations::IDENTITY);
+    let mut reader = decoder.read_info().context("failed to read image")?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut buffer)
+        .context("failed to read image")?;
+    let (width, height) = (frame.width as usize, frame.height as usize);
+
+    // Palette entries that still match the exported palette resolve to their tile directly.
+    let mut indexed: Option<Vec<Option<usize>>> = None;
+    if frame.color_type == png::ColorType::Indexed {
+        if let Some(palette) = reader.info().palette.as_ref() {
+            let depth = frame.bit_depth as usize;
+            let entries: Vec<Option<usize>> = palette
+                .chunks(3)
+                .enumerate()
+                .map(|(index, color)| {
+                    let tile = tiles.get_index(index).map(|(_, tile)| tile_color(tile));
+                    if tile.as_ref().map(|tile| tile.as_slice()) == Some(color) {
+                        Some(index)
+                    } else {
+                        tiles.values().position(|tile| tile_color(tile) == color)
+                    }
+                })
+                .collect();
+            let mut pixels = Vec::with_capacity(width * height);
+            for line in buffer[..frame.buffer_size()].chunks(frame.line_size) {
+                for x in 0..width {
+                    let bit = x * depth;
+                    let value =
+                        (line[bit / 8] >> (8 - depth - bit % 8)) & ((1u16 << depth) - 1) as u8;
+                    pixels.push(entries.get(value as usize).copied().flatten());
+                }
+            }
+            indexed = Some(pixels);
+        }
+    }
+    let pixels = match indexed {
+        Some(pixels) => pixels,
+        None => image::load_from_memory(png)
+            .context("failed to read image")?
+            .to_rgb8()
+            .pixels()
+            .map(|pixel| tiles.values().position(|tile| tile_color(tile) == pixel.0))
+            .collect(),
+    };
+
+    let mut rows = vec![];
+    for (y, line) in pixels.chunks(width).enumerate() {
+        let mut row = vec![];
+        for (x, index) in line.iter().enumerate() {
+            let (tid, _) = index
+                .and_then(|index| tiles.get_index(index))
+                .ok_or_else(|| {
+                    anyhow!("pixel ({}, {}) doesn't match the color of any tile", x, y)
+                })?;
+            row.push(tid.clone());
+        }
+        rows.push(row);
+    }
+    set_grid(terrain, rows)
+}
@@ -0,0 +1,35 @@
+use std::ops::RangeInclusive;
+
+use astra_types::Job;
+
+/// Every weapon item kind, from swords to special weapons, in the same order as the
+/// `weapon_*` fields of [Job].
+pub const WEAPON_KINDS: RangeInclusive<i8> = 1..=9;
+
+/// Staves heal instead of attacking.
+pub const STAFF_KIND: i8 = 7;
+
+/// Dragonstones, monster attacks and other weapons that aren't sold or found.
+pub const SPECIAL_KIND: i8 = 9;
+
+/// Whether items of this kind can attack.
+pub fn is_attack_kind(kind: i8) -> bool {
+    WEAPON_KINDS.contains(&kind) && kind != STAFF_KIND
+}
+
+/// The class's rank in an item kind, or 0 if it can't use it.
+pub fn weapon_rank(job: &Job, kind: i8) -> i8 {
+    match kind {
+        1 => job.weapon_sword,
+        2 => job.weapon_lance,
+        3 => job.weapon_axe,
+        4 => job.weapon_bow,
+        5 => job.weapon_dagger,
+        6 => job.weapon_magic,
+        7 => job.weapon_rod,
+        8 => job.weapon_fist,
+        9 => job.weapon_special,
+        10 => job.weapon_tool,
+        _ => 0,
+    }
+}
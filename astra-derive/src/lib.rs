@@ -1,7 +1,43 @@
+use std::collections::HashSet;
+
 use darling::FromField;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Fields, Item};
+use syn::{parse_macro_input, Fields, GenericArgument, Item, PathArguments, Type};
+
+/// Must match `astra_types::normalize_patch_name`.
+fn normalize_patch_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '@')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The innermost type of a sheet, ex. `Person` for `Sheet<IndexMap<String, Person>>`.
+fn row_type_name(ty: &Type) -> Option<String> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(inner)) = args.args.last() {
+            return row_type_name(inner);
+        }
+    }
+    Some(segment.ident.to_string())
+}
+
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Vec")
+            .unwrap_or_default(),
+        _ => false,
+    }
+}
 
 #[derive(Debug, FromField)]
 #[darling(attributes(astra))]
@@ -43,6 +79,10 @@ pub fn astra(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut field_options = vec![];
     let mut public_array_entry = quote! {};
     let mut unique_book_entry = quote! {};
+    let mut patch_names = HashSet::new();
+    let mut field_setters = vec![];
+    let mut field_appenders = vec![];
+    let mut id_setter = quote! { let _ = key; };
     for f in &fields.named {
         let options = match FieldOptions::from_field(f) {
             Ok(options) => options,
@@ -64,6 +104,14 @@ pub fn astra(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             };
         }
         if options.id {
+            id_setter = quote! {
+                let mut row = row;
+                if let Some(key) = key {
+                    row.#ident = astra_formats::FromSheetParamAttribute::from_sheet_param_attribute(
+                        key.to_string()
+                    )?;
+                }
+            };
             unique_book_entry = quote! {
                 impl #impl_generics astra_formats::UniqueBookEntry for #name #ty_generics #where_clause {
                     fn get_id(&self) -> &str {
@@ -77,12 +125,53 @@ pub fn astra(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 .ok_or_else(|| astra_formats::error::anyhow!("expected value for '{}'", #key))?;
             let #ident = astra_formats::FromSheetParamAttribute::from_sheet_param_attribute(raw_value)?;
         });
+        let names: Vec<String> = [ident.to_string(), key.clone()]
+            .iter()
+            .map(|name| normalize_patch_name(name))
+            .filter(|name| patch_names.insert(name.clone()))
+            .collect();
+        if !names.is_empty() {
+            field_setters.push(quote! {
+                #(#names)|* => {
+                    self.#ident = astra_formats::FromSheetParamAttribute::from_sheet_param_attribute(value)?;
+                }
+            });
+            if is_vec(&f.ty) {
+                field_appenders.push(quote! {
+                    #(#names)|* => {
+                        self.#ident.push(astra_formats::FromSheetParamAttribute::from_sheet_param_attribute(value)?);
+                    }
+                });
+            }
+        }
         initializers.push(quote! { #ident, });
         setters.push(quote! {
             map.insert(#key.to_string(), self.#ident.to_sheet_param_attribute());
         });
         field_options.push(options);
     }
+    let blank_fields: Vec<_> = fields
+        .named
+        .iter()
+        .map(|f| {
+            let ident = f.ident.as_ref().unwrap();
+            quote! { #ident: Default::default(), }
+        })
+        .collect();
+    let append_body = if field_appenders.is_empty() {
+        quote! {
+            let _ = value;
+            Err(astra_formats::error::anyhow!("'{}' is not a list field", field))
+        }
+    } else {
+        quote! {
+            match crate::normalize_patch_name(field).as_str() {
+                #(#field_appenders)*
+                _ => return Err(astra_formats::error::anyhow!("'{}' is not a list field", field)),
+            }
+            Ok(())
+        }
+    };
 
     quote! {
         impl #impl_generics astra_formats::FromSheetDataParam for #name #ty_generics #where_clause {
@@ -107,6 +196,85 @@ pub fn astra(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             }
         }
 
+        impl #impl_generics crate::DiffableRow for #name #ty_generics #where_clause {
+            fn diff_values(&self) -> astra_formats::indexmap::IndexMap<String, String> {
+                astra_formats::ToSheetDataParam::to_sheet_data_param_values(self)
+                    .into_iter()
+                    .map(|(key, value)| (key.trim_start_matches('@').to_string(), value))
+                    .collect()
+            }
+
+            fn copy_row(&self) -> astra_formats::error::Result<Self> {
+                astra_formats::FromSheetDataParam::from_sheet_data_param(
+                    astra_formats::ToSheetDataParam::to_sheet_data_param_values(self)
+                )
+            }
+        }
+
+        impl #impl_generics crate::PatchableRow for #name #ty_generics #where_clause {
+            fn patch_values(&self) -> astra_formats::indexmap::IndexMap<String, String> {
+                astra_formats::ToSheetDataParam::to_sheet_data_param_values(self)
+            }
+
+            fn set_field(&mut self, field: &str, value: String) -> astra_formats::error::Result<()> {
+                match crate::normalize_patch_name(field).as_str() {
+                    #(#field_setters)*
+                    _ => return Err(astra_formats::error::anyhow!("unknown field '{}'", field)),
+                }
+                Ok(())
+            }
+
+            fn append_field(&mut self, field: &str, value: String) -> astra_formats::error::Result<()> {
+                #append_body
+            }
+        }
+
+        impl #impl_generics crate::PatchNode for #name #ty_generics #where_clause {
+            fn row(&self, keys: &[String]) -> astra_formats::error::Result<&dyn crate::PatchableRow> {
+                match keys.first() {
+                    Some(key) => Err(astra_formats::error::anyhow!("unexpected key '{}' after row key", key)),
+                    None => Ok(self),
+                }
+            }
+
+            fn row_mut(&mut self, keys: &[String]) -> astra_formats::error::Result<&mut dyn crate::PatchableRow> {
+                match keys.first() {
+                    Some(key) => Err(astra_formats::error::anyhow!("unexpected key '{}' after row key", key)),
+                    None => Ok(self),
+                }
+            }
+
+            fn insert_row(
+                &mut self,
+                _keys: &[String],
+                _base: Option<astra_formats::indexmap::IndexMap<String, String>>,
+            ) -> astra_formats::error::Result<&mut dyn crate::PatchableRow> {
+                Err(astra_formats::error::anyhow!("cannot insert a row into another row"))
+            }
+
+            fn remove_row(&mut self, _keys: &[String]) -> astra_formats::error::Result<()> {
+                Err(astra_formats::error::anyhow!("cannot remove a row from another row"))
+            }
+
+            fn is_row() -> bool {
+                true
+            }
+
+            fn create(
+                key: Option<&str>,
+                base: Option<astra_formats::indexmap::IndexMap<String, String>>,
+            ) -> astra_formats::error::Result<Self> {
+                let row: Self = match base {
+                    Some(values) => astra_formats::FromSheetDataParam::from_sheet_data_param(values)?,
+                    None => Self {
+                        #(#blank_fields)*
+                    },
+                };
+                #id_setter
+                Ok(row)
+            }
+        }
+
         #public_array_entry
 
         #unique_book_entry
@@ -142,8 +310,37 @@ pub fn astra_book(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut from_sheet_conversions = vec![];
     let mut ref_to_sheet_conversions = vec![];
     let mut to_sheet_conversions = vec![];
+    let mut sheet_diffs = vec![];
+    let mut sheet_pulls = vec![];
+    let mut patch_sheets = vec![];
+    let mut patch_sheets_mut = vec![];
     for f in &fields.named {
         let ident = f.ident.as_ref().unwrap();
+        let sheet_name = ident.to_string();
+        let patch_names: Vec<String> = [Some(sheet_name.clone()), row_type_name(&f.ty)]
+            .into_iter()
+            .flatten()
+            .map(|name| normalize_patch_name(&name))
+            .collect();
+        patch_sheets.push(quote! {
+            if [#(#patch_names),*].contains(&sheet.as_str()) {
+                return Some(&self.#ident.data);
+            }
+        });
+        patch_sheets_mut.push(quote! {
+            if [#(#patch_names),*].contains(&sheet.as_str()) {
+                return Some(&mut self.#ident.data);
+            }
+        });
+        sheet_diffs.push(quote! {
+            sheets.push(crate::SheetDiff {
+                sheet: #sheet_name,
+                rows: crate::DiffableSheet::diff_rows(&self.#ident.data, &theirs.#ident.data),
+            });
+        });
+        sheet_pulls.push(quote! {
+            #sheet_name => crate::DiffableSheet::pull_row(&mut self.#ident.data, &theirs.#ident.data, key),
+        });
         from_sheet_conversions.push(quote! {
             #ident: value.sheets
                 .pop()
@@ -211,6 +408,35 @@ pub fn astra_book(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 }
             }
         }
+
+        impl #impl_generics crate::DiffableBook for #name #ty_generics #where_clause {
+            fn diff(&self, theirs: &Self) -> Vec<crate::SheetDiff> {
+                let mut sheets = vec![];
+                #(#sheet_diffs)*
+                sheets
+            }
+
+            fn pull_row(&mut self, theirs: &Self, sheet: &str, key: &str) -> astra_formats::error::Result<bool> {
+                match sheet {
+                    #(#sheet_pulls)*
+                    _ => Err(astra_formats::error::anyhow!("unknown sheet '{}'", sheet)),
+                }
+            }
+        }
+
+        impl #impl_generics crate::PatchableBook for #name #ty_generics #where_clause {
+            fn patch_sheet(&self, sheet: &str) -> Option<&dyn crate::PatchNode> {
+                let sheet = crate::normalize_patch_name(sheet);
+                #(#patch_sheets)*
+                None
+            }
+
+            fn patch_sheet_mut(&mut self, sheet: &str) -> Option<&mut dyn crate::PatchNode> {
+                let sheet = crate::normalize_patch_name(sheet);
+                #(#patch_sheets_mut)*
+                None
+            }
+        }
     }
     .into()
 }
@@ -82,7 +82,7 @@ pub struct Chapter {
     pub net_ranking_index: u8,
 }
 
-#[derive(Astra, Debug)]
+#[derive(Astra, Debug, Default, Clone)]
 pub struct Challenge {
     #[astra(key = "@Cid")]
     pub cid: String,
@@ -0,0 +1,183 @@
+use astra_formats::error::{anyhow, Result};
+use astra_formats::indexmap::IndexMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowDiffKind {
+    /// The row only exists in the other project.
+    Added,
+    /// The row only exists in this project.
+    Removed,
+    /// The row exists in both projects with different values.
+    Changed,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldDiff {
+    pub field: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RowDiff {
+    pub key: String,
+    pub kind: RowDiffKind,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SheetDiff {
+    pub sheet: &'static str,
+    pub rows: Vec<RowDiff>,
+}
+
+/// A row that can be compared field by field. Implemented by `#[derive(Astra)]`.
+/// Groups (`Vec<T>`) count as a single row so grouped sheets are pulled a group at a time.
+pub trait DiffableRow: Sized {
+    fn diff_values(&self) -> IndexMap<String, String>;
+
+    /// Copy the row without requiring [Clone], which not every row type implements.
+    fn copy_row(&self) -> Result<Self>;
+}
+
+impl<T> DiffableRow for Vec<T>
+where
+    T: DiffableRow,
+{
+    fn diff_values(&self) -> IndexMap<String, String> {
+        let mut values = IndexMap::new();
+        for (index, row) in self.iter().enumerate() {
+            for (field, value) in row.diff_values() {
+                values.insert(format!("[{}] {}", index, field), value);
+            }
+        }
+        values
+    }
+
+    fn copy_row(&self) -> Result<Self> {
+        self.iter().map(DiffableRow::copy_row).collect()
+    }
+}
+
+/// Row-level comparison between the same sheet from two projects.
+pub trait DiffableSheet {
+    fn diff_rows(&self, theirs: &Self) -> Vec<RowDiff>;
+
+    /// Replace the row identified by `key` with the version from `theirs`.
+    /// Rows missing from `theirs` are removed. Returns true if anything changed.
+    ///
+    /// List sheets are keyed by index, so rows can only be added or removed at the end:
+    /// pull removals from the last row backwards and additions from the first row forwards.
+    fn pull_row(&mut self, theirs: &Self, key: &str) -> Result<bool>;
+}
+
+/// Sheet-by-sheet comparison between two copies of a book. Implemented by `#[derive(AstraBook)]`.
+pub trait DiffableBook {
+    fn diff(&self, theirs: &Self) -> Vec<SheetDiff>;
+
+    fn pull_row(&mut self, theirs: &Self, sheet: &str, key: &str) -> Result<bool>;
+}
+
+fn diff_row<T: DiffableRow>(key: String, ours: Option<&T>, theirs: Option<&T>) -> Option<RowDiff> {
+    let kind = match (ours, theirs) {
+        (Some(_), Some(_)) => RowDiffKind::Changed,
+        (Some(_), None) => RowDiffKind::Removed,
+        (None, Some(_)) => RowDiffKind::Added,
+        (None, None) => return None,
+    };
+    let ours = ours.map(|row| row.diff_values()).unwrap_or_default();
+    let mut theirs = theirs.map(|row| row.diff_values()).unwrap_or_default();
+    let mut fields = vec![];
+    for (field, ours) in ours {
+        let theirs = theirs.shift_remove(&field).unwrap_or_default();
+        if ours != theirs {
+            fields.push(FieldDiff {
+                field,
+                ours,
+                theirs,
+            });
+        }
+    }
+    fields.extend(theirs.into_iter().map(|(field, theirs)| FieldDiff {
+        field,
+        ours: String::new(),
+        theirs,
+    }));
+    (kind != RowDiffKind::Changed || !fields.is_empty()).then_some(RowDiff { key, kind, fields })
+}
+
+impl<T> DiffableSheet for IndexMap<String, T>
+where
+    T: DiffableRow,
+{
+    fn diff_rows(&self, theirs: &Self) -> Vec<RowDiff> {
+        let mut rows: Vec<RowDiff> = self
+            .iter()
+            .filter_map(|(key, ours)| diff_row(key.clone(), Some(ours), theirs.get(key)))
+            .collect();
+        rows.extend(
+            theirs
+                .iter()
+                .filter(|(key, _)| !self.contains_key(*key))
+                .filter_map(|(key, row)| diff_row(key.clone(), None, Some(row))),
+        );
+        rows
+    }
+
+    fn pull_row(&mut self, theirs: &Self, key: &str) -> Result<bool> {
+        match theirs.get_index_of(key) {
+            Some(index) => {
+                let row = theirs[index].copy_row()?;
+                match self.get_mut(key) {
+                    Some(existing) => *existing = row,
+                    None => {
+                        // Try to land the row where the other project has it.
+                        self.insert(key.to_string(), row);
+                        let target = index.min(self.len() - 1);
+                        self.move_index(self.len() - 1, target);
+                    }
+                }
+                Ok(true)
+            }
+            None => Ok(self.shift_remove(key).is_some()),
+        }
+    }
+}
+
+impl<T> DiffableSheet for Vec<T>
+where
+    T: DiffableRow,
+{
+    fn diff_rows(&self, theirs: &Self) -> Vec<RowDiff> {
+        (0..self.len().max(theirs.len()))
+            .filter_map(|index| diff_row(index.to_string(), self.get(index), theirs.get(index)))
+            .collect()
+    }
+
+    fn pull_row(&mut self, theirs: &Self, key: &str) -> Result<bool> {
+        let index: usize = key.parse()?;
+        match theirs.get(index) {
+            Some(row) if index < self.len() => self[index] = row.copy_row()?,
+            Some(row) if index == self.len() => self.push(row.copy_row()?),
+            Some(_) => {
+                return Err(anyhow!(
+                    "row {} can't be added before row {}",
+                    index,
+                    self.len()
+                ))
+            }
+            None if index + 1 == self.len() => {
+                self.pop();
+            }
+            None if index < self.len() => {
+                return Err(anyhow!(
+                    "row {} can't be removed before row {}",
+                    index,
+                    self.len() - 1
+                ))
+            }
+            None => return Ok(false),
+        }
+        Ok(true)
+    }
+}
@@ -9,6 +9,7 @@ mod calculator;
 mod chapter;
 mod chart;
 mod cook;
+mod diff;
 mod dispos;
 mod dragon_ride;
 mod effect;
@@ -32,6 +33,7 @@ mod movie;
 mod muscle_exercise;
 mod music;
 mod param;
+mod patch;
 mod person;
 mod photograph;
 mod profile_card;
@@ -58,6 +60,7 @@ pub use calculator::*;
 pub use chapter::*;
 pub use chart::*;
 pub use cook::*;
+pub use diff::*;
 pub use dispos::*;
 pub use dragon_ride::*;
 pub use effect::*;
@@ -81,6 +84,7 @@ pub use movie::*;
 pub use muscle_exercise::*;
 pub use music::*;
 pub use param::*;
+pub use patch::*;
 pub use person::*;
 pub use photograph::*;
 pub use profile_card::*;
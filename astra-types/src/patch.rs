@@ -0,0 +1,211 @@
+use astra_formats::error::{anyhow, Result};
+use astra_formats::indexmap::IndexMap;
+
+/// Normalize a sheet or field name so recipes can use either the Rust name (`grow_hp`)
+/// or the XML name (`GrowHp` / `@GrowHp`).
+pub fn normalize_patch_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '@')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// A single row that can be edited field by field. Implemented by `#[derive(Astra)]`.
+pub trait PatchableRow {
+    fn patch_values(&self) -> IndexMap<String, String>;
+
+    fn set_field(&mut self, field: &str, value: String) -> Result<()>;
+
+    /// Push a value onto a list field such as `Job.skills`.
+    fn append_field(&mut self, field: &str, value: String) -> Result<()>;
+}
+
+/// A row or a container of rows addressed by a path of keys.
+/// Keyed sheets use the row key, list sheets use the row index and grouped sheets use both.
+pub trait PatchNode {
+    fn row(&self, keys: &[String]) -> Result<&dyn PatchableRow>;
+
+    fn row_mut(&mut self, keys: &[String]) -> Result<&mut dyn PatchableRow>;
+
+    /// Add a new row at `keys`, optionally starting from the values of another row.
+    fn insert_row(
+        &mut self,
+        keys: &[String],
+        base: Option<IndexMap<String, String>>,
+    ) -> Result<&mut dyn PatchableRow>;
+
+    fn remove_row(&mut self, keys: &[String]) -> Result<()>;
+
+    fn is_row() -> bool
+    where
+        Self: Sized;
+
+    /// Build an empty node. Rows start from `base` (or blank values) with their id set to `key`.
+    fn create(key: Option<&str>, base: Option<IndexMap<String, String>>) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+fn parse_index(key: &str) -> Result<usize> {
+    key.parse()
+        .map_err(|_| anyhow!("expected a row index but found '{}'", key))
+}
+
+impl<T> PatchNode for Vec<T>
+where
+    T: PatchNode,
+{
+    fn row(&self, keys: &[String]) -> Result<&dyn PatchableRow> {
+        let (key, rest) = keys
+            .split_first()
+            .ok_or_else(|| anyhow!("expected a row index"))?;
+        let index = parse_index(key)?;
+        self.get(index)
+            .ok_or_else(|| anyhow!("row index {} is out of bounds", index))?
+            .row(rest)
+    }
+
+    fn row_mut(&mut self, keys: &[String]) -> Result<&mut dyn PatchableRow> {
+        let (key, rest) = keys
+            .split_first()
+            .ok_or_else(|| anyhow!("expected a row index"))?;
+        let index = parse_index(key)?;
+        self.get_mut(index)
+            .ok_or_else(|| anyhow!("row index {} is out of bounds", index))?
+            .row_mut(rest)
+    }
+
+    fn insert_row(
+        &mut self,
+        keys: &[String],
+        base: Option<IndexMap<String, String>>,
+    ) -> Result<&mut dyn PatchableRow> {
+        match keys {
+            [] => {
+                self.push(T::create(None, base)?);
+                self.last_mut().unwrap().row_mut(&[])
+            }
+            [key] if T::is_row() => {
+                let index = parse_index(key)?;
+                if index > self.len() {
+                    return Err(anyhow!("row index {} is out of bounds", index));
+                }
+                self.insert(index, T::create(None, base)?);
+                self[index].row_mut(&[])
+            }
+            [key, rest @ ..] => {
+                let index = parse_index(key)?;
+                self.get_mut(index)
+                    .ok_or_else(|| anyhow!("row index {} is out of bounds", index))?
+                    .insert_row(rest, base)
+            }
+        }
+    }
+
+    fn remove_row(&mut self, keys: &[String]) -> Result<()> {
+        match keys {
+            [] => Err(anyhow!("expected a row index")),
+            [key] => {
+                let index = parse_index(key)?;
+                if index >= self.len() {
+                    return Err(anyhow!("row index {} is out of bounds", index));
+                }
+                self.remove(index);
+                Ok(())
+            }
+            [key, rest @ ..] => {
+                let index = parse_index(key)?;
+                self.get_mut(index)
+                    .ok_or_else(|| anyhow!("row index {} is out of bounds", index))?
+                    .remove_row(rest)
+            }
+        }
+    }
+
+    fn is_row() -> bool {
+        false
+    }
+
+    fn create(_key: Option<&str>, _base: Option<IndexMap<String, String>>) -> Result<Self> {
+        Ok(vec![])
+    }
+}
+
+impl<T> PatchNode for IndexMap<String, T>
+where
+    T: PatchNode,
+{
+    fn row(&self, keys: &[String]) -> Result<&dyn PatchableRow> {
+        let (key, rest) = keys
+            .split_first()
+            .ok_or_else(|| anyhow!("expected a row key"))?;
+        self.get(key)
+            .ok_or_else(|| anyhow!("no row with key '{}'", key))?
+            .row(rest)
+    }
+
+    fn row_mut(&mut self, keys: &[String]) -> Result<&mut dyn PatchableRow> {
+        let (key, rest) = keys
+            .split_first()
+            .ok_or_else(|| anyhow!("expected a row key"))?;
+        self.get_mut(key)
+            .ok_or_else(|| anyhow!("no row with key '{}'", key))?
+            .row_mut(rest)
+    }
+
+    fn insert_row(
+        &mut self,
+        keys: &[String],
+        base: Option<IndexMap<String, String>>,
+    ) -> Result<&mut dyn PatchableRow> {
+        let (key, rest) = keys
+            .split_first()
+            .ok_or_else(|| anyhow!("expected a row key"))?;
+        if T::is_row() {
+            if !rest.is_empty() {
+                return Err(anyhow!("unexpected key '{}' after row key", rest[0]));
+            }
+            if self.contains_key(key) {
+                return Err(anyhow!("a row with key '{}' already exists", key));
+            }
+            self.insert(key.clone(), T::create(Some(key), base)?);
+            return self.get_mut(key).unwrap().row_mut(&[]);
+        }
+        // Groups are created on demand so rows can be appended to new groups.
+        if !self.contains_key(key) {
+            self.insert(key.clone(), T::create(Some(key), None)?);
+        }
+        self.get_mut(key).unwrap().insert_row(rest, base)
+    }
+
+    fn remove_row(&mut self, keys: &[String]) -> Result<()> {
+        match keys {
+            [] => Err(anyhow!("expected a row key")),
+            [key] => {
+                self.shift_remove(key)
+                    .ok_or_else(|| anyhow!("no row with key '{}'", key))?;
+                Ok(())
+            }
+            [key, rest @ ..] => self
+                .get_mut(key)
+                .ok_or_else(|| anyhow!("no row with key '{}'", key))?
+                .remove_row(rest),
+        }
+    }
+
+    fn is_row() -> bool {
+        false
+    }
+
+    fn create(_key: Option<&str>, _base: Option<IndexMap<String, String>>) -> Result<Self> {
+        Ok(IndexMap::new())
+    }
+}
+
+/// Look up sheets by name for recipes. Implemented by `#[derive(AstraBook)]`.
+/// Sheets match on either the field name (`persons`) or the row type (`Person`).
+pub trait PatchableBook {
+    fn patch_sheet(&self, sheet: &str) -> Option<&dyn PatchNode>;
+
+    fn patch_sheet_mut(&mut self, sheet: &str) -> Option<&mut dyn PatchNode>;
+}
@@ -5,6 +5,9 @@ use crate::{
     first_run, main_window, project_creator, project_loader, project_selector, AppConfig, AppState,
 };
 
+/// The main UI font. Exported maps use it too.
+pub const UI_FONT: &[u8] = include_bytes!("../assets/NotoSans-Regular.ttf");
+
 pub struct AstraApp {
     pub config: AppConfig,
     pub state: AppState,
@@ -22,10 +25,9 @@ impl AstraApp {
         }
 
         let mut font_definitions = FontDefinitions::default();
-        font_definitions.font_data.insert(
-            "noto_sans".to_owned(),
-            FontData::from_static(include_bytes!("../assets/NotoSans-Regular.ttf")),
-        );
+        font_definitions
+            .font_data
+            .insert("noto_sans".to_owned(), FontData::from_static(UI_FONT));
         font_definitions.font_data.insert(
             "noto_sans_jp".to_owned(),
             FontData::from_static(include_bytes!("../assets/NotoSansJP-Regular.otf")),
@@ -0,0 +1,89 @@
+use egui::{CentralPanel, ComboBox, Grid, ScrollArea};
+
+use crate::{
+    queue_transition, validate_audio, AudioIssue, AudioReferenceKind, EditorState, Transition,
+};
+
+const KINDS: [AudioReferenceKind; 3] = [
+    AudioReferenceKind::Music,
+    AudioReferenceKind::SoundEvent,
+    AudioReferenceKind::Movie,
+];
+
+/// Audio fields naming music, sound events or movies that don't exist.
+#[derive(Default)]
+pub struct AudioCheckScreen {
+    issues: Option<Vec<AudioIssue>>,
+    kind: Option<AudioReferenceKind>,
+}
+
+impl AudioCheckScreen {
+    pub fn ui(&mut self, ctx: &egui::Context, state: &EditorState) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Audio Check");
+            ui.horizontal(|ui| {
+                if ui.button("Check").clicked() {
+                    self.issues = Some(validate_audio(state));
+                }
+                ComboBox::from_id_source("audio_check_kind")
+                    .selected_text(self.kind.map_or("All References", |kind| kind.label()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.kind, None, "All References");
+                        for kind in KINDS {
+                            ui.selectable_value(&mut self.kind, Some(kind), kind.label());
+                        }
+                    });
+            });
+            ui.label(
+                "Music fields are checked against the music table. Sound events are checked \
+                against the sound event and music tables, so events that only exist in the \
+                game's sound banks are listed too.",
+            );
+            ui.separator();
+
+            let Some(issues) = &self.issues else {
+                ui.label("Press Check to look for broken references.");
+                return;
+            };
+            let issues: Vec<&AudioIssue> = issues
+                .iter()
+                .filter(|issue| self.kind.map_or(true, |kind| issue.kind == kind))
+                .collect();
+            if issues.is_empty() {
+                ui.label("No broken references found.");
+                return;
+            }
+            ui.label(format!("{} unresolved name(s)", issues.len()));
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    Grid::new("audio_check_issues")
+                        .striped(true)
+                        .num_columns(5)
+                        .show(ui, |ui| {
+                            for header in ["Expected", "Row", "Field", "Value", ""] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for issue in issues {
+                                ui.label(issue.kind.label());
+                                ui.label(&issue.row);
+                                ui.label(issue.field);
+                                ui.colored_label(ui.visuals().error_fg_color, &issue.value);
+                                match issue.screen {
+                                    Some(screen) => {
+                                        if ui.button("Open").clicked() {
+                                            queue_transition(Transition::new(screen, issue.index));
+                                        }
+                                    }
+                                    None => {
+                                        ui.label("");
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+}
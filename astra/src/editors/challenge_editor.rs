@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+use astra_types::{Challenge, Chapter, ChapterBook};
+use egui::{ComboBox, Response, Ui};
+use indexmap::IndexMap;
+
+use crate::{
+    editable_list, id_field, model_drop_down, msbt_key_value_singleline, sheet_retriever,
+    standard_keyed_display, CacheItem, CachedView, ChapterSheetRetriever, EditorState,
+    ListEditorContent, PropertyGrid, ViewItem,
+};
+
+sheet_retriever!(Challenge, ChapterBook, challenges, Vec<Challenge>);
+
+impl ViewItem for Challenge {
+    type Dependencies = EditorState;
+
+    fn text(&self, dependencies: &Self::Dependencies) -> Cow<'_, str> {
+        standard_keyed_display!(self, dependencies, cid)
+    }
+}
+
+/// Chapter picker that flags CIDs missing from the chapter sheet.
+fn chapter_picker(
+    ui: &mut Ui,
+    chapters: &IndexMap<String, CacheItem<Chapter>>,
+    cid: &mut String,
+) -> Response {
+    ui.horizontal(|ui| {
+        let response = ui.add(model_drop_down(chapters, &(), cid));
+        if !cid.is_empty() && !chapters.contains_key(cid.as_str()) {
+            ui.colored_label(ui.visuals().error_fg_color, "Unknown chapter");
+        }
+        response
+    })
+    .inner
+}
+
+/// Picker over the reward tables in the item book.
+fn reward_picker(ui: &mut Ui, state: &EditorState, reward: &mut String) -> Response {
+    state.reward_data.read(|data| {
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            let mut response = ComboBox::from_id_source("challenge_reward")
+                .selected_text(reward.as_str())
+                .width(200.)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(reward.is_empty(), "None").clicked() {
+                        reward.clear();
+                        changed = true;
+                    }
+                    for key in data.keys() {
+                        if ui.selectable_label(reward == key, key).clicked() {
+                            reward.clone_from(key);
+                            changed = true;
+                        }
+                    }
+                })
+                .response;
+            if changed {
+                response.mark_changed();
+            }
+            if !reward.is_empty() && !data.contains_key(reward.as_str()) {
+                ui.colored_label(ui.visuals().error_fg_color, "Unknown reward table");
+            }
+            response
+        })
+        .inner
+    })
+}
+
+pub struct ChallengeEditor {
+    challenge: ChallengeSheet,
+    content: ListEditorContent<Vec<Challenge>, Challenge, EditorState>,
+
+    // Must use a cached view of chapters since they are contained in the same book.
+    cache: CachedView<ChapterSheetRetriever, ChapterBook, Chapter>,
+}
+
+impl ChallengeEditor {
+    pub fn new(state: &EditorState) -> Self {
+        Self {
+            challenge: state.challenge.clone(),
+            content: ListEditorContent::new("challenge_editor"),
+            cache: CachedView::new(state.chapter.clone(), state),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, state: &EditorState) {
+        self.cache.refresh(state);
+        self.content.left_panel(ctx, &self.challenge, state);
+
+        let chapters = self.cache.get();
+        self.challenge.write(|data| {
+            self.content.content(ctx, data, |ui, selection| {
+                PropertyGrid::new("challenge", selection)
+                    .new_section("Core")
+                    .field("CID", |ui, d| ui.add(id_field(&mut d.cid)))
+                    .field("Name", |ui, d| {
+                        msbt_key_value_singleline!(ui, state, "gamedata", d.name)
+                    })
+                    .field("Unlock CID", |ui, d| {
+                        chapter_picker(ui, chapters, &mut d.unlock_cid)
+                    })
+                    .default_field("Sortie Count", |d| &mut d.sortie_count)
+                    .field("Reward", |ui, d| reward_picker(ui, state, &mut d.reward))
+                    .new_section("Stages")
+                    .field("Stage 1", |ui, d| {
+                        ui.add(editable_list(&mut d.stage_1, |_, cid, ui| {
+                            chapter_picker(ui, chapters, cid)
+                        }))
+                    })
+                    .field("Stage 2", |ui, d| {
+                        ui.add(editable_list(&mut d.stage_2, |_, cid, ui| {
+                            chapter_picker(ui, chapters, cid)
+                        }))
+                    })
+                    .field("Stage 3", |ui, d| {
+                        ui.add(editable_list(&mut d.stage_3, |_, cid, ui| {
+                            chapter_picker(ui, chapters, cid)
+                        }))
+                    })
+                    .show(ui)
+                    .changed()
+            })
+        });
+    }
+}
@@ -1,27 +1,40 @@
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::Arc;
 
-use astra_core::{Astra, OpenTerrain};
+use astra_core::error::Result;
+use astra_core::{
+    chapter_file_name, move_spawns, resize_terrain, shift_terrain, spawn_items, spawn_items_mut,
+    terrain_from_csv, terrain_from_png, terrain_to_csv, terrain_to_png, Astra, Difficulty,
+    MapCoordinates, MapRenderOptions, OpenTerrain,
+};
 use astra_types::{Chapter, ChapterBook, Spawn, TerrainData};
 use egui::{
-    Button, CentralPanel, ComboBox, DragValue, SidePanel, Slider, TopBottomPanel, Ui,
+    Button, CentralPanel, CollapsingHeader, ComboBox, DragValue, Grid, Response, ScrollArea, Sense,
+    SidePanel, Slider, TopBottomPanel, Ui,
 };
 use egui_extras::{Size, StripBuilder};
 use egui_modal::{Icon, Modal};
 use indexmap::IndexMap;
 use parking_lot::RwLock;
+use rfd::FileDialog;
 
+use crate::app::UI_FONT;
 use crate::widgets::{
-    bitgrid_i32, bitgrid_u16, chapter_encount_type, chapter_spot_state,
-    force_drop_down, id_field, keyed_add_modal_content, TerrainBrush,
+    bitgrid_i32, bitgrid_u16, chapter_encount_type, chapter_spot_state, force_drop_down, id_field,
+    keyed_add_modal_content, TerrainBrush, TerrainClipboard, TerrainSymmetry, TerrainTools,
 };
 use crate::{
-    blank_slate, dispos_grid, editor_tab_strip, indexed_model_drop_down, model_drop_down,
-    msbt_key_value_singleline, terrain_grid, AppConfig, CacheItem, CachedView, ChapterSheet,
-    ChapterSheetRetriever, EditorState, GroupEditorContent, ListEditorContent, PropertyGrid,
-    SheetHandle, SpawnSheet, SpawnSheetRetriever,
+    blank_slate, chapter_balance, compare_difficulties, danger_zone, dispos_grid, editor_tab_strip,
+    effective_stats, indexed_model_drop_down, model_drop_down, msbt_key_value_singleline,
+    reinforcement_timeline, scan_reinforcements, spawn_movement_range, spawn_tile, terrain_grid,
+    validate_all_dispos, validate_dispos, AppConfig, ArrivalTiming, CacheItem, CachedValue,
+    CachedView, ChapterBalance, ChapterIssues, ChapterSheet, ChapterSheetRetriever,
+    DisposGridOptions, DisposIssue, DisposSelection, EditorState, GroupEditorContent,
+    ListEditorContent, PropertyGrid, ScriptedSpawn, SheetHandle, SpawnComparison, SpawnSheet,
+    SpawnSheetRetriever, Tile, ViewItem, EFFECTIVENESS_LABELS, STANDARD_STAT_LABELS,
+    WEAPON_TYPE_LABELS,
 };
 
 const CHAPTER_FLAG_LABELS: &[&str] = &[
@@ -43,7 +56,16 @@ const CHAPTER_FLAG_LABELS: &[&str] = &[
     "Can Slope",
 ];
 
-const SPAWN_FLAG_LABELS: &[&str] = &[
+const SPAWN_ITEM_LABELS: [(&str, &str); 6] = [
+    ("Item 1", "Item 1 (Drop)"),
+    ("Item 2", "Item 2 (Drop)"),
+    ("Item 3", "Item 3 (Drop)"),
+    ("Item 4", "Item 4 (Drop)"),
+    ("Item 5", "Item 5 (Drop)"),
+    ("Item 6", "Item 6 (Drop)"),
+];
+
+pub const SPAWN_FLAG_LABELS: &[&str] = &[
     "Normal",
     "Hard",
     "Lunatic",
@@ -61,9 +83,90 @@ const SPAWN_FLAG_LABELS: &[&str] = &[
 enum Tab {
     Core,
     Dispos,
+    Difficulties,
+    Balance,
     Terrain,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TerrainTransform {
+    Resize(i32, i32),
+    Shift(i32, i32),
+}
+
+/// A group of spawn fields that can be copied onto every selected spawn at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BulkSpawnEdit {
+    Levels,
+    Items,
+    Ai,
+    SetFlags,
+    AddFlags,
+    RemoveFlags,
+}
+
+impl BulkSpawnEdit {
+    fn apply(self, template: &Spawn, spawn: &mut Spawn) {
+        match self {
+            BulkSpawnEdit::Levels => {
+                spawn.level_n = template.level_n;
+                spawn.level_h = template.level_h;
+                spawn.level_l = template.level_l;
+            }
+            BulkSpawnEdit::Items => {
+                for ((iid, drop), (template_iid, template_drop)) in spawn_items_mut(spawn)
+                    .into_iter()
+                    .zip(spawn_items(template))
+                {
+                    *iid = template_iid.clone();
+                    *drop = template_drop;
+                }
+            }
+            BulkSpawnEdit::Ai => {
+                spawn.ai_action_name = template.ai_action_name.clone();
+                spawn.ai_action_val = template.ai_action_val.clone();
+                spawn.ai_mind_name = template.ai_mind_name.clone();
+                spawn.ai_mind_val = template.ai_mind_val.clone();
+                spawn.ai_attack_name = template.ai_attack_name.clone();
+                spawn.ai_attack_val = template.ai_attack_val.clone();
+                spawn.ai_move_name = template.ai_move_name.clone();
+                spawn.ai_move_val = template.ai_move_val.clone();
+            }
+            BulkSpawnEdit::SetFlags => spawn.flag = template.flag,
+            BulkSpawnEdit::AddFlags => spawn.flag |= template.flag,
+            BulkSpawnEdit::RemoveFlags => spawn.flag &= !template.flag,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TerrainFileAction {
+    ImportCsv,
+    ImportPng,
+    ExportCsv,
+    ExportPng,
+}
+
+/// Settings for a blank terrain map made from the Terrain tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NewTerrainForm {
+    name: String,
+    width: i32,
+    height: i32,
+    fill_tid: String,
+}
+
+impl Default for NewTerrainForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            width: 16,
+            height: 16,
+            fill_tid: String::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DisposKind {
     Main,
@@ -76,12 +179,14 @@ pub enum CoordinateKind {
     Appear,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Difficulty {
-    All,
-    Normal,
-    Hard,
-    Lunatic,
+/// What the dispos grid overlays were computed from. Edits bump the revision numbers.
+#[derive(Clone, PartialEq)]
+struct OverlaySource {
+    dispos_kind: DisposKind,
+    dispos_revision: usize,
+    terrain_revision: usize,
+    coordinate_kind: CoordinateKind,
+    difficulty: Difficulty,
 }
 
 struct OpenChapterState {
@@ -99,18 +204,16 @@ impl OpenChapterState {
         astra: &mut Astra,
         spawn_cache: &mut HashMap<String, SpawnSheet>,
     ) -> Self {
-        let cid_part = chapter.cid.trim_start_matches("CID_");
-        let dispos_stem = chapter.dispos.replace('*', cid_part).to_lowercase();
+        let dispos_stem = chapter_file_name(&chapter.cid, &chapter.dispos);
         let encount_stem = format!("{}e", dispos_stem);
-        let terrain =
-            astra.get_chapter_terrain(&chapter.terrain.replace('*', cid_part).to_lowercase());
+        let terrain = astra.get_chapter_terrain(&chapter_file_name(&chapter.cid, &chapter.terrain));
         Self {
             dispos: load_dispos_sheet(spawn_cache, astra, dispos_stem),
             encount_dispos: load_dispos_sheet(spawn_cache, astra, encount_stem),
             terrain,
-            script: chapter.script_bmap.replace('*', cid_part).to_lowercase(),
-            encount_script: chapter.script_encount.replace('*', cid_part).to_lowercase(),
-            kizuna_script: chapter.script_kizuna.replace('*', cid_part).to_lowercase(),
+            script: chapter_file_name(&chapter.cid, &chapter.script_bmap),
+            encount_script: chapter_file_name(&chapter.cid, &chapter.script_encount),
+            kizuna_script: chapter_file_name(&chapter.cid, &chapter.script_kizuna),
         }
     }
 }
@@ -189,11 +292,48 @@ pub struct ChapterEditor {
     dispos_kind: DisposKind,
     coordinate_kind: CoordinateKind,
     dispos_difficulty: Difficulty,
+    /// Hide units that are set up the same on every difficulty in the comparison tab.
+    differences_only: bool,
+    show_danger_zone: bool,
+    hidden_danger_zone_groups: HashSet<String>,
     hovered_tile: Option<String>,
     hovered_spawn: Option<String>,
+    selected_spawns: Vec<(String, usize)>,
+    /// Values to copy onto every selected spawn, seeded from the primary selection.
+    bulk_spawn: Option<Spawn>,
     script_open_error: Option<String>,
+    map_export_error: Option<String>,
+    /// Results of the last placement check and the dispos book it ran on.
+    dispos_issues: Option<(DisposKind, Vec<DisposIssue>)>,
+    /// Results of the last placement check across every chapter's main dispos.
+    all_dispos_issues: Option<Vec<ChapterIssues>>,
+    /// A chapter and spawn picked from the all chapters check, opened on the next frame.
+    open_issue: Option<(String, Option<(String, usize)>)>,
+    /// The last balance summary and the dispos book and difficulty it covers.
+    balance: Option<(DisposKind, Difficulty, ChapterBalance)>,
+    show_reinforcements: bool,
+    /// Scripted group references and the dispos book they were scanned for.
+    reinforcements: Option<(DisposKind, Vec<ScriptedSpawn>)>,
+    reinforcement_script_error: Option<String>,
+    /// The timeline row whose arrival points are shaded on the grid.
+    selected_arrival: Option<String>,
+    /// Where the primary selected spawn can move.
+    movement_range: CachedValue<(OverlaySource, Option<(String, usize)>), HashSet<Tile>>,
+    /// Tiles enemies outside the hidden groups can attack.
+    danger_zone: CachedValue<(OverlaySource, HashSet<String>), HashSet<Tile>>,
+    /// Units lined up across difficulties, for the dispos book and revision they came from.
+    difficulty_comparisons: CachedValue<(DisposKind, usize), Vec<SpawnComparison>>,
+    terrain_resize: (i32, i32),
+    terrain_shift: (i32, i32),
+    terrain_transform_messages: Vec<String>,
+    terrain_file_error: Option<String>,
+    new_terrain: NewTerrainForm,
+    duplicate_cid: String,
+    /// What [Astra::clone_chapter] copied, shown once the duplicate is selected.
+    duplicate_messages: Vec<String>,
+    duplicate_error: Option<String>,
     selected_chapter_index: Option<usize>,
-    terrain_brush: TerrainBrush,
+    terrain_tools: TerrainTools,
 
     terrain_content: ListEditorContent<IndexMap<String, TerrainData>, TerrainData, EditorState>,
     dispos_content: GroupEditorContent,
@@ -211,11 +351,36 @@ impl ChapterEditor {
             dispos_kind: DisposKind::Main,
             coordinate_kind: CoordinateKind::Dispos,
             dispos_difficulty: Difficulty::All,
+            differences_only: false,
+            show_danger_zone: false,
+            hidden_danger_zone_groups: HashSet::new(),
             hovered_tile: None,
             hovered_spawn: None,
+            selected_spawns: vec![],
+            bulk_spawn: None,
             script_open_error: None,
+            map_export_error: None,
+            dispos_issues: None,
+            all_dispos_issues: None,
+            open_issue: None,
+            balance: None,
+            show_reinforcements: false,
+            reinforcements: None,
+            reinforcement_script_error: None,
+            selected_arrival: None,
+            movement_range: Default::default(),
+            danger_zone: Default::default(),
+            difficulty_comparisons: Default::default(),
+            terrain_resize: (0, 0),
+            terrain_shift: (0, 0),
+            terrain_transform_messages: vec![],
+            terrain_file_error: None,
+            new_terrain: Default::default(),
+            duplicate_cid: String::new(),
+            duplicate_messages: vec![],
+            duplicate_error: None,
             selected_chapter_index: None,
-            terrain_brush: Default::default(),
+            terrain_tools: Default::default(),
 
             terrain_content: ListEditorContent::new("chapter_terrain_list_editor")
                 .with_add_modal_content(keyed_add_modal_content),
@@ -232,8 +397,23 @@ impl ChapterEditor {
         self.selected_chapter_index = index;
     }
 
+    /// Load the selected chapter and drop everything computed for the previous one.
+    fn reload_chapter(&mut self, state: &mut EditorState) {
+        self.loader.load(state, self.selected_chapter_index);
+        self.dispos_issues = None;
+        self.balance = None;
+        self.reinforcements = None;
+        self.selected_arrival = None;
+        self.movement_range.clear();
+        self.danger_zone.clear();
+        self.difficulty_comparisons.clear();
+    }
+
     pub fn show(&mut self, ctx: &egui::Context, state: &mut EditorState, config: &mut AppConfig) {
         self.loader.update();
+        if let Some((cid, spawn)) = self.open_issue.take() {
+            self.open_chapter_spawn(state, &cid, spawn);
+        }
 
         if self.loader.is_loading() {
             CentralPanel::default().show(ctx, |ui| {
@@ -270,10 +450,55 @@ impl ChapterEditor {
                                 false
                             }
                         });
-                        self.loader.load(state, self.selected_chapter_index);
+                        self.reload_chapter(state);
+                    }
+                });
+            });
+
+            let duplicate_modal = Modal::new(ctx, "chapter_duplicate_modal");
+            duplicate_modal.show(|ui| {
+                duplicate_modal.title(ui, "Duplicate Chapter");
+                duplicate_modal.frame(ui, |ui| {
+                    ui.label("Copies the chapter's dispos, terrain, scripts and messages.");
+                    ui.horizontal(|ui| {
+                        ui.label("New CID");
+                        ui.text_edit_singleline(&mut self.duplicate_cid);
+                    });
+                });
+                duplicate_modal.buttons(ui, |ui| {
+                    duplicate_modal.button(ui, "Cancel");
+                    if duplicate_modal.suggested_button(ui, "Duplicate").clicked() {
+                        self.duplicate_chapter(state);
+                    }
+                });
+            });
+        }
+
+        if let Some(error) = self.duplicate_error.clone() {
+            let error_modal = Modal::new(ctx, "chapter_duplicate_error_modal");
+            error_modal.show(|ui| {
+                error_modal.title(ui, "Failed to duplicate chapter");
+                error_modal.body_and_icon(ui, &error, Icon::Error);
+                error_modal.buttons(ui, |ui| {
+                    if error_modal.button(ui, "Close").clicked() {
+                        self.duplicate_error = None;
+                    }
+                });
+            });
+            error_modal.open();
+        }
+        if !self.duplicate_messages.is_empty() {
+            let modal = Modal::new(ctx, "chapter_duplicate_result_modal");
+            modal.show(|ui| {
+                modal.title(ui, "Chapter Duplicated");
+                modal.body_and_icon(ui, self.duplicate_messages.join("\n"), Icon::Info);
+                modal.buttons(ui, |ui| {
+                    if modal.button(ui, "Close").clicked() {
+                        self.duplicate_messages.clear();
                     }
                 });
             });
+            modal.open();
         }
 
         if !matches!(self.loader, ChapterLoader::Loaded(_)) {
@@ -288,6 +513,8 @@ impl ChapterEditor {
         match self.tab {
             Tab::Core => self.core_tab_content(ctx, state, config),
             Tab::Dispos => self.dispos_tab_content(ctx, state, config),
+            Tab::Difficulties => self.difficulties_tab_content(ctx, state),
+            Tab::Balance => self.balance_tab_content(ctx, state),
             Tab::Terrain => self.terrain_tab_content(ctx, state, config),
         }
     }
@@ -305,20 +532,34 @@ impl ChapterEditor {
                 let modal = Modal::new(ui.ctx(), "chapter_delete_confirm_modal");
                 modal.open();
             }
-            self.chapter.read(|data| {
-                if ui
-                    .add(indexed_model_drop_down(
-                        data,
-                        state,
-                        &mut self.selected_chapter_index,
-                    ))
-                    .changed()
-                {
-                    self.loader.load(state, self.selected_chapter_index);
-                }
+            if ui
+                .add_enabled(
+                    self.selected_chapter_index.is_some(),
+                    Button::new("Duplicate"),
+                )
+                .on_hover_text("Copy this chapter and its files under a new CID.")
+                .clicked()
+            {
+                self.duplicate_cid.clear();
+                Modal::new(ui.ctx(), "chapter_duplicate_modal").open();
+            }
+            let chapter_changed = self.chapter.read(|data| {
+                ui.add(indexed_model_drop_down(
+                    data,
+                    state,
+                    &mut self.selected_chapter_index,
+                ))
+                .changed()
             });
+            if chapter_changed {
+                self.reload_chapter(state);
+            }
             ui.selectable_value(&mut self.tab, Tab::Core, "Core");
             ui.selectable_value(&mut self.tab, Tab::Dispos, "Dispos");
+            ui.selectable_value(&mut self.tab, Tab::Difficulties, "Compare")
+                .on_hover_text("Normal, Hard and Lunatic side by side");
+            ui.selectable_value(&mut self.tab, Tab::Balance, "Balance")
+                .on_hover_text("Enemy, weapon and drop totals for the dispos");
             ui.selectable_value(&mut self.tab, Tab::Terrain, "Terrain");
             ComboBox::from_id_source("dispos_kind")
                 .selected_text(match self.dispos_kind {
@@ -347,29 +588,66 @@ impl ChapterEditor {
                     );
                 });
             ComboBox::from_id_source("dispos_difficulty_drop_down")
-                .selected_text(match self.dispos_difficulty {
-                    Difficulty::All => "All Difficulties",
-                    Difficulty::Normal => "Normal",
-                    Difficulty::Hard => "Hard",
-                    Difficulty::Lunatic => "Lunatic",
-                })
+                .selected_text(self.dispos_difficulty.label())
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(
-                        &mut self.dispos_difficulty,
-                        Difficulty::All,
-                        "All Difficulties",
-                    );
-                    ui.selectable_value(&mut self.dispos_difficulty, Difficulty::Normal, "Normal");
-                    ui.selectable_value(&mut self.dispos_difficulty, Difficulty::Hard, "Hard");
-                    ui.selectable_value(
-                        &mut self.dispos_difficulty,
-                        Difficulty::Lunatic,
-                        "Lunatic",
-                    );
+                    for difficulty in [Difficulty::All].into_iter().chain(Difficulty::FLAGGED) {
+                        ui.selectable_value(
+                            &mut self.dispos_difficulty,
+                            difficulty,
+                            difficulty.label(),
+                        );
+                    }
                 });
         });
     }
 
+    /// Switch to a chapter's main dispos and select one of its spawns.
+    fn open_chapter_spawn(
+        &mut self,
+        state: &mut EditorState,
+        cid: &str,
+        spawn: Option<(String, usize)>,
+    ) {
+        let Some(index) = self.chapter.read(|data| data.get_index_of(cid)) else {
+            return;
+        };
+        if self.selected_chapter_index != Some(index) {
+            self.selected_chapter_index = Some(index);
+            self.reload_chapter(state);
+        }
+        self.tab = Tab::Dispos;
+        self.dispos_kind = DisposKind::Main;
+        if spawn.is_some() {
+            *self.dispos_content.selection_mut() = spawn;
+        }
+    }
+
+    fn duplicate_chapter(&mut self, state: &mut EditorState) {
+        let Some(source) = self.selected_chapter_index.and_then(|index| {
+            self.chapter
+                .read(|data| data.get_index(index).map(|(_, chapter)| chapter.clone()))
+        }) else {
+            return;
+        };
+        let result = self
+            .astra
+            .write()
+            .clone_chapter(&source, self.duplicate_cid.trim());
+        match result {
+            Ok((chapter, messages)) => {
+                let mut index = None;
+                self.chapter.write(|data| {
+                    index = Some(data.insert_full(chapter.cid.clone(), chapter).0);
+                    true
+                });
+                self.selected_chapter_index = index;
+                self.reload_chapter(state);
+                self.duplicate_messages = messages;
+            }
+            Err(error) => self.duplicate_error = Some(format!("{:?}", error)),
+        }
+    }
+
     fn core_tab_content(&mut self, ctx: &egui::Context, state: &EditorState, config: &AppConfig) {
         CentralPanel::default().show(ctx, |ui| {
             self.script_buttons(ui, config);
@@ -551,25 +829,43 @@ impl ChapterEditor {
                 ui.text_edit_singleline(&mut chapter.sound_field_situation)
             })
             .field("Player Phase BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.player_phase_bgm)
+                state.music.read(|data| {
+                    ui.add(model_drop_down(data, state, &mut chapter.player_phase_bgm))
+                })
             })
             .field("Enemy Phase BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.enemy_phase_bgm)
+                state
+                    .music
+                    .read(|data| ui.add(model_drop_down(data, state, &mut chapter.enemy_phase_bgm)))
             })
             .field("Ally Phase BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.ally_phase_bgm)
+                state
+                    .music
+                    .read(|data| ui.add(model_drop_down(data, state, &mut chapter.ally_phase_bgm)))
             })
             .field("Player Encount BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.player_encount_bgm)
+                state.music.read(|data| {
+                    ui.add(model_drop_down(
+                        data,
+                        state,
+                        &mut chapter.player_encount_bgm,
+                    ))
+                })
             })
             .field("Enemy Encount BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.enemy_encount_bgm)
+                state.music.read(|data| {
+                    ui.add(model_drop_down(data, state, &mut chapter.enemy_encount_bgm))
+                })
             })
             .field("Sortie BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.sortie_bgm)
+                state
+                    .music
+                    .read(|data| ui.add(model_drop_down(data, state, &mut chapter.sortie_bgm)))
             })
             .field("Kizuna BGM", |ui, chapter| {
-                ui.text_edit_singleline(&mut chapter.kizuna_bgm)
+                state
+                    .music
+                    .read(|data| ui.add(model_drop_down(data, state, &mut chapter.kizuna_bgm)))
             })
             .new_section("Misc.")
             .field("Progress", |ui, chapter| {
@@ -597,6 +893,10 @@ impl ChapterEditor {
         state: &EditorState,
         config: &mut AppConfig,
     ) {
+        if self.show_reinforcements {
+            self.load_reinforcements();
+        }
+        self.all_dispos_issues_panel(ctx);
         let dispos = match &self.loader {
             ChapterLoader::Loaded(Some(state)) => match self.dispos_kind {
                 DisposKind::Main => state.dispos.as_ref(),
@@ -605,10 +905,45 @@ impl ChapterEditor {
             _ => None,
         };
         if let Some(dispos) = dispos {
+            let mut export_requested = false;
+            let mut check_requested = false;
+            let mut check_all_requested = false;
             TopBottomPanel::bottom("dispos_bottom_panel").show(ctx, |ui| {
                 ui.horizontal_top(|ui| {
                     ui.label("Tile Brightness");
                     ui.add(Slider::new(&mut config.terrain_brightness, 0.0..=1.0));
+                    ui.checkbox(&mut self.show_danger_zone, "Danger Zone")
+                        .on_hover_text("Shade every tile an enemy can attack next turn");
+                    ui.add_enabled_ui(self.show_danger_zone, |ui| {
+                        ui.menu_button("Groups", |ui| {
+                            dispos.read(|data| {
+                                for group in data.keys() {
+                                    let mut shown = !self.hidden_danger_zone_groups.contains(group);
+                                    if ui.checkbox(&mut shown, group).changed() {
+                                        if shown {
+                                            self.hidden_danger_zone_groups.remove(group);
+                                        } else {
+                                            self.hidden_danger_zone_groups.insert(group.clone());
+                                        }
+                                    }
+                                }
+                            });
+                        });
+                    });
+                    ui.checkbox(&mut self.show_reinforcements, "Reinforcements")
+                        .on_hover_text("Show when the chapter script brings in each group");
+                    export_requested = ui.button("Export PNG").clicked();
+                    check_requested = ui
+                        .button("Check Placement")
+                        .on_hover_text("List spawns that are off the map, overlapping, stuck, etc.")
+                        .clicked();
+                    check_all_requested = ui
+                        .button("Check All Chapters")
+                        .on_hover_text(
+                            "Check placement in every chapter's main dispos. \
+                            Reads every chapter's dispos and terrain, which can take a moment.",
+                        )
+                        .clicked();
                     if let Some(tile) = self.hovered_tile.as_deref() {
                         ui.label(format!("Tile: {}", tile));
                     }
@@ -617,11 +952,180 @@ impl ChapterEditor {
                     }
                 });
             });
+            if export_requested {
+                if let Err(error) = self.export_map(state, config) {
+                    self.map_export_error = Some(format!("{:?}", error));
+                }
+            }
+            if let Some(error) = self.map_export_error.clone() {
+                let error_modal = Modal::new(ctx, "map_export_error_modal");
+                error_modal.show(|ui| {
+                    error_modal.title(ui, "Failed to export map");
+                    error_modal.body_and_icon(ui, &error, Icon::Error);
+                    error_modal.buttons(ui, |ui| {
+                        if error_modal.button(ui, "Close").clicked() {
+                            self.map_export_error = None;
+                        }
+                        if error_modal.button(ui, "Copy Error").clicked() {
+                            ui.output_mut(|out| {
+                                out.copied_text = error.to_string();
+                            });
+                        }
+                    });
+                });
+                error_modal.open();
+            }
+            if check_all_requested {
+                self.all_dispos_issues = Some(validate_all_dispos(state));
+            }
+            if check_requested {
+                let chapter = self.chapter.read(|data| {
+                    self.selected_chapter_index
+                        .and_then(|index| data.get_index(index))
+                        .map(|(_, chapter)| chapter.clone())
+                });
+                let terrain = match &self.loader {
+                    ChapterLoader::Loaded(Some(state)) => state.terrain.as_ref(),
+                    _ => None,
+                };
+                if let (Some(chapter), Some(terrain)) = (chapter, terrain) {
+                    let issues = terrain.read(|terrain| {
+                        dispos.read(|data| validate_dispos(terrain, state, &chapter, data))
+                    });
+                    self.dispos_issues = Some((self.dispos_kind, issues));
+                }
+            }
+            if let Some((kind, issues)) = &self.dispos_issues {
+                if *kind == self.dispos_kind {
+                    let mut close = false;
+                    TopBottomPanel::bottom("dispos_issues_panel")
+                        .resizable(true)
+                        .default_height(120.)
+                        .show(ctx, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong(format!("{} placement issue(s)", issues.len()));
+                                close = ui.button("Close").clicked();
+                            });
+                            ScrollArea::vertical()
+                                .auto_shrink([false, false])
+                                .show(ui, |ui| {
+                                    let selection = self.dispos_content.selection_mut();
+                                    for issue in issues {
+                                        let selected =
+                                            issue.spawn.is_some() && issue.spawn == *selection;
+                                        if ui.selectable_label(selected, &issue.message).clicked()
+                                            && issue.spawn.is_some()
+                                        {
+                                            *selection = issue.spawn.clone();
+                                        }
+                                    }
+                                });
+                        });
+                    if close {
+                        self.dispos_issues = None;
+                    }
+                }
+            }
+
+            let mut arrival_groups: HashSet<String> = HashSet::new();
+            if self.show_reinforcements {
+                let rows = self
+                    .reinforcements
+                    .as_ref()
+                    .map(|(_, spawns)| reinforcement_timeline(spawns))
+                    .unwrap_or_default();
+                let mut refresh = false;
+                let mut close = false;
+                TopBottomPanel::bottom("dispos_reinforcement_panel")
+                    .resizable(true)
+                    .default_height(140.)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.strong("Reinforcement Timeline");
+                            refresh = ui.button("Refresh").clicked();
+                            close = ui.button("Close").clicked();
+                            ui.label("Select a row to shade where its groups arrive.");
+                        });
+                        if let Some(error) = &self.reinforcement_script_error {
+                            ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                format!("Failed to read script: {}", error),
+                            );
+                        } else if rows.is_empty() {
+                            ui.label("The script doesn't reference any dispos groups.");
+                        }
+                        ScrollArea::vertical()
+                            .auto_shrink([false, false])
+                            .show(ui, |ui| {
+                                Grid::new("dispos_reinforcement_timeline")
+                                    .striped(true)
+                                    .num_columns(2)
+                                    .show(ui, |ui| {
+                                        for row in &rows {
+                                            let selected =
+                                                self.selected_arrival.as_ref() == Some(&row.label);
+                                            if ui.selectable_label(selected, &row.label).clicked() {
+                                                self.selected_arrival =
+                                                    (!selected).then(|| row.label.clone());
+                                            }
+                                            ui.horizontal(|ui| {
+                                                if row.spawns.is_empty() {
+                                                    ui.weak("-");
+                                                }
+                                                for spawn in &row.spawns {
+                                                    Self::scripted_spawn_label(ui, spawn);
+                                                }
+                                            });
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    });
+                if refresh {
+                    self.reinforcements = None;
+                }
+                if close {
+                    self.show_reinforcements = false;
+                }
+                arrival_groups = rows
+                    .into_iter()
+                    .filter(|row| self.selected_arrival.as_ref() == Some(&row.label))
+                    .flat_map(|row| row.spawns)
+                    .map(|spawn| spawn.group)
+                    .collect();
+            }
 
             self.dispos_content.left_panel(ctx, dispos, state);
 
             dispos.write(|data| {
-                let mut changed = self.dispos_content.right_panel(ctx, data, |ui, spawn| {
+                let mut changed = false;
+                if self.selected_spawns.len() > 1 {
+                    let template = self.bulk_spawn.get_or_insert_with(|| {
+                        self.dispos_content
+                            .selection_mut()
+                            .as_ref()
+                            .and_then(|(group, index)| data.get(group)?.get(*index).cloned())
+                            .unwrap_or_default()
+                    });
+                    let count = self.selected_spawns.len();
+                    let edit = SidePanel::right("dispos_bulk_edit_panel")
+                        .show(ctx, |ui| Self::bulk_spawn_panel(ui, template, count, state))
+                        .inner;
+                    if let Some(edit) = edit {
+                        for (group, index) in &self.selected_spawns {
+                            let spawn = data
+                                .get_mut(group)
+                                .and_then(|spawns| spawns.get_mut(*index));
+                            if let Some(spawn) = spawn {
+                                edit.apply(template, spawn);
+                                changed = true;
+                            }
+                        }
+                    }
+                } else {
+                    self.bulk_spawn = None;
+                }
+                changed |= self.dispos_content.right_panel(ctx, data, |ui, spawn| {
                     Self::spawn_property_grid(ui, spawn, state)
                 });
 
@@ -631,15 +1135,75 @@ impl ChapterEditor {
                         _ => None,
                     };
                     if let Some(chapter_terrain) = terrain {
+                        let source = OverlaySource {
+                            dispos_kind: self.dispos_kind,
+                            dispos_revision: dispos.revision_number(),
+                            terrain_revision: chapter_terrain.revision_number(),
+                            coordinate_kind: self.coordinate_kind,
+                            difficulty: self.dispos_difficulty,
+                        };
+                        // Edits made this frame haven't bumped the revision numbers yet.
+                        if changed {
+                            self.movement_range.clear();
+                            self.danger_zone.clear();
+                        }
                         chapter_terrain.read(|terrain_data| {
+                            let no_danger_zone = HashSet::new();
+                            let danger_zone = if self.show_danger_zone {
+                                let key = (source.clone(), self.hidden_danger_zone_groups.clone());
+                                self.danger_zone.get(key, || {
+                                    danger_zone(
+                                        terrain_data,
+                                        state,
+                                        data,
+                                        self.coordinate_kind,
+                                        self.dispos_difficulty,
+                                        &self.hidden_danger_zone_groups,
+                                    )
+                                })
+                            } else {
+                                &no_danger_zone
+                            };
+                            let arrivals: HashSet<Tile> = arrival_groups
+                                .iter()
+                                .filter_map(|group| data.get(group))
+                                .flatten()
+                                .filter(|spawn| self.dispos_difficulty.includes(spawn))
+                                .map(|spawn| spawn_tile(spawn, CoordinateKind::Appear))
+                                .collect();
+                            let selected = self.dispos_content.selection_mut().clone();
+                            let movement_range =
+                                self.movement_range.get((source, selected.clone()), || {
+                                    selected
+                                        .and_then(|(group, index)| data.get(&group)?.get(index))
+                                        .map(|spawn| {
+                                            spawn_movement_range(
+                                                terrain_data,
+                                                state,
+                                                data,
+                                                spawn,
+                                                self.coordinate_kind,
+                                                self.dispos_difficulty,
+                                            )
+                                        })
+                                        .unwrap_or_default()
+                                });
                             let result = dispos_grid(
                                 ui,
                                 terrain_data,
                                 state,
                                 data,
-                                self.dispos_content.selection_mut(),
-                                self.coordinate_kind,
-                                self.dispos_difficulty,
+                                DisposSelection {
+                                    primary: self.dispos_content.selection_mut(),
+                                    spawns: &mut self.selected_spawns,
+                                },
+                                DisposGridOptions {
+                                    coordinate_kind: self.coordinate_kind,
+                                    difficulty: self.dispos_difficulty,
+                                    movement_range,
+                                    danger_zone,
+                                    arrivals: &arrivals,
+                                },
                                 config,
                             );
                             changed |= result.changed;
@@ -664,6 +1228,565 @@ impl ChapterEditor {
         }
     }
 
+    fn difficulties_tab_content(&mut self, ctx: &egui::Context, state: &EditorState) {
+        let dispos = match &self.loader {
+            ChapterLoader::Loaded(Some(state)) => match self.dispos_kind {
+                DisposKind::Main => state.dispos.as_ref(),
+                DisposKind::Encount => state.encount_dispos.as_ref(),
+            },
+            _ => None,
+        };
+        let Some(dispos) = dispos else {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| {
+                    ui.heading("Dispos not found.");
+                });
+            });
+            return;
+        };
+        let comparisons = self
+            .difficulty_comparisons
+            .get((self.dispos_kind, dispos.revision_number()), || {
+                dispos.read(compare_difficulties)
+            });
+        let mut open_spawn = None;
+        CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.differences_only, "Only show differences");
+                ui.label(format!(
+                    "{} unit(s), {} missing from some difficulties",
+                    comparisons.len(),
+                    comparisons
+                        .iter()
+                        .filter(|comparison| comparison.is_partial())
+                        .count()
+                ));
+            });
+            ui.label("Dropped items are marked with *. Click a unit to open it in the Dispos tab.");
+            ui.separator();
+            let warn = ui.visuals().warn_fg_color;
+            let error = ui.visuals().error_fg_color;
+            let text = ui.visuals().text_color();
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    Grid::new("chapter_difficulty_comparison")
+                        .striped(true)
+                        .num_columns(12)
+                        .show(ui, |ui| {
+                            for header in ["Group", "Character", "Position"] {
+                                ui.strong(header);
+                            }
+                            for difficulty in ["Normal", "Hard", "Lunatic"] {
+                                ui.strong(format!("{} Lv.", difficulty));
+                                ui.strong(format!("{} Class", difficulty));
+                                ui.strong(format!("{} Items", difficulty));
+                            }
+                            ui.end_row();
+
+                            state.person.read(|persons| {
+                                for comparison in comparisons {
+                                    if self.differences_only && !comparison.has_differences() {
+                                        continue;
+                                    }
+                                    let name = persons
+                                        .get(&comparison.pid)
+                                        .map(|person| person.text(state).into_owned())
+                                        .unwrap_or_else(|| comparison.pid.clone());
+                                    let name_color =
+                                        if comparison.is_partial() { warn } else { text };
+                                    ui.label(&comparison.group);
+                                    ui.colored_label(name_color, name)
+                                        .on_hover_text(&comparison.pid);
+                                    ui.label(format!(
+                                        "({}, {})",
+                                        comparison.position.0, comparison.position.1
+                                    ));
+                                    let levels_differ = comparison.levels_differ();
+                                    let jobs_differ = comparison.jobs_differ();
+                                    let items_differ = comparison.items_differ();
+                                    for (difficulty, spawn) in
+                                        Difficulty::FLAGGED.into_iter().zip(&comparison.spawns)
+                                    {
+                                        let Some(spawn) = spawn else {
+                                            ui.colored_label(warn, "Missing");
+                                            ui.label("");
+                                            ui.label("");
+                                            continue;
+                                        };
+                                        let color =
+                                            |differs: bool| if differs { error } else { text };
+                                        let level = if spawn.level == 0 {
+                                            "Default".to_string()
+                                        } else {
+                                            spawn.level.to_string()
+                                        };
+                                        let clicked = [
+                                            ui.colored_label(color(levels_differ), level),
+                                            ui.colored_label(color(jobs_differ), &spawn.jid),
+                                            ui.colored_label(
+                                                color(items_differ),
+                                                spawn.items.join(", "),
+                                            ),
+                                        ]
+                                        .into_iter()
+                                        .any(|response| {
+                                            response.interact(Sense::click()).clicked()
+                                        });
+                                        if clicked {
+                                            open_spawn = Some((
+                                                difficulty,
+                                                comparison.group.clone(),
+                                                spawn.index,
+                                            ));
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                });
+        });
+        if let Some((difficulty, group, index)) = open_spawn {
+            self.dispos_difficulty = difficulty;
+            *self.dispos_content.selection_mut() = Some((group, index));
+            self.tab = Tab::Dispos;
+        }
+    }
+
+    /// Placement issues from every chapter, grouped by chapter.
+    /// Clicking one opens its chapter and selects the spawn at fault.
+    fn all_dispos_issues_panel(&mut self, ctx: &egui::Context) {
+        let Some(chapters) = &self.all_dispos_issues else {
+            return;
+        };
+        let open_cid = self.selected_chapter_index.and_then(|index| {
+            self.chapter
+                .read(|data| data.get_index(index).map(|(cid, _)| cid.clone()))
+        });
+        let selection = match self.dispos_kind {
+            DisposKind::Main => self.dispos_content.selection_mut().clone(),
+            DisposKind::Encount => None,
+        };
+        let mut close = false;
+        let mut open = None;
+        TopBottomPanel::bottom("all_dispos_issues_panel")
+            .resizable(true)
+            .default_height(160.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let count: usize = chapters.iter().map(|chapter| chapter.issues.len()).sum();
+                    ui.strong(format!(
+                        "{} placement issue(s) in {} chapter(s)",
+                        count,
+                        chapters.len()
+                    ));
+                    close = ui.button("Close").clicked();
+                });
+                ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for chapter in chapters {
+                            let chapter_open = open_cid.as_ref() == Some(&chapter.cid);
+                            CollapsingHeader::new(format!(
+                                "{} ({})",
+                                chapter.cid,
+                                chapter.issues.len()
+                            ))
+                            .id_source(("all_dispos_issues", &chapter.cid))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for issue in &chapter.issues {
+                                    let selected = chapter_open
+                                        && issue.spawn.is_some()
+                                        && issue.spawn == selection;
+                                    if ui.selectable_label(selected, &issue.message).clicked() {
+                                        open = Some((chapter.cid.clone(), issue.spawn.clone()));
+                                    }
+                                }
+                            });
+                        }
+                    });
+            });
+        if close {
+            self.all_dispos_issues = None;
+        }
+        if open.is_some() {
+            self.open_issue = open;
+        }
+    }
+
+    /// Scan the script that goes with the shown dispos book unless the last scan covers it.
+    /// The timeline and the balance summary share the result.
+    fn load_reinforcements(&mut self) {
+        if matches!(&self.reinforcements, Some((kind, _)) if *kind == self.dispos_kind) {
+            return;
+        }
+        let dispos = match &self.loader {
+            ChapterLoader::Loaded(Some(state)) => match self.dispos_kind {
+                DisposKind::Main => state.dispos.as_ref(),
+                DisposKind::Encount => state.encount_dispos.as_ref(),
+            },
+            _ => None,
+        };
+        let (spawns, error) = match dispos {
+            Some(dispos) => self.scan_chapter_script(dispos),
+            None => (vec![], None),
+        };
+        self.reinforcements = Some((self.dispos_kind, spawns));
+        self.reinforcement_script_error = error;
+    }
+
+    /// Scan the script that goes with the shown dispos book for references to its groups.
+    fn scan_chapter_script(&self, dispos: &SpawnSheet) -> (Vec<ScriptedSpawn>, Option<String>) {
+        let script = match &self.loader {
+            ChapterLoader::Loaded(Some(state)) => match self.dispos_kind {
+                DisposKind::Main => state.script.as_str(),
+                DisposKind::Encount => state.encount_script.as_str(),
+            },
+            _ => "",
+        };
+        if script.is_empty() {
+            return (vec![], None);
+        }
+        match self.astra.read().read_script(script) {
+            Ok(source) => {
+                let spawns = dispos
+                    .read(|data| scan_reinforcements(&source, data.keys().map(String::as_str)));
+                (spawns, None)
+            }
+            Err(error) => (vec![], Some(format!("{:?}", error))),
+        }
+    }
+
+    fn scripted_spawn_label(ui: &mut Ui, spawn: &ScriptedSpawn) {
+        let text = match spawn.timing {
+            ArrivalTiming::Turn { from, to } if to != from => {
+                format!("{} (until turn {})", spawn.group, to)
+            }
+            _ => spawn.group.clone(),
+        };
+        let function = spawn.function.as_deref().unwrap_or("top level");
+        ui.label(text)
+            .on_hover_text(format!("{}, line {}", function, spawn.line));
+    }
+
+    fn refresh_balance(&mut self, state: &EditorState) {
+        let dispos = match &self.loader {
+            ChapterLoader::Loaded(Some(state)) => match self.dispos_kind {
+                DisposKind::Main => state.dispos.as_ref(),
+                DisposKind::Encount => state.encount_dispos.as_ref(),
+            },
+            _ => None,
+        };
+        let Some(dispos) = dispos else {
+            self.balance = None;
+            return;
+        };
+        let balance = dispos.read(|data| chapter_balance(state, data, self.dispos_difficulty));
+        self.balance = Some((self.dispos_kind, self.dispos_difficulty, balance));
+    }
+
+    fn balance_tab_content(&mut self, ctx: &egui::Context, state: &EditorState) {
+        let is_current = matches!(
+            &self.balance,
+            Some((kind, difficulty, _))
+                if *kind == self.dispos_kind && *difficulty == self.dispos_difficulty
+        );
+        if !is_current {
+            self.refresh_balance(state);
+        }
+        self.load_reinforcements();
+        let Some((_, difficulty, balance)) = &self.balance else {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| {
+                    ui.heading("Dispos not found.");
+                });
+            });
+            return;
+        };
+        let difficulty_label = difficulty.label();
+        let mut refresh = false;
+        CentralPanel::default().show(ctx, |ui| {
+            refresh = ui
+                .button("Refresh")
+                .on_hover_text("Recount after editing the dispos or script")
+                .clicked();
+            ui.label(
+                "Levels include class internal levels. Weapons, threats and drops follow the \
+                difficulty filter.",
+            );
+            ui.separator();
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.heading("Enemies");
+                    Grid::new("chapter_balance_enemies")
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            for label in ["Normal", "Hard", "Lunatic"] {
+                                ui.strong(label);
+                            }
+                            ui.end_row();
+                            ui.label("Count");
+                            for count in balance.enemy_count {
+                                ui.label(count.to_string());
+                            }
+                            ui.end_row();
+                            ui.label("Average Level");
+                            for level in balance.average_level {
+                                ui.label(
+                                    level
+                                        .map(|level| format!("{:.1}", level))
+                                        .unwrap_or_default(),
+                                );
+                            }
+                            ui.end_row();
+                            ui.label("Max Level");
+                            for level in balance.max_level {
+                                ui.label(level.map(|level| level.to_string()).unwrap_or_default());
+                            }
+                            ui.end_row();
+                        });
+                    ui.separator();
+
+                    ui.heading(format!("Weapon Types ({})", difficulty_label));
+                    Grid::new("chapter_balance_weapons")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            let labels = WEAPON_TYPE_LABELS.iter().chain(["Unarmed"].iter());
+                            for (label, count) in labels.zip(balance.weapon_types) {
+                                if count > 0 {
+                                    ui.label(*label);
+                                    ui.label(count.to_string());
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                    ui.separator();
+
+                    ui.heading(format!("Effective Threats ({})", difficulty_label));
+                    Grid::new("chapter_balance_threats")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (label, count) in
+                                EFFECTIVENESS_LABELS.iter().zip(balance.effective_threats)
+                            {
+                                ui.label(format!("vs. {}", label));
+                                ui.label(count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                    ui.separator();
+
+                    ui.heading("Reinforcements");
+                    let reinforcements = self
+                        .reinforcements
+                        .as_ref()
+                        .map(|(_, spawns)| spawns.as_slice())
+                        .unwrap_or_default();
+                    if let Some(error) = &self.reinforcement_script_error {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("Failed to read script: {}", error),
+                        );
+                    } else if reinforcements.is_empty() {
+                        ui.label("The script doesn't call any dispos groups.");
+                    }
+                    Grid::new("chapter_balance_reinforcements")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for spawn in reinforcements {
+                                ui.label(spawn.timing.label());
+                                ui.label(&spawn.group);
+                                ui.label(format!(
+                                    "{} (line {})",
+                                    spawn.function.as_deref().unwrap_or("top level"),
+                                    spawn.line
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                    ui.separator();
+
+                    ui.heading(format!("Drops ({})", difficulty_label));
+                    ui.label(format!("Gold: {}", balance.gold));
+                    let mut drops: IndexMap<&str, usize> = IndexMap::new();
+                    for iid in &balance.drops {
+                        *drops.entry(iid.as_str()).or_default() += 1;
+                    }
+                    state.item.read(|items| {
+                        Grid::new("chapter_balance_drops")
+                            .striped(true)
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (iid, count) in drops {
+                                    let name = items
+                                        .get(iid)
+                                        .map(|item| item.text(state).into_owned())
+                                        .unwrap_or_else(|| iid.to_string());
+                                    ui.label(name).on_hover_text(iid);
+                                    ui.label(format!("x{}", count));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+        });
+        if refresh {
+            self.reinforcements = None;
+            self.refresh_balance(state);
+        }
+    }
+
+    /// The spawn's real stats on each difficulty. Capped stats are highlighted.
+    fn effective_stats_table(ui: &mut Ui, spawn: &Spawn, state: &EditorState) -> Response {
+        ui.vertical(|ui| {
+            Grid::new("spawn_effective_stats")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Lv");
+                    for label in STANDARD_STAT_LABELS {
+                        ui.label(label);
+                    }
+                    ui.end_row();
+                    for difficulty in Difficulty::FLAGGED {
+                        let label = difficulty.label();
+                        if difficulty.includes(spawn) {
+                            ui.label(label);
+                        } else {
+                            ui.weak(label)
+                                .on_hover_text("The spawn doesn't appear on this difficulty");
+                        }
+                        match effective_stats(state, spawn, difficulty) {
+                            Some(stats) => {
+                                ui.label(stats.level.to_string());
+                                for (stat, cap) in stats.stats.into_iter().zip(stats.caps) {
+                                    if cap > 0 && stat >= cap {
+                                        ui.strong(stat.to_string()).on_hover_text("Capped");
+                                    } else {
+                                        ui.label(stat.to_string());
+                                    }
+                                }
+                            }
+                            None => {
+                                ui.weak("Unknown character or class");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        })
+        .response
+    }
+
+    /// Fields to copy onto every selected spawn. Returns the group of fields to apply, if any.
+    fn bulk_spawn_panel(
+        ui: &mut Ui,
+        template: &mut Spawn,
+        count: usize,
+        state: &EditorState,
+    ) -> Option<BulkSpawnEdit> {
+        ui.heading(format!("{} Spawns", count));
+        ui.label("Fill in the fields below, then apply them to every selected spawn.");
+        let mut edit = None;
+        ui.horizontal_wrapped(|ui| {
+            for (label, value) in [
+                ("Set Levels", BulkSpawnEdit::Levels),
+                ("Set Items", BulkSpawnEdit::Items),
+                ("Set AI", BulkSpawnEdit::Ai),
+                ("Set Flags", BulkSpawnEdit::SetFlags),
+                ("Add Flags", BulkSpawnEdit::AddFlags),
+                ("Remove Flags", BulkSpawnEdit::RemoveFlags),
+            ] {
+                if ui.button(label).clicked() {
+                    edit = Some(value);
+                }
+            }
+        });
+        ui.separator();
+        PropertyGrid::new("bulk_spawn", template)
+            .new_section("Levels")
+            .fields(Self::spawn_level_fields)
+            .new_section("Flags")
+            .field("Flag", |ui, spawn| {
+                ui.add(bitgrid_u16(SPAWN_FLAG_LABELS, 1, &mut spawn.flag))
+            })
+            .new_section("Items")
+            .fields(|grid| Self::spawn_item_fields(grid, state))
+            .new_section("AI")
+            .fields(Self::spawn_ai_fields)
+            .show(ui);
+        edit
+    }
+
+    /// The level fields shared by the spawn editor and the bulk edit panel.
+    fn spawn_level_fields(grid: PropertyGrid<Spawn>) -> PropertyGrid<Spawn> {
+        grid.field("Level (N)", |ui, spawn| {
+            ui.add(DragValue::new(&mut spawn.level_n))
+        })
+        .field("Level (H)", |ui, spawn| {
+            ui.add(DragValue::new(&mut spawn.level_h))
+        })
+        .field("Level (L)", |ui, spawn| {
+            ui.add(DragValue::new(&mut spawn.level_l))
+        })
+    }
+
+    /// The item and drop fields shared by the spawn editor and the bulk edit panel.
+    fn spawn_item_fields<'a>(
+        grid: PropertyGrid<'a, Spawn>,
+        state: &'a EditorState,
+    ) -> PropertyGrid<'a, Spawn> {
+        SPAWN_ITEM_LABELS
+            .into_iter()
+            .enumerate()
+            .fold(grid, |grid, (slot, (label, drop_label))| {
+                grid.field(label, move |ui, spawn| {
+                    state.item.read(|data| {
+                        ui.add(model_drop_down(data, state, spawn_items_mut(spawn)[slot].0))
+                    })
+                })
+                .field(drop_label, move |ui, spawn| {
+                    ui.add(DragValue::new(spawn_items_mut(spawn)[slot].1))
+                })
+            })
+    }
+
+    /// The AI fields shared by the spawn editor and the bulk edit panel.
+    fn spawn_ai_fields(grid: PropertyGrid<Spawn>) -> PropertyGrid<Spawn> {
+        grid.field("AI Action Name", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_action_name)
+        })
+        .field("AI Action Val", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_action_val)
+        })
+        .field("AI Mind Name", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_mind_name)
+        })
+        .field("AI Mind Val", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_mind_val)
+        })
+        .field("AI Attack Name", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_attack_name)
+        })
+        .field("AI Attack Val", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_attack_val)
+        })
+        .field("AI Move Name", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_move_name)
+        })
+        .field("AI Move Val", |ui, spawn| {
+            ui.text_edit_singleline(&mut spawn.ai_move_val)
+        })
+    }
+
     fn spawn_property_grid(ui: &mut Ui, spawn: &mut Spawn, state: &EditorState) -> bool {
         PropertyGrid::new("spawn", spawn)
             .new_section("Core")
@@ -705,67 +1828,16 @@ impl ChapterEditor {
             .field("Direction", |ui, spawn| {
                 ui.add(DragValue::new(&mut spawn.direction))
             })
-            .field("Level (N)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.level_n))
-            })
-            .field("Level (H)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.level_h))
-            })
-            .field("Level (L)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.level_l))
-            })
+            .fields(Self::spawn_level_fields)
             .field("HP Stock Count", |ui, spawn| {
                 ui.add(DragValue::new(&mut spawn.hp_stock_count))
             })
-            .new_section("Items")
-            .field("Item 1", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_1_iid)))
-            })
-            .field("Item 1 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_1_drop))
-            })
-            .field("Item 2", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_2_iid)))
-            })
-            .field("Item 2 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_2_drop))
-            })
-            .field("Item 3", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_3_iid)))
-            })
-            .field("Item 3 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_3_drop))
-            })
-            .field("Item 4", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_4_iid)))
-            })
-            .field("Item 4 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_4_drop))
-            })
-            .field("Item 5", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_5_iid)))
-            })
-            .field("Item 5 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_5_drop))
-            })
-            .field("Item 6", |ui, spawn| {
-                state
-                    .item
-                    .read(|data| ui.add(model_drop_down(data, state, &mut spawn.item_6_iid)))
-            })
-            .field("Item 6 (Drop)", |ui, spawn| {
-                ui.add(DragValue::new(&mut spawn.item_6_drop))
+            .new_section("Effective Stats")
+            .field("By Difficulty", |ui, spawn| {
+                Self::effective_stats_table(ui, spawn, state)
             })
+            .new_section("Items")
+            .fields(|grid| Self::spawn_item_fields(grid, state))
             .new_section("States")
             .field("State 0", |ui, spawn| {
                 ui.add(DragValue::new(&mut spawn.state_0))
@@ -786,30 +1858,7 @@ impl ChapterEditor {
                 ui.add(DragValue::new(&mut spawn.state_5))
             })
             .new_section("AI")
-            .field("AI Action Name", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_action_name)
-            })
-            .field("AI Action Val", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_action_val)
-            })
-            .field("AI Mind Name", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_mind_name)
-            })
-            .field("AI Mind Val", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_mind_val)
-            })
-            .field("AI Attack Name", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_attack_name)
-            })
-            .field("AI Attack Val", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_attack_val)
-            })
-            .field("AI Move Name", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_move_name)
-            })
-            .field("AI Move Val", |ui, spawn| {
-                ui.text_edit_singleline(&mut spawn.ai_move_val)
-            })
+            .fields(Self::spawn_ai_fields)
             .field("AI Battle Rate", |ui, spawn| {
                 ui.text_edit_singleline(&mut spawn.ai_battle_rate)
             })
@@ -835,15 +1884,136 @@ impl ChapterEditor {
             .changed()
     }
 
+    fn selected_cid(&self) -> Option<String> {
+        self.chapter.read(|data| {
+            self.selected_chapter_index
+                .and_then(|index| data.get_index(index))
+                .map(|(cid, _)| cid.clone())
+        })
+    }
+
+    fn export_map(&self, state: &EditorState, config: &AppConfig) -> Result<()> {
+        let Some(cid) = self.selected_cid() else {
+            return Ok(());
+        };
+        let path = FileDialog::new()
+            .add_filter("PNG", &["png"])
+            .set_file_name(&format!("{}.png", cid))
+            .save_file();
+        let Some(path) = path else {
+            return Ok(());
+        };
+        let options = MapRenderOptions {
+            coordinates: match self.coordinate_kind {
+                CoordinateKind::Dispos => MapCoordinates::Dispos,
+                CoordinateKind::Appear => MapCoordinates::Appear,
+            },
+            encounter: self.dispos_kind == DisposKind::Encount,
+            difficulties: self.dispos_difficulty.flag(),
+            brightness: config.terrain_brightness,
+            tile_color_overrides: config
+                .tile_color_overrides
+                .iter()
+                .map(|(tid, color)| (tid.clone(), [color.r(), color.g(), color.b()]))
+                .collect(),
+            terrain_names: state.terrain.read(|data| {
+                data.values()
+                    .map(|tile| (tile.tid.clone(), tile.text(state).into_owned()))
+                    .collect()
+            }),
+            font: UI_FONT,
+            ..Default::default()
+        };
+        self.astra.write().export_chapter_map(&cid, &options, &path)
+    }
+
+    /// The terrain name the selected chapter resolves to.
+    fn chapter_terrain_name(&self) -> Option<String> {
+        self.chapter.read(|data| {
+            self.selected_chapter_index
+                .and_then(|index| data.get_index(index))
+                .map(|(_, chapter)| {
+                    if chapter.terrain.is_empty() {
+                        chapter_file_name(&chapter.cid, "*")
+                    } else {
+                        chapter_file_name(&chapter.cid, &chapter.terrain)
+                    }
+                })
+        })
+    }
+
+    fn new_terrain_form(form: &mut NewTerrainForm, ui: &mut Ui, state: &EditorState) -> bool {
+        Grid::new("new_terrain_form").num_columns(2).show(ui, |ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut form.name);
+            ui.end_row();
+
+            ui.label("Size");
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut form.width).clamp_range(1..=32));
+                ui.label("x");
+                ui.add(DragValue::new(&mut form.height).clamp_range(1..=32));
+            });
+            ui.end_row();
+
+            ui.label("Fill");
+            state
+                .terrain
+                .read(|data| ui.add(model_drop_down(data, state, &mut form.fill_tid)));
+            ui.end_row();
+        });
+        ui.add_enabled(
+            !form.name.trim().is_empty() && !form.fill_tid.is_empty(),
+            Button::new("Create"),
+        )
+        .on_hover_text("The selected chapter will use the new map.")
+        .clicked()
+    }
+
+    /// Make the map described by the new terrain form and point the selected chapter at it.
+    fn create_terrain(&mut self, state: &mut EditorState) {
+        let name = self.new_terrain.name.trim().to_lowercase();
+        let result = self.astra.write().create_chapter_terrain(
+            &name,
+            self.new_terrain.width,
+            self.new_terrain.height,
+            &self.new_terrain.fill_tid,
+        );
+        if let Err(error) = result {
+            self.terrain_file_error = Some(format!("{:?}", error));
+            return;
+        }
+        if self.chapter_terrain_name().as_ref() != Some(&name) {
+            if let Some(index) = self.selected_chapter_index {
+                self.chapter.write(|data| match data.get_index_mut(index) {
+                    Some((_, chapter)) => {
+                        chapter.terrain = name;
+                        true
+                    }
+                    None => false,
+                });
+            }
+        }
+        self.new_terrain.name.clear();
+        self.reload_chapter(state);
+    }
+
     fn terrain_tab_content(
         &mut self,
         ctx: &egui::Context,
-        state: &EditorState,
+        state: &mut EditorState,
         config: &mut AppConfig,
     ) {
-        let terrain = match &self.loader {
-            ChapterLoader::Loaded(Some(state)) => state.terrain.as_ref(),
-            _ => None,
+        let mut create_terrain = false;
+        let (terrain, spawn_sheets) = match &self.loader {
+            ChapterLoader::Loaded(Some(state)) => (
+                state.terrain.as_ref(),
+                [
+                    ("Main", state.dispos.as_ref()),
+                    ("Encount", state.encount_dispos.as_ref()),
+                ],
+            ),
+            _ => (None, [("Main", None), ("Encount", None)]),
         };
         if let Some(chapter_terrain) = terrain {
             SidePanel::right("terrain_right_panel").show(ctx, |ui| {
@@ -857,22 +2027,187 @@ impl ChapterEditor {
                             ui.horizontal_centered(|ui| ui.separator());
                         });
                         strip.cell(|ui| {
-                            ui.selectable_value(&mut self.terrain_brush, TerrainBrush::Stamp, "🖊");
-                            ui.selectable_value(&mut self.terrain_brush, TerrainBrush::Fill, "💧");
-                            ui.selectable_value(&mut self.terrain_brush, TerrainBrush::Box, "⬜");
+                            let brush = &mut self.terrain_tools.brush;
+                            for (value, icon, hint) in [
+                                (TerrainBrush::Stamp, "🖊", "Stamp"),
+                                (TerrainBrush::Fill, "💧", "Fill"),
+                                (TerrainBrush::Box, "⬜", "Box"),
+                                (TerrainBrush::Line, "📏", "Line"),
+                                (TerrainBrush::Ellipse, "⭕", "Ellipse"),
+                                (TerrainBrush::Replace, "🔁", "Replace all of a tile"),
+                                (TerrainBrush::Select, "🔲", "Select"),
+                            ] {
+                                ui.selectable_value(brush, value, icon).on_hover_text(hint);
+                            }
+                            ui.add_enabled_ui(self.terrain_tools.clipboard.is_some(), |ui| {
+                                ui.selectable_value(brush, TerrainBrush::Paste, "📋")
+                                    .on_hover_text("Paste with the clicked tile as the top left")
+                                    .on_disabled_hover_text("Copy a selection first");
+                            });
                         });
                     });
             });
 
+            let fill_tid = state.terrain.read(|data| {
+                self.terrain_content
+                    .selection()
+                    .and_then(|index| data.item(index))
+                    .map(|tile| tile.tid.clone())
+            });
+            let mut transform = None;
+            let mut file_action = None;
             TopBottomPanel::bottom("dispos_bottom_panel").show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Tile Brightness");
                     ui.add(Slider::new(&mut config.terrain_brightness, 0.0..=1.0));
+                    let resize_menu = ui.menu_button("Resize", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.add(DragValue::new(&mut self.terrain_resize.0).clamp_range(1..=32));
+                            ui.label("x");
+                            ui.add(DragValue::new(&mut self.terrain_resize.1).clamp_range(1..=32));
+                        });
+                        let (width, height) = self.terrain_resize;
+                        if ui
+                            .add_enabled(fill_tid.is_some(), Button::new("Apply"))
+                            .on_disabled_hover_text("Select a tile to pad the map with")
+                            .clicked()
+                        {
+                            transform = Some(TerrainTransform::Resize(width, height));
+                            ui.close_menu();
+                        }
+                    });
+                    if resize_menu.inner.is_none() {
+                        self.terrain_resize =
+                            chapter_terrain.read(|data| (data.width, data.height));
+                    }
+                    ui.menu_button("Shift", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("X");
+                            ui.add(DragValue::new(&mut self.terrain_shift.0).clamp_range(-31..=31));
+                            ui.label("Y");
+                            ui.add(DragValue::new(&mut self.terrain_shift.1).clamp_range(-31..=31));
+                        });
+                        let (dx, dy) = self.terrain_shift;
+                        if ui
+                            .add_enabled(fill_tid.is_some(), Button::new("Apply"))
+                            .on_disabled_hover_text("Select a tile to fill the uncovered edge with")
+                            .clicked()
+                        {
+                            transform = Some(TerrainTransform::Shift(dx, dy));
+                            ui.close_menu();
+                        }
+                    });
+                    ui.menu_button("File", |ui| {
+                        for (label, action) in [
+                            ("Import CSV...", TerrainFileAction::ImportCsv),
+                            ("Import PNG...", TerrainFileAction::ImportPng),
+                            ("Export CSV...", TerrainFileAction::ExportCsv),
+                            ("Export PNG...", TerrainFileAction::ExportPng),
+                        ] {
+                            if ui.button(label).clicked() {
+                                file_action = Some(action);
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        ui.menu_button("New Map", |ui| {
+                            if Self::new_terrain_form(&mut self.new_terrain, ui, state) {
+                                create_terrain = true;
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                    let symmetry = &mut self.terrain_tools.symmetry;
+                    ComboBox::from_id_source("terrain_symmetry")
+                        .selected_text(format!("Symmetry: {}", symmetry.label()))
+                        .show_ui(ui, |ui| {
+                            for value in [
+                                TerrainSymmetry::None,
+                                TerrainSymmetry::Horizontal,
+                                TerrainSymmetry::Vertical,
+                                TerrainSymmetry::FourWay,
+                                TerrainSymmetry::Rotational,
+                            ] {
+                                ui.selectable_value(symmetry, value, value.label());
+                            }
+                        });
+                    ui.menu_button("Selection", |ui| {
+                        let tools = &mut self.terrain_tools;
+                        if ui
+                            .add_enabled(tools.selection.is_some(), Button::new("Copy"))
+                            .clicked()
+                        {
+                            if let Some(selection) = tools.selection {
+                                tools.clipboard = Some(
+                                    chapter_terrain
+                                        .read(|data| TerrainClipboard::copy(data, selection)),
+                                );
+                                tools.brush = TerrainBrush::Paste;
+                            }
+                            ui.close_menu();
+                        }
+                        if ui
+                            .add_enabled(tools.selection.is_some(), Button::new("Deselect"))
+                            .clicked()
+                        {
+                            tools.selection = None;
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        if let Some(clipboard) = &mut tools.clipboard {
+                            let (width, height) = clipboard.size();
+                            ui.label(format!("Clipboard: {}x{}", width, height));
+                            if ui.button("Rotate Clockwise").clicked() {
+                                clipboard.rotate();
+                            }
+                            if ui.button("Mirror Left / Right").clicked() {
+                                clipboard.mirror_horizontal();
+                            }
+                            if ui.button("Mirror Top / Bottom").clicked() {
+                                clipboard.mirror_vertical();
+                            }
+                        } else {
+                            ui.weak("Clipboard is empty");
+                        }
+                    });
                     if let Some(tile) = self.hovered_tile.as_deref() {
                         ui.label(format!("Tile: {}", tile));
                     }
                 });
             });
+            if let (Some(transform), Some(fill_tid)) = (transform, fill_tid) {
+                self.terrain_transform_messages =
+                    Self::transform_terrain(chapter_terrain, &spawn_sheets, &fill_tid, transform)
+                        .unwrap_or_else(|error| vec![format!("{:?}", error)]);
+            }
+            if let Some(action) = file_action {
+                let cid = self.selected_cid().unwrap_or_default();
+                match Self::terrain_file(chapter_terrain, state, &cid, action) {
+                    Ok(true) => {
+                        self.terrain_transform_messages =
+                            Self::fit_spawns(chapter_terrain, &spawn_sheets, 0, 0);
+                    }
+                    Ok(false) => {}
+                    Err(error) => self.terrain_file_error = Some(format!("{:?}", error)),
+                }
+            }
+            if !self.terrain_transform_messages.is_empty() {
+                let modal = Modal::new(ctx, "terrain_transform_modal");
+                modal.show(|ui| {
+                    modal.title(ui, "Check Spawn Positions");
+                    modal.body_and_icon(
+                        ui,
+                        self.terrain_transform_messages.join("\n"),
+                        Icon::Warning,
+                    );
+                    modal.buttons(ui, |ui| {
+                        if modal.button(ui, "Close").clicked() {
+                            self.terrain_transform_messages.clear();
+                        }
+                    });
+                });
+                modal.open();
+            }
 
             self.terrain_content.left_panel(ctx, &state.terrain, state);
 
@@ -884,7 +2219,7 @@ impl ChapterEditor {
                         self.terrain_content.selection(),
                         state,
                         config,
-                        self.terrain_brush,
+                        &mut self.terrain_tools,
                     );
                     self.hovered_tile = result.hovered_tile;
                     if let Some(selection) = result.selected_tile {
@@ -897,11 +2232,146 @@ impl ChapterEditor {
                 });
             });
         } else {
+            if self.new_terrain.name.is_empty() {
+                self.new_terrain.name = self.chapter_terrain_name().unwrap_or_default();
+            }
             CentralPanel::default().show(ctx, |ui| {
-                ui.centered_and_justified(|ui| {
+                ui.vertical_centered(|ui| {
                     ui.heading("Terrain not found.");
+                    ui.label("Create a blank map for this chapter:");
+                    create_terrain = Self::new_terrain_form(&mut self.new_terrain, ui, state);
+                });
+            });
+        }
+
+        if create_terrain {
+            self.create_terrain(state);
+        }
+        if let Some(error) = self.terrain_file_error.clone() {
+            let error_modal = Modal::new(ctx, "terrain_file_error_modal");
+            error_modal.show(|ui| {
+                error_modal.title(ui, "Terrain Error");
+                error_modal.body_and_icon(ui, &error, Icon::Error);
+                error_modal.buttons(ui, |ui| {
+                    if error_modal.button(ui, "Close").clicked() {
+                        self.terrain_file_error = None;
+                    }
+                    if error_modal.button(ui, "Copy Error").clicked() {
+                        ui.output_mut(|out| {
+                            out.copied_text = error.to_string();
+                        });
+                    }
                 });
             });
+            error_modal.open();
+        }
+    }
+
+    /// Resize or shift the map and move the spawns of both dispos books along with it.
+    /// Returns a warning for every spawn that no longer fits on the map.
+    fn transform_terrain(
+        chapter_terrain: &OpenTerrain,
+        spawn_sheets: &[(&str, Option<&SpawnSheet>)],
+        fill_tid: &str,
+        transform: TerrainTransform,
+    ) -> Result<Vec<String>> {
+        let mut result = Ok(());
+        chapter_terrain.write(|data| {
+            result = match transform {
+                TerrainTransform::Resize(width, height) => {
+                    resize_terrain(data, width, height, fill_tid)
+                }
+                TerrainTransform::Shift(dx, dy) => {
+                    shift_terrain(data, dx, dy, fill_tid);
+                    Ok(())
+                }
+            };
+            result.is_ok()
+        });
+        result?;
+
+        let (dx, dy) = match transform {
+            TerrainTransform::Resize(..) => (0, 0),
+            TerrainTransform::Shift(dx, dy) => (dx, dy),
+        };
+        Ok(Self::fit_spawns(chapter_terrain, spawn_sheets, dx, dy))
+    }
+
+    /// Move the spawns of both dispos books by `(dx, dy)`.
+    /// Returns a warning for every spawn that was left in place because it wouldn't fit.
+    fn fit_spawns(
+        chapter_terrain: &OpenTerrain,
+        spawn_sheets: &[(&str, Option<&SpawnSheet>)],
+        dx: i32,
+        dy: i32,
+    ) -> Vec<String> {
+        let (width, height) = chapter_terrain.read(|data| (data.width, data.height));
+        let mut warnings = vec![];
+        for (label, sheet) in spawn_sheets {
+            if let Some(sheet) = sheet {
+                sheet.write(|data| {
+                    let (moved, off_map) = move_spawns(data, dx, dy, width, height);
+                    warnings.extend(
+                        off_map
+                            .into_iter()
+                            .map(|warning| format!("{}: {}", label, warning)),
+                    );
+                    moved > 0
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Import or export the terrain grid as a CSV of TIDs or an indexed PNG.
+    /// Returns true if the map was replaced by an import.
+    fn terrain_file(
+        chapter_terrain: &OpenTerrain,
+        state: &EditorState,
+        cid: &str,
+        action: TerrainFileAction,
+    ) -> Result<bool> {
+        let extension = match action {
+            TerrainFileAction::ImportCsv | TerrainFileAction::ExportCsv => "csv",
+            TerrainFileAction::ImportPng | TerrainFileAction::ExportPng => "png",
+        };
+        let dialog = FileDialog::new().add_filter(&extension.to_uppercase(), &[extension]);
+        match action {
+            TerrainFileAction::ExportCsv | TerrainFileAction::ExportPng => {
+                let path = dialog
+                    .set_file_name(&format!("{}_terrain.{}", cid, extension))
+                    .save_file();
+                let Some(path) = path else {
+                    return Ok(false);
+                };
+                let contents = if action == TerrainFileAction::ExportCsv {
+                    chapter_terrain.read(terrain_to_csv).into_bytes()
+                } else {
+                    state
+                        .terrain
+                        .read(|tiles| chapter_terrain.read(|data| terrain_to_png(data, tiles)))?
+                };
+                std::fs::write(path, contents)?;
+                Ok(false)
+            }
+            TerrainFileAction::ImportCsv | TerrainFileAction::ImportPng => {
+                let Some(path) = dialog.pick_file() else {
+                    return Ok(false);
+                };
+                let contents = std::fs::read(path)?;
+                let mut result = Ok(());
+                state.terrain.read(|tiles| {
+                    chapter_terrain.write(|data| {
+                        result = if action == TerrainFileAction::ImportCsv {
+                            terrain_from_csv(data, &String::from_utf8_lossy(&contents), tiles)
+                        } else {
+                            terrain_from_png(data, &contents, tiles)
+                        };
+                        result.is_ok()
+                    })
+                });
+                result.map(|_| true)
+            }
         }
     }
 
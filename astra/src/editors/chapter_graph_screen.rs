@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use astra_types::Chapter;
+use egui::{
+    Align2, CentralPanel, Color32, FontId, Pos2, Rect, ScrollArea, Sense, SidePanel, Stroke, Ui,
+    Vec2,
+};
+use indexmap::IndexMap;
+
+use crate::{
+    build_chapter_graph, model_drop_down, queue_transition, CachedValue, ChapterGraph,
+    ChapterLinkKind, EditorState, Screens, Transition, ViewItem,
+};
+
+const NODE_SIZE: Vec2 = Vec2::new(140., 28.);
+const COLUMN_SPACING: f32 = 200.;
+const ROW_SPACING: f32 = 44.;
+const MARGIN: f32 = 16.;
+
+const LINK_KINDS: [ChapterLinkKind; 3] = [
+    ChapterLinkKind::Next,
+    ChapterLinkKind::MapSpot,
+    ChapterLinkKind::Challenge,
+];
+
+fn link_color(kind: ChapterLinkKind, ui: &Ui) -> Color32 {
+    match kind {
+        ChapterLinkKind::Next => ui.visuals().text_color(),
+        ChapterLinkKind::MapSpot => Color32::LIGHT_BLUE,
+        ChapterLinkKind::Challenge => Color32::GOLD,
+    }
+}
+
+/// Lay chapters out in columns by their distance from the start chapter.
+/// Unreachable chapters go in a column of their own at the end.
+fn layout(graph: &ChapterGraph) -> (Vec<Rect>, Vec2) {
+    let unreachable_column = graph.depths.iter().flatten().max().map_or(0, |d| d + 1);
+    let mut rows = vec![0; unreachable_column + 1];
+    let rects: Vec<Rect> = graph
+        .depths
+        .iter()
+        .map(|depth| {
+            let column = depth.unwrap_or(unreachable_column);
+            let row = rows[column];
+            rows[column] += 1;
+            Rect::from_min_size(
+                Pos2::new(
+                    MARGIN + column as f32 * COLUMN_SPACING,
+                    MARGIN + row as f32 * ROW_SPACING,
+                ),
+                NODE_SIZE,
+            )
+        })
+        .collect();
+    let size = Vec2::new(
+        MARGIN * 2. + unreachable_column as f32 * COLUMN_SPACING + NODE_SIZE.x,
+        MARGIN * 2. + rows.iter().max().copied().unwrap_or_default() as f32 * ROW_SPACING,
+    );
+    (rects, size)
+}
+
+/// Node graph of the campaign route with checks for broken links.
+#[derive(Default)]
+pub struct ChapterGraphScreen {
+    start: String,
+    selected: Option<usize>,
+    /// Keyed by the chapter and challenge revisions and the start chapter.
+    graph: CachedValue<(usize, usize, String), Arc<ChapterGraph>>,
+}
+
+impl ChapterGraphScreen {
+    fn side_panel(
+        &mut self,
+        ui: &mut Ui,
+        state: &EditorState,
+        chapters: &IndexMap<String, Chapter>,
+        graph: &ChapterGraph,
+    ) {
+        ui.heading("Start Chapter");
+        ui.add(model_drop_down(chapters, state, &mut self.start));
+        ui.separator();
+
+        ui.heading("Legend");
+        for kind in LINK_KINDS {
+            ui.colored_label(link_color(kind, ui), kind.label());
+        }
+        ui.colored_label(ui.visuals().warn_fg_color, "Unreachable");
+        ui.colored_label(ui.visuals().error_fg_color, "Has issues");
+        ui.separator();
+
+        if let Some((index, chapter)) = self
+            .selected
+            .and_then(|index| chapters.get_index(index).map(|(_, c)| (index, c)))
+        {
+            ui.heading("Selected");
+            ui.label(chapter.text(state).into_owned());
+            if ui.button("Open in Chapter Editor").clicked() {
+                queue_transition(Transition::new(Screens::Chapter, index));
+            }
+            ui.separator();
+        }
+
+        ui.heading(format!("Issues ({})", graph.issues.len()));
+        ScrollArea::vertical().show(ui, |ui| {
+            for issue in &graph.issues {
+                let clicked = ui
+                    .selectable_label(
+                        issue.chapter.is_some() && issue.chapter == self.selected,
+                        &issue.message,
+                    )
+                    .clicked();
+                if clicked && issue.chapter.is_some() {
+                    self.selected = issue.chapter;
+                }
+            }
+        });
+    }
+
+    fn graph_view(
+        &mut self,
+        ui: &mut Ui,
+        state: &EditorState,
+        chapters: &IndexMap<String, Chapter>,
+        graph: &ChapterGraph,
+    ) {
+        let (rects, size) = layout(graph);
+        ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let (response, painter) = ui.allocate_painter(size, Sense::hover());
+                let offset = response.rect.min.to_vec2();
+                let rects: Vec<Rect> = rects.iter().map(|rect| rect.translate(offset)).collect();
+
+                for link in &graph.links {
+                    let (from, to) = (rects[link.from], rects[link.to]);
+                    let stroke = Stroke::new(1.5, link_color(link.kind, ui));
+                    let (start, end) = if to.center().x > from.center().x {
+                        (from.right_center(), to.left_center())
+                    } else {
+                        (from.center_bottom(), to.center_top())
+                    };
+                    painter.arrow(start, end - start, stroke);
+                }
+
+                for (index, (rect, (cid, chapter))) in rects.iter().zip(chapters).enumerate() {
+                    let has_issue = graph
+                        .issues
+                        .iter()
+                        .any(|issue| issue.chapter == Some(index));
+                    let stroke = if self.selected == Some(index) {
+                        ui.visuals().selection.stroke
+                    } else if has_issue {
+                        Stroke::new(1.5, ui.visuals().error_fg_color)
+                    } else {
+                        ui.visuals().widgets.noninteractive.bg_stroke
+                    };
+                    let text_color = if graph.depths[index].is_none() {
+                        ui.visuals().warn_fg_color
+                    } else {
+                        ui.visuals().text_color()
+                    };
+                    painter.rect(*rect, 4., ui.visuals().extreme_bg_color, stroke);
+                    painter.text(
+                        rect.center(),
+                        Align2::CENTER_CENTER,
+                        cid,
+                        FontId::proportional(13.),
+                        text_color,
+                    );
+                    let node = ui
+                        .interact(
+                            *rect,
+                            ui.id().with(("chapter_graph_node", index)),
+                            Sense::click(),
+                        )
+                        .on_hover_text(chapter.text(state).into_owned());
+                    if node.clicked() {
+                        self.selected = Some(index);
+                    }
+                    if node.double_clicked() {
+                        queue_transition(Transition::new(Screens::Chapter, index));
+                    }
+                }
+            });
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, state: &EditorState) {
+        if self.start.is_empty() {
+            if let Some(cid) = state
+                .chapter
+                .read(|chapters| chapters.keys().next().cloned())
+            {
+                self.start = cid;
+            }
+        }
+        let key = (
+            state.chapter.revision_number(),
+            state.challenge.revision_number(),
+            self.start.clone(),
+        );
+        let graph = self
+            .graph
+            .get(key, || {
+                // Both sheets live in the chapter book, so copy the challenges out instead of
+                // nesting locks.
+                let challenges = state.challenge.read(|data| data.clone());
+                Arc::new(
+                    state
+                        .chapter
+                        .read(|chapters| build_chapter_graph(chapters, &challenges, &self.start)),
+                )
+            })
+            .clone();
+        state.chapter.read(|chapters| {
+            if self.selected.is_some_and(|index| index >= chapters.len()) {
+                self.selected = None;
+            }
+
+            SidePanel::left("chapter_graph_side_panel")
+                .default_width(300.)
+                .show(ctx, |ui| self.side_panel(ui, state, chapters, &graph));
+
+            CentralPanel::default().show(ctx, |ui| {
+                ui.heading("Chapter Progression");
+                ui.label("Click a chapter to select it, double click to open it.");
+                ui.separator();
+                self.graph_view(ui, state, chapters, &graph);
+            });
+        });
+    }
+}
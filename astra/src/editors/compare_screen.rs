@@ -0,0 +1,429 @@
+use std::collections::HashSet;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+use astra_core::error::Result;
+use astra_core::{Astra, AstraProject, BookDiff, MessageDiff, ReferenceProject};
+use astra_types::RowDiffKind;
+use egui::{
+    Button, CentralPanel, CollapsingHeader, ComboBox, Grid, ScrollArea, SidePanel, TextEdit, Ui,
+};
+use egui_notify::Toasts;
+use parking_lot::RwLock;
+
+use crate::AppConfig;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CompareSelection {
+    Book(usize),
+    Messages,
+}
+
+enum ReferenceLoader {
+    NotLoaded,
+    Loading(Receiver<Result<ReferenceProject>>),
+    Loaded(Box<ReferenceProject>),
+    Error(String),
+}
+
+/// Compare the open project against a second, read-only project and pull rows across.
+pub struct CompareScreen {
+    astra: Arc<RwLock<Astra>>,
+    loader: ReferenceLoader,
+    project_index: Option<usize>,
+    book_diffs: Vec<BookDiff>,
+    message_diffs: Vec<MessageDiff>,
+    dispos_error: Option<String>,
+    selection: Option<CompareSelection>,
+    checked_rows: HashSet<(String, String, String)>,
+    checked_messages: HashSet<(String, String)>,
+    search: String,
+}
+
+impl CompareScreen {
+    pub fn new(astra: Arc<RwLock<Astra>>) -> Self {
+        Self {
+            astra,
+            loader: ReferenceLoader::NotLoaded,
+            project_index: None,
+            book_diffs: vec![],
+            message_diffs: vec![],
+            dispos_error: None,
+            selection: None,
+            checked_rows: HashSet::new(),
+            checked_messages: HashSet::new(),
+            search: String::new(),
+        }
+    }
+
+    fn load(&mut self, project: AstraProject) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            sender.send(ReferenceProject::load(project)).unwrap();
+        });
+        self.loader = ReferenceLoader::Loading(receiver);
+    }
+
+    fn refresh(&mut self) {
+        if let ReferenceLoader::Loaded(reference) = &mut self.loader {
+            let mut astra = self.astra.write();
+            self.book_diffs = astra.diff_books(reference);
+            self.message_diffs = astra.diff_messages(reference);
+            match astra.diff_chapter_dispos(reference) {
+                Ok(diffs) => {
+                    self.book_diffs.extend(diffs);
+                    self.dispos_error = None;
+                }
+                Err(err) => self.dispos_error = Some(format!("{:?}", err)),
+            }
+        }
+        self.checked_rows.clear();
+        self.checked_messages.clear();
+        if let Some(CompareSelection::Book(index)) = self.selection {
+            if index >= self.book_diffs.len() {
+                self.selection = None;
+            }
+        }
+    }
+
+    /// The checked rows in the order they have to be pulled.
+    /// List sheets are keyed by index, so removals go from the last row backwards
+    /// and everything else from the first row forwards.
+    fn checked_rows_in_pull_order(&self) -> Vec<(String, String, String)> {
+        let mut rows: Vec<(String, String, String, RowDiffKind)> = self
+            .book_diffs
+            .iter()
+            .flat_map(|diff| {
+                diff.sheets.iter().flat_map(move |sheet| {
+                    sheet.rows.iter().map(move |row| {
+                        let sheet = sheet.sheet.to_string();
+                        (diff.book.clone(), sheet, row.key.clone(), row.kind)
+                    })
+                })
+            })
+            .filter(|(book, sheet, key, _)| {
+                self.checked_rows
+                    .contains(&(book.clone(), sheet.clone(), key.clone()))
+            })
+            .collect();
+        rows.sort_by_key(|(book, sheet, key, kind)| {
+            let index = key.parse::<i64>().unwrap_or_default();
+            let removed = *kind == RowDiffKind::Removed;
+            (
+                book.clone(),
+                sheet.clone(),
+                !removed,
+                if removed { -index } else { index },
+            )
+        });
+        rows.into_iter()
+            .map(|(book, sheet, key, _)| (book, sheet, key))
+            .collect()
+    }
+
+    fn pull_checked(&mut self, toasts: &mut Toasts) {
+        let rows = self.checked_rows_in_pull_order();
+        let reference = match &mut self.loader {
+            ReferenceLoader::Loaded(reference) => reference,
+            _ => return,
+        };
+        let mut astra = self.astra.write();
+        let mut pulled = 0;
+        for (book, sheet, key) in &rows {
+            match astra.pull_row(reference, book, sheet, key) {
+                Ok(true) => pulled += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    toasts.error(format!("{:?}", err));
+                }
+            }
+        }
+        for (archive, key) in &self.checked_messages {
+            match astra.pull_message(reference, archive, key) {
+                Ok(true) => pulled += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    toasts.error(format!("{:?}", err));
+                }
+            }
+        }
+        drop(astra);
+        toasts
+            .success(format!("Pulled {} change(s)", pulled))
+            .set_duration(Some(Duration::from_secs(2)));
+        self.refresh();
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, config: &AppConfig, toasts: &mut Toasts) {
+        if let ReferenceLoader::Loading(receiver) = &self.loader {
+            if let Ok(result) = receiver.try_recv() {
+                self.loader = match result {
+                    Ok(reference) => ReferenceLoader::Loaded(Box::new(reference)),
+                    Err(err) => ReferenceLoader::Error(format!("{:?}", err)),
+                };
+                self.selection = None;
+                self.refresh();
+            }
+        }
+
+        egui::TopBottomPanel::top("compare_screen_top_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Compare With");
+                ComboBox::from_id_source("compare_screen_project")
+                    .selected_text(
+                        self.project_index
+                            .and_then(|index| config.projects.get(index))
+                            .map(|project| project.name.as_str())
+                            .unwrap_or_default(),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (index, project) in config.projects.iter().enumerate() {
+                            if Some(index) != config.active_project {
+                                ui.selectable_value(
+                                    &mut self.project_index,
+                                    Some(index),
+                                    &project.name,
+                                );
+                            }
+                        }
+                    });
+                let loading = matches!(self.loader, ReferenceLoader::Loading(_));
+                let project = self
+                    .project_index
+                    .and_then(|index| config.projects.get(index))
+                    .filter(|project| project.is_valid());
+                if ui
+                    .add_enabled(project.is_some() && !loading, Button::new("Load"))
+                    .clicked()
+                {
+                    if let Some(project) = project {
+                        self.load(project.clone().into());
+                    }
+                }
+                let loaded = matches!(self.loader, ReferenceLoader::Loaded(_));
+                if ui.add_enabled(loaded, Button::new("⟳ Refresh")).clicked() {
+                    self.refresh();
+                }
+                let checked = self.checked_rows.len() + self.checked_messages.len();
+                if ui
+                    .add_enabled(
+                        checked > 0,
+                        Button::new(format!("⮨ Pull {} Selected", checked)),
+                    )
+                    .clicked()
+                {
+                    self.pull_checked(toasts);
+                }
+            });
+        });
+
+        match &self.loader {
+            ReferenceLoader::NotLoaded => {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.centered_and_justified(|ui| {
+                        ui.heading("Select a project to compare against.");
+                    });
+                });
+                return;
+            }
+            ReferenceLoader::Loading(_) => {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.centered_and_justified(|ui| {
+                        ui.add(egui::Spinner::new().size(96.0));
+                    });
+                });
+                return;
+            }
+            ReferenceLoader::Error(error) => {
+                let mut error = error.clone();
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.heading("Failed to load project");
+                    ui.add_enabled(
+                        false,
+                        TextEdit::multiline(&mut error).desired_width(f32::INFINITY),
+                    );
+                });
+                return;
+            }
+            ReferenceLoader::Loaded(_) => {}
+        }
+
+        SidePanel::left("compare_screen_left_panel")
+            .default_width(250.)
+            .show(ctx, |ui| {
+                ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        if let Some(error) = &self.dispos_error {
+                            ui.colored_label(ui.visuals().error_fg_color, error);
+                        }
+                        if self.book_diffs.is_empty() && self.message_diffs.is_empty() {
+                            ui.label("No differences found.");
+                        }
+                        for (index, diff) in self.book_diffs.iter().enumerate() {
+                            let count: usize =
+                                diff.sheets.iter().map(|sheet| sheet.rows.len()).sum();
+                            ui.selectable_value(
+                                &mut self.selection,
+                                Some(CompareSelection::Book(index)),
+                                format!("{} ({})", diff.book, count),
+                            );
+                        }
+                        if !self.message_diffs.is_empty() {
+                            ui.separator();
+                            ui.selectable_value(
+                                &mut self.selection,
+                                Some(CompareSelection::Messages),
+                                format!("Messages ({})", self.message_diffs.len()),
+                            );
+                        }
+                    });
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.search)
+                    .hint_text("Filter keys...")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.separator();
+            let search = self.search.to_lowercase();
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| match self.selection {
+                    Some(CompareSelection::Book(index)) => {
+                        if let Some(diff) = self.book_diffs.get(index) {
+                            book_diff_ui(ui, diff, &search, &mut self.checked_rows);
+                        }
+                    }
+                    Some(CompareSelection::Messages) => {
+                        message_diff_ui(
+                            ui,
+                            &self.message_diffs,
+                            &search,
+                            &mut self.checked_messages,
+                        );
+                    }
+                    None => {
+                        ui.centered_and_justified(|ui| {
+                            ui.heading("Select a book.");
+                        });
+                    }
+                });
+        });
+    }
+}
+
+fn kind_label(kind: RowDiffKind) -> &'static str {
+    match kind {
+        RowDiffKind::Added => "➕ Added",
+        RowDiffKind::Removed => "➖ Removed",
+        RowDiffKind::Changed => "✏ Changed",
+    }
+}
+
+fn book_diff_ui(
+    ui: &mut Ui,
+    diff: &BookDiff,
+    search: &str,
+    checked: &mut HashSet<(String, String, String)>,
+) {
+    for sheet in &diff.sheets {
+        if sheet.rows.is_empty() {
+            continue;
+        }
+        CollapsingHeader::new(format!("{} ({})", sheet.sheet, sheet.rows.len()))
+            .id_source((&diff.book, sheet.sheet))
+            .default_open(true)
+            .show(ui, |ui| {
+                for row in &sheet.rows {
+                    if !search.is_empty() && !row.key.to_lowercase().contains(search) {
+                        continue;
+                    }
+                    let id = (diff.book.clone(), sheet.sheet.to_string(), row.key.clone());
+                    let mut is_checked = checked.contains(&id);
+                    ui.horizontal(|ui| {
+                        if ui.checkbox(&mut is_checked, "").changed() {
+                            if is_checked {
+                                checked.insert(id.clone());
+                            } else {
+                                checked.remove(&id);
+                            }
+                        }
+                        ui.label(kind_label(row.kind));
+                        if row.fields.is_empty() {
+                            ui.label(&row.key);
+                        } else {
+                            CollapsingHeader::new(format!(
+                                "{} ({} fields)",
+                                row.key,
+                                row.fields.len()
+                            ))
+                            .id_source(&id)
+                            .show(ui, |ui| {
+                                Grid::new(ui.auto_id_with("compare_fields"))
+                                    .striped(true)
+                                    .num_columns(3)
+                                    .show(ui, |ui| {
+                                        ui.strong("Field");
+                                        ui.strong("Current");
+                                        ui.strong("Other");
+                                        ui.end_row();
+                                        for field in &row.fields {
+                                            ui.label(&field.field);
+                                            ui.label(&field.ours);
+                                            ui.label(&field.theirs);
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        }
+                    });
+                }
+            });
+    }
+}
+
+fn message_diff_ui(
+    ui: &mut Ui,
+    diffs: &[MessageDiff],
+    search: &str,
+    checked: &mut HashSet<(String, String)>,
+) {
+    Grid::new("compare_messages_grid")
+        .striped(true)
+        .num_columns(5)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.strong("Archive");
+            ui.strong("Key");
+            ui.strong("Current");
+            ui.strong("Other");
+            ui.end_row();
+            for diff in diffs {
+                if !search.is_empty() && !diff.key.to_lowercase().contains(search) {
+                    continue;
+                }
+                let id = (diff.archive.clone(), diff.key.clone());
+                let mut is_checked = checked.contains(&id);
+                // Messages can't be removed, so there is nothing to pull.
+                let enabled = diff.theirs.is_some();
+                if ui
+                    .add_enabled(enabled, egui::Checkbox::new(&mut is_checked, ""))
+                    .changed()
+                {
+                    if is_checked {
+                        checked.insert(id);
+                    } else {
+                        checked.remove(&id);
+                    }
+                }
+                ui.label(&diff.archive);
+                ui.label(&diff.key);
+                ui.label(diff.ours.as_deref().unwrap_or("{missing}"));
+                ui.label(diff.theirs.as_deref().unwrap_or("{missing}"));
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,227 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use anyhow::Result;
+use astra_core::{ItemSource, ItemSourceKind};
+use egui::{CentralPanel, ComboBox, TextEdit};
+use egui_extras::{Column, TableBuilder};
+use egui_notify::Toasts;
+use rfd::FileDialog;
+
+use crate::{EditorState, ViewItem};
+
+const KINDS: [ItemSourceKind; 4] = [
+    ItemSourceKind::Drop,
+    ItemSourceKind::ChapterReward,
+    ItemSourceKind::RewardTable,
+    ItemSourceKind::Shop,
+];
+
+const COLUMNS: [&str; 7] = [
+    "Item",
+    "Name",
+    "Kind",
+    "Chapter",
+    "Difficulty",
+    "Source",
+    "Detail",
+];
+
+struct ReportRow {
+    source: ItemSource,
+    name: String,
+}
+
+impl ReportRow {
+    fn column(&self, column: usize) -> &str {
+        match column {
+            0 => &self.source.iid,
+            1 => &self.name,
+            2 => self.source.kind.label(),
+            3 => &self.source.chapter,
+            4 => &self.source.difficulty,
+            5 => &self.source.source,
+            _ => &self.source.detail,
+        }
+    }
+
+    fn compare(&self, other: &Self, column: usize) -> Ordering {
+        match column {
+            2 => self.source.kind.cmp(&other.source.kind),
+            _ => self.column(column).cmp(other.column(column)),
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Every way the player can obtain each item, sortable and exportable to CSV.
+#[derive(Default)]
+pub struct ItemReportScreen {
+    rows: Vec<ReportRow>,
+    filter: String,
+    kind: Option<ItemSourceKind>,
+    sort_column: usize,
+    descending: bool,
+}
+
+impl ItemReportScreen {
+    fn generate(&mut self, state: &EditorState) -> Result<()> {
+        let sources = state.astra.write().item_report()?;
+        self.rows = state.item.read(|items| {
+            state.accessory.read(|accessories| {
+                sources
+                    .into_iter()
+                    .map(|source| {
+                        let name = items
+                            .get(&source.iid)
+                            .map(|item| item.text(state).into_owned())
+                            .or_else(|| {
+                                accessories
+                                    .get(&source.iid)
+                                    .map(|accessory| accessory.text(state).into_owned())
+                            })
+                            .unwrap_or_default();
+                        ReportRow { source, name }
+                    })
+                    .collect()
+            })
+        });
+        self.sort();
+        Ok(())
+    }
+
+    fn sort(&mut self) {
+        let column = self.sort_column;
+        self.rows.sort_by(|a, b| a.compare(b, column));
+        if self.descending {
+            self.rows.reverse();
+        }
+    }
+
+    fn visible_rows(&self) -> Vec<&ReportRow> {
+        let filter = self.filter.to_lowercase();
+        self.rows
+            .iter()
+            .filter(|row| self.kind.map_or(true, |kind| row.source.kind == kind))
+            .filter(|row| {
+                filter.is_empty()
+                    || (0..COLUMNS.len())
+                        .any(|column| row.column(column).to_lowercase().contains(&filter))
+            })
+            .collect()
+    }
+
+    fn export(&self) -> Result<bool> {
+        let Some(path) = FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("item_report.csv")
+            .save_file()
+        else {
+            return Ok(false);
+        };
+        let mut csv = COLUMNS.join(",");
+        csv.push('\n');
+        for row in self.visible_rows() {
+            let line: Vec<String> = (0..COLUMNS.len())
+                .map(|column| csv_field(row.column(column)))
+                .collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        std::fs::write(path, csv)?;
+        Ok(true)
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, state: &EditorState, toasts: &mut Toasts) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Item Report");
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Generate")
+                    .on_hover_text("Reads every chapter's dispos, which can take a moment.")
+                    .clicked()
+                {
+                    if let Err(err) = self.generate(state) {
+                        toasts.error(format!("Failed to generate item report: {:#}", err));
+                    }
+                }
+                ComboBox::from_id_source("item_report_kind")
+                    .selected_text(self.kind.map_or("All Sources", |kind| kind.label()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.kind, None, "All Sources");
+                        for kind in KINDS {
+                            ui.selectable_value(&mut self.kind, Some(kind), kind.label());
+                        }
+                    });
+                ui.add(TextEdit::singleline(&mut self.filter).hint_text("Filter"));
+                if ui.button("Export CSV").clicked() {
+                    match self.export() {
+                        Ok(true) => {
+                            toasts
+                                .success("Exported item report")
+                                .set_duration(Some(Duration::from_secs(2)));
+                        }
+                        Ok(false) => {}
+                        Err(err) => {
+                            toasts.error(format!("Failed to export item report: {:#}", err));
+                        }
+                    }
+                }
+            });
+            ui.separator();
+
+            let mut clicked_column = None;
+            let rows = self.visible_rows();
+            ui.label(format!("{} source(s)", rows.len()));
+            TableBuilder::new(ui)
+                .striped(true)
+                .resizable(true)
+                .columns(Column::auto().at_least(80.), COLUMNS.len() - 1)
+                .column(Column::remainder())
+                .header(20., |mut header| {
+                    for (column, label) in COLUMNS.iter().enumerate() {
+                        header.col(|ui| {
+                            let text = if column == self.sort_column {
+                                format!("{} {}", label, if self.descending { "⏷" } else { "⏶" })
+                            } else {
+                                label.to_string()
+                            };
+                            if ui
+                                .selectable_label(column == self.sort_column, text)
+                                .clicked()
+                            {
+                                clicked_column = Some(column);
+                            }
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(18., rows.len(), |mut row| {
+                        let report_row = rows[row.index()];
+                        for column in 0..COLUMNS.len() {
+                            row.col(|ui| {
+                                ui.label(report_row.column(column));
+                            });
+                        }
+                    });
+                });
+
+            if let Some(column) = clicked_column {
+                if column == self.sort_column {
+                    self.descending = !self.descending;
+                } else {
+                    self.sort_column = column;
+                    self.descending = false;
+                }
+                self.sort();
+            }
+        });
+    }
+}
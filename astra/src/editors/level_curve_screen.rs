@@ -0,0 +1,180 @@
+use astra_core::Difficulty;
+use egui::{CentralPanel, ComboBox, DragValue, Grid, RichText, ScrollArea};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+use crate::{gap_swings, level_curve, ChapterLevels, EditorState};
+
+fn format_level(level: Option<f32>) -> String {
+    level
+        .map(|level| format!("{:.1}", level))
+        .unwrap_or_default()
+}
+
+/// Enemy levels across the campaign against the chart's expected player levels.
+pub struct LevelCurveScreen {
+    levels: Vec<ChapterLevels>,
+    difficulty: Difficulty,
+    threshold: f32,
+}
+
+impl Default for LevelCurveScreen {
+    fn default() -> Self {
+        Self {
+            levels: vec![],
+            difficulty: Difficulty::Normal,
+            threshold: 3.,
+        }
+    }
+}
+
+impl LevelCurveScreen {
+    fn chart(&self, ui: &mut egui::Ui) {
+        let difficulty = self.difficulty;
+        let series = |value: &dyn Fn(&ChapterLevels) -> Option<f32>| -> Vec<[f64; 2]> {
+            self.levels
+                .iter()
+                .enumerate()
+                .filter_map(|(index, chapter)| Some([index as f64, value(chapter)? as f64]))
+                .collect()
+        };
+        Plot::new("level_curve_chart")
+            .legend(Legend::default())
+            .height(ui.available_height() / 2.)
+            .x_axis_label("Chapter #")
+            .y_axis_label("Level")
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(series(&|c| c.expected(difficulty))))
+                        .name("Chart Average"),
+                );
+                plot_ui.line(
+                    Line::new(PlotPoints::from(series(&|c| c.recommended_level())))
+                        .name("Recommended"),
+                );
+                plot_ui.line(
+                    Line::new(PlotPoints::from(series(&|c| {
+                        c.enemy_displayed_average(difficulty)
+                    })))
+                    .name("Enemy Average (Displayed)"),
+                );
+                plot_ui.line(
+                    Line::new(PlotPoints::from(series(&|c| c.enemy_average(difficulty))))
+                        .name("Enemy Average"),
+                );
+                plot_ui.line(
+                    Line::new(PlotPoints::from(series(&|c| {
+                        c.enemy_max(difficulty).map(|level| level as f32)
+                    })))
+                    .name("Enemy Max"),
+                );
+            });
+    }
+
+    fn table(&self, ui: &mut egui::Ui) {
+        let swings = gap_swings(&self.levels, self.difficulty);
+        ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                Grid::new("level_curve_table")
+                    .striped(true)
+                    .num_columns(9)
+                    .show(ui, |ui| {
+                        for header in [
+                            "#",
+                            "Chapter",
+                            "Recommended",
+                            "Hold",
+                            "Chart Avg.",
+                            "Enemy Avg.",
+                            "Enemy Max",
+                            "Gap",
+                            "Swing",
+                        ] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (index, (chapter, swing)) in self.levels.iter().zip(swings).enumerate()
+                        {
+                            ui.label(index.to_string());
+                            ui.label(&chapter.cid);
+                            ui.label(chapter.recommended_level.to_string());
+                            ui.label(chapter.hold_level.to_string());
+                            ui.label(format_level(chapter.expected(self.difficulty)));
+                            ui.label(format_level(chapter.enemy_average(self.difficulty)));
+                            ui.label(
+                                chapter
+                                    .enemy_max(self.difficulty)
+                                    .map(|level| level.to_string())
+                                    .unwrap_or_default(),
+                            );
+                            ui.label(format_level(chapter.gap(self.difficulty)));
+                            match swing {
+                                Some(swing) if swing >= self.threshold => {
+                                    ui.label(
+                                        RichText::new(format!("+{:.1} jump", swing))
+                                            .color(ui.visuals().error_fg_color),
+                                    );
+                                }
+                                Some(swing) if swing <= -self.threshold => {
+                                    ui.label(
+                                        RichText::new(format!("{:.1} sag", swing))
+                                            .color(ui.visuals().warn_fg_color),
+                                    );
+                                }
+                                swing => {
+                                    ui.label(format_level(swing));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, state: &EditorState) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Level Curve");
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Generate")
+                    .on_hover_text("Reads every chapter's dispos, which can take a moment.")
+                    .clicked()
+                {
+                    self.levels = level_curve(state);
+                }
+                ComboBox::from_id_source("level_curve_difficulty")
+                    .selected_text(self.difficulty.label())
+                    .show_ui(ui, |ui| {
+                        for difficulty in Difficulty::FLAGGED {
+                            ui.selectable_value(
+                                &mut self.difficulty,
+                                difficulty,
+                                difficulty.label(),
+                            );
+                        }
+                    });
+                ui.label("Flag swings of");
+                ui.add(
+                    DragValue::new(&mut self.threshold)
+                        .clamp_range(0.5..=20.)
+                        .speed(0.1),
+                );
+                ui.label("levels or more");
+            });
+            ui.label(
+                "Levels count promoted classes' internal levels, except the recommended level \
+                and the displayed enemy average. Gap is the average enemy level minus the chart \
+                average, or the displayed average minus the recommended level when the chart \
+                has no entry. Swing is the change in gap since the previous chapter.",
+            );
+            ui.separator();
+            if self.levels.is_empty() {
+                ui.label("Press Generate to build the curve.");
+                return;
+            }
+            self.chart(ui);
+            ui.separator();
+            self.table(ui);
+        });
+    }
+}
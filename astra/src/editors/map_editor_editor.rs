@@ -4,8 +4,9 @@ use egui::Ui;
 use indexmap::IndexMap;
 
 use crate::{
-    editor_tab_strip, id_field, keyed_add_modal_content, model_drop_down, sheet_retriever,
-    CachedView, EditorState, KeyedViewItem, ListEditorContent, PropertyGrid, ViewItem,
+    editor_tab_strip, id_field, keyed_add_modal_content, known_sound_events, model_drop_down,
+    name_options, sheet_retriever, string_drop_down, CachedValue, CachedView, EditorState,
+    KeyedViewItem, ListEditorContent, NameOption, PropertyGrid, ViewItem,
 };
 
 use astra_types::{MapEditorBook, MapEditorCategory, MapEditorObject};
@@ -92,6 +93,8 @@ pub struct MapEditorEditor {
     objects: MapEditorObjectSheet,
     categories: MapEditorCategorySheet,
     categories_cache: CachedView<MapEditorCategorySheetRetriever, MapEditorBook, MapEditorCategory>,
+    /// Keyed by the sound event and music revisions.
+    sound_events: CachedValue<(usize, usize), IndexMap<String, NameOption>>,
     objects_content:
         ListEditorContent<IndexMap<String, MapEditorObject>, MapEditorObject, EditorState>,
     categories_content:
@@ -105,6 +108,7 @@ impl MapEditorEditor {
             objects: state.map_editor_objects.clone(),
             categories: state.map_editor_categories.clone(),
             categories_cache: CachedView::new(state.map_editor_categories.clone(), state),
+            sound_events: Default::default(),
             objects_content: ListEditorContent::new("objects_editor")
                 .with_add_modal_content(keyed_add_modal_content),
             categories_content: ListEditorContent::new("categories_editor")
@@ -112,6 +116,12 @@ impl MapEditorEditor {
         }
     }
 
+    /// Select an object. Switches to the objects tab.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.tab = Tab::MapEditorObject;
+        self.objects_content.select(index);
+    }
+
     pub fn tab_strip(&mut self, ui: &mut Ui) {
         editor_tab_strip(ui, |ui| {
             ui.selectable_value(&mut self.tab, Tab::MapEditorObject, "Objects");
@@ -124,13 +134,22 @@ impl MapEditorEditor {
 
         match self.tab {
             Tab::MapEditorObject => {
+                let sound_events = self.sound_events.get(
+                    (
+                        state.sound_events.revision_number(),
+                        state.music.revision_number(),
+                    ),
+                    || name_options(&known_sound_events(state)),
+                );
                 self.objects_content.left_panel(ctx, &self.objects, state);
                 self.objects.write(|data| {
                     self.objects_content.content(ctx, data, |ui, selection| {
                         PropertyGrid::new("objects", selection)
                             .new_section("")
                             .field("Object Name", |ui, d| ui.add(id_field(&mut d.object_name)))
-                            .default_field("Sound Event", |d| &mut d.sound_event)
+                            .field("Sound Event", |ui, d| {
+                                ui.add(string_drop_down(sound_events, &mut d.sound_event))
+                            })
                             .field("Category", |ui, d| {
                                 ui.add(model_drop_down(
                                     self.categories_cache.get(),
@@ -5,9 +5,13 @@ mod anim_set_editor;
 mod animal_editor;
 mod arena_editor;
 mod asset_table_editor;
+mod audio_check_screen;
 mod calculator_editor;
+mod challenge_editor;
 mod chapter_editor;
+mod chapter_graph_screen;
 mod chart_editor;
+mod compare_screen;
 mod cook_editor;
 mod dragon_ride_editor;
 mod effect_editor;
@@ -18,9 +22,11 @@ mod friend_list_editor;
 mod god_editor;
 mod hub_editor;
 mod item_editor;
+mod item_report_screen;
 mod job_editor;
 mod kill_bonus_editor;
 mod later_talk_editor;
+mod level_curve_screen;
 mod map_editor_editor;
 mod mascot_editor;
 mod misc_editor;
@@ -31,6 +37,8 @@ mod param_editor;
 mod person_editor;
 mod photograph_editor;
 mod profile_card_editor;
+mod randomizer_screen;
+mod recipe_screen;
 mod relay_editor;
 mod reliance_editor;
 mod ring_editor;
@@ -38,6 +46,8 @@ mod save_screen;
 mod script_manager;
 mod shop_editor;
 mod skill_editor;
+mod spawn_tuning_screen;
+mod stat_planner_screen;
 mod terrain_editor;
 mod text_data_editor;
 mod title_editor;
@@ -50,9 +60,13 @@ pub use anim_set_editor::*;
 pub use animal_editor::*;
 pub use arena_editor::*;
 pub use asset_table_editor::*;
+pub use audio_check_screen::*;
 pub use calculator_editor::*;
+pub use challenge_editor::*;
 pub use chapter_editor::*;
+pub use chapter_graph_screen::*;
 pub use chart_editor::*;
+pub use compare_screen::*;
 pub use cook_editor::*;
 pub use dragon_ride_editor::*;
 pub use effect_editor::*;
@@ -63,9 +77,11 @@ pub use friend_list_editor::*;
 pub use god_editor::*;
 pub use hub_editor::*;
 pub use item_editor::*;
+pub use item_report_screen::*;
 pub use job_editor::*;
 pub use kill_bonus_editor::*;
 pub use later_talk_editor::*;
+pub use level_curve_screen::*;
 pub use map_editor_editor::*;
 pub use mascot_editor::*;
 pub use misc_editor::*;
@@ -76,6 +92,8 @@ pub use param_editor::*;
 pub use person_editor::*;
 pub use photograph_editor::*;
 pub use profile_card_editor::*;
+pub use randomizer_screen::*;
+pub use recipe_screen::*;
 pub use relay_editor::*;
 pub use reliance_editor::*;
 pub use ring_editor::*;
@@ -83,6 +101,8 @@ pub use save_screen::*;
 pub use script_manager::*;
 pub use shop_editor::*;
 pub use skill_editor::*;
+pub use spawn_tuning_screen::*;
+pub use stat_planner_screen::*;
 pub use terrain_editor::*;
 pub use text_data_editor::*;
 pub use title_editor::*;
@@ -3,8 +3,9 @@ use std::borrow::Cow;
 use indexmap::IndexMap;
 
 use crate::{
-    id_field, keyed_add_modal_content, msbt_key_value_multiline, msbt_key_value_singleline,
-    sheet_retriever, standard_keyed_display, EditorState, KeyedViewItem, ListEditorContent,
+    id_field, keyed_add_modal_content, known_sound_events, msbt_key_value_multiline,
+    msbt_key_value_singleline, name_options, sheet_retriever, standard_keyed_display,
+    string_drop_down, CachedValue, EditorState, KeyedViewItem, ListEditorContent, NameOption,
     PropertyGrid, ViewItem,
 };
 
@@ -32,6 +33,8 @@ impl KeyedViewItem for Movie {
 
 pub struct MovieEditor {
     movies: MovieSheet,
+    /// Keyed by the sound event and music revisions.
+    sound_events: CachedValue<(usize, usize), IndexMap<String, NameOption>>,
     movies_content: ListEditorContent<IndexMap<String, Movie>, Movie, EditorState>,
 }
 
@@ -39,12 +42,24 @@ impl MovieEditor {
     pub fn new(state: &EditorState) -> Self {
         Self {
             movies: state.movies.clone(),
+            sound_events: Default::default(),
             movies_content: ListEditorContent::new("movies_editor")
                 .with_add_modal_content(keyed_add_modal_content),
         }
     }
 
+    pub fn select(&mut self, index: Option<usize>) {
+        self.movies_content.select(index);
+    }
+
     pub fn show(&mut self, ctx: &egui::Context, state: &EditorState) {
+        let sound_events = self.sound_events.get(
+            (
+                state.sound_events.revision_number(),
+                state.music.revision_number(),
+            ),
+            || name_options(&known_sound_events(state)),
+        );
         self.movies_content.left_panel(ctx, &self.movies, state);
 
         self.movies.write(|data| {
@@ -62,23 +77,41 @@ impl MovieEditor {
                     })
                     .default_field("Condition", |d| &mut d.condition)
                     .default_field("No", |d| &mut d.no)
-                    .default_field("Before Sound Event Name 1", |d| {
-                        &mut d.before_sound_event_name_1
+                    .field("Before Sound Event Name 1", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.before_sound_event_name_1,
+                        ))
                     })
-                    .default_field("Before Sound Event Name 2", |d| {
-                        &mut d.before_sound_event_name_2
+                    .field("Before Sound Event Name 2", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.before_sound_event_name_2,
+                        ))
                     })
-                    .default_field("Before Sound Event Name 3", |d| {
-                        &mut d.before_sound_event_name_3
+                    .field("Before Sound Event Name 3", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.before_sound_event_name_3,
+                        ))
                     })
-                    .default_field("After Sound Event Name 1", |d| {
-                        &mut d.after_sound_event_name_1
+                    .field("After Sound Event Name 1", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.after_sound_event_name_1,
+                        ))
                     })
-                    .default_field("After Sound Event Name 2", |d| {
-                        &mut d.after_sound_event_name_2
+                    .field("After Sound Event Name 2", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.after_sound_event_name_2,
+                        ))
                     })
-                    .default_field("After Sound Event Name 3", |d| {
-                        &mut d.after_sound_event_name_3
+                    .field("After Sound Event Name 3", |ui, d| {
+                        ui.add(string_drop_down(
+                            sound_events,
+                            &mut d.after_sound_event_name_3,
+                        ))
                     })
                     .default_field("Mess File Name", |d| &mut d.mess_file_name)
                     .default_field("Dlc Directory Name", |d| &mut d.dlc_directory_name)
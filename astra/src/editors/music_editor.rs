@@ -44,6 +44,10 @@ impl MusicEditor {
         }
     }
 
+    pub fn select(&mut self, index: Option<usize>) {
+        self.music_content.select(index);
+    }
+
     pub fn show(&mut self, ctx: &egui::Context, state: &EditorState) {
         self.music_content.left_panel(ctx, &self.music, state);
 
@@ -217,7 +217,11 @@ impl PersonEditor {
                     .new_section("Misc.")
                     .default_field("Die", |p| &mut p.die)
                     .default_field("Support Category", |p| &mut p.support_category)
-                    .default_field("Combat Music", |p| &mut p.combat_bgm)
+                    .field("Combat Music", |ui, p| {
+                        state
+                            .music
+                            .read(|data| ui.add(model_drop_down(data, state, &mut p.combat_bgm)))
+                    })
                     .field("Exist Die Chapter", |ui, p| {
                         state
                             .chapter
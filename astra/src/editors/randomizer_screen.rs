@@ -0,0 +1,162 @@
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use astra_core::error::Result;
+use astra_core::{randomize, AstraProject, RandomizerOptions, RandomizerReport};
+use egui::{Button, CentralPanel, DragValue, Grid, Slider, TextEdit};
+use egui_modal::Modal;
+use egui_notify::Toasts;
+
+use crate::{AppConfig, AppState, LoadProjectState};
+
+/// Randomize vanilla data from a seed and a set of options.
+#[derive(Default)]
+pub struct RandomizerScreen {
+    rx: Option<Receiver<Result<RandomizerReport>>>,
+    error: Option<String>,
+}
+
+impl RandomizerScreen {
+    fn run(&mut self, project: AstraProject, options: RandomizerOptions) {
+        let (sx, rx) = std::sync::mpsc::channel();
+        self.rx = Some(rx);
+        std::thread::spawn(move || {
+            sx.send(randomize(&project, &options)).unwrap();
+        });
+    }
+
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut AppConfig,
+        next_state: &mut Option<AppState>,
+        toasts: &mut Toasts,
+    ) {
+        if let Some(rx) = &self.rx {
+            if let Ok(result) = rx.try_recv() {
+                self.rx = None;
+                match result {
+                    Ok(report) => {
+                        let message = format!(
+                            "Randomized {} characters, {} spawns, {} shop items, {} emblem levels",
+                            report.persons, report.spawns, report.shop_items, report.god_levels
+                        );
+                        toasts
+                            .success(message)
+                            .set_duration(Some(Duration::from_secs(4)));
+                        // Reload so the editors see the new data.
+                        *next_state = Some(AppState::LoadProject(LoadProjectState::default()));
+                    }
+                    Err(err) => self.error = Some(format!("{:?}", err)),
+                }
+            }
+        }
+
+        let project = match config
+            .active_project
+            .and_then(|index| config.projects.get_mut(index))
+        {
+            Some(project) => project,
+            None => return,
+        };
+
+        CentralPanel::default().show(ctx, |ui| {
+            let modal = Modal::new(ctx, "randomizer_screen_error_modal");
+            modal.show(|ui| {
+                modal.title(ui, "Failed to Randomize");
+                let mut buffer = self.error.clone().unwrap_or_default();
+                ui.add_enabled_ui(false, |ui| {
+                    ui.add(
+                        TextEdit::multiline(&mut buffer)
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(8),
+                    );
+                });
+                modal.buttons(ui, |ui| {
+                    if modal.button(ui, "Close").clicked() {
+                        self.error = None;
+                    }
+                    if modal.button(ui, "Copy Error").clicked() {
+                        ui.output_mut(|out| {
+                            out.copied_text = buffer;
+                        });
+                    }
+                });
+            });
+            if self.error.is_some() {
+                modal.open();
+            }
+
+            if self.rx.is_some() {
+                ui.centered_and_justified(|ui| {
+                    ui.add(egui::Spinner::new().size(96.0));
+                });
+                return;
+            }
+
+            ui.heading("Randomizer");
+            ui.separator();
+            let options = &mut project.randomizer;
+            Grid::new("randomizer_screen_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Seed");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut options.seed));
+                        if ui.button("🎲").on_hover_text("Pick a new seed").clicked() {
+                            options.seed = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map(|time| time.as_nanos() as u64)
+                                .unwrap_or_default();
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Classes");
+                    ui.checkbox(&mut options.classes, "")
+                        .on_hover_text("Give playable characters a new class of the same tier");
+                    ui.end_row();
+
+                    ui.label("Growths");
+                    ui.checkbox(&mut options.growths, "")
+                        .on_hover_text("Shuffle and rescale playable characters' growth rates");
+                    ui.end_row();
+
+                    ui.label("Growth Variance");
+                    ui.add_enabled(
+                        options.growths,
+                        Slider::new(&mut options.growth_variance, 0..=100).suffix("%"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Enemies");
+                    ui.checkbox(&mut options.dispos, "")
+                        .on_hover_text("Randomize enemy classes and shuffle their items");
+                    ui.end_row();
+
+                    ui.label("Shops");
+                    ui.checkbox(&mut options.shops, "").on_hover_text(
+                        "Restock shops with items they sell by that point in the story",
+                    );
+                    ui.end_row();
+
+                    ui.label("Emblem Skills");
+                    ui.checkbox(&mut options.god_skills, "")
+                        .on_hover_text("Shuffle synchro and inheritable skills between emblems");
+                    ui.end_row();
+                });
+            ui.separator();
+            if ui
+                .add(Button::new("Randomize Vanilla Data"))
+                .on_hover_text(
+                    "Books touched by the randomizer are rebuilt from vanilla data and saved. \
+                    The project is reloaded afterwards, so save your work first.",
+                )
+                .clicked()
+            {
+                let options = options.clone();
+                self.run(project.clone().into(), options);
+            }
+        });
+    }
+}
@@ -0,0 +1,221 @@
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use astra_core::error::Result;
+use astra_core::{apply_recipes, AstraProject, Recipe};
+use egui::{Button, CentralPanel, Grid, ScrollArea, TextEdit};
+use egui_modal::Modal;
+use egui_notify::Toasts;
+use rfd::FileDialog;
+
+use crate::{AppConfig, AppState, LoadProjectState};
+
+struct LoadedRecipe {
+    path: String,
+    recipe: std::result::Result<Recipe, String>,
+}
+
+/// Apply declarative recipe files on top of vanilla data.
+#[derive(Default)]
+pub struct RecipeScreen {
+    recipes: Vec<LoadedRecipe>,
+    rx: Option<Receiver<Result<usize>>>,
+    error: Option<String>,
+}
+
+impl RecipeScreen {
+    /// Re-parse recipes when the project's recipe list changes.
+    fn sync(&mut self, paths: &[String]) {
+        let unchanged = self.recipes.len() == paths.len()
+            && self
+                .recipes
+                .iter()
+                .zip(paths)
+                .all(|(recipe, path)| &recipe.path == path);
+        if !unchanged {
+            self.reload(paths);
+        }
+    }
+
+    fn reload(&mut self, paths: &[String]) {
+        self.recipes = paths
+            .iter()
+            .map(|path| LoadedRecipe {
+                path: path.clone(),
+                recipe: Recipe::load(path).map_err(|err| format!("{:?}", err)),
+            })
+            .collect();
+    }
+
+    fn apply(&mut self, project: AstraProject) {
+        let recipes: Vec<Recipe> = self
+            .recipes
+            .iter()
+            .filter_map(|recipe| recipe.recipe.as_ref().ok().cloned())
+            .collect();
+        let (sx, rx) = std::sync::mpsc::channel();
+        self.rx = Some(rx);
+        std::thread::spawn(move || {
+            sx.send(apply_recipes(&project, &recipes)).unwrap();
+        });
+    }
+
+    pub fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut AppConfig,
+        next_state: &mut Option<AppState>,
+        toasts: &mut Toasts,
+    ) {
+        if let Some(rx) = &self.rx {
+            if let Ok(result) = rx.try_recv() {
+                self.rx = None;
+                match result {
+                    Ok(count) => {
+                        toasts
+                            .success(format!("Applied {} operation(s)", count))
+                            .set_duration(Some(Duration::from_secs(2)));
+                        // Reload so the editors see the new data.
+                        *next_state = Some(AppState::LoadProject(LoadProjectState::default()));
+                    }
+                    Err(err) => self.error = Some(format!("{:?}", err)),
+                }
+            }
+        }
+
+        let project = match config
+            .active_project
+            .and_then(|index| config.projects.get_mut(index))
+        {
+            Some(project) => project,
+            None => return,
+        };
+        self.sync(&project.recipes);
+
+        CentralPanel::default().show(ctx, |ui| {
+            let modal = Modal::new(ctx, "recipe_screen_error_modal");
+            modal.show(|ui| {
+                modal.title(ui, "Failed to Apply Recipes");
+                let mut buffer = self.error.clone().unwrap_or_default();
+                ui.add_enabled_ui(false, |ui| {
+                    ui.add(
+                        TextEdit::multiline(&mut buffer)
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(8),
+                    );
+                });
+                modal.buttons(ui, |ui| {
+                    if modal.button(ui, "Close").clicked() {
+                        self.error = None;
+                    }
+                    if modal.button(ui, "Copy Error").clicked() {
+                        ui.output_mut(|out| {
+                            out.copied_text = buffer;
+                        });
+                    }
+                });
+            });
+            if self.error.is_some() {
+                modal.open();
+            }
+
+            if self.rx.is_some() {
+                ui.centered_and_justified(|ui| {
+                    ui.add(egui::Spinner::new().size(96.0));
+                });
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.heading("Recipes");
+                ui.separator();
+                if ui.button("➕ Add").clicked() {
+                    if let Some(paths) = FileDialog::new()
+                        .add_filter("Recipe", &["yaml", "yml", "toml"])
+                        .pick_files()
+                    {
+                        project.recipes.extend(
+                            paths
+                                .into_iter()
+                                .map(|path| path.to_string_lossy().to_string()),
+                        );
+                    }
+                }
+                if ui.button("⟳ Reload").clicked() {
+                    self.reload(&project.recipes);
+                }
+                let valid = !self.recipes.is_empty()
+                    && self.recipes.iter().all(|recipe| recipe.recipe.is_ok());
+                if ui
+                    .add_enabled(valid, Button::new("Apply to Vanilla Data"))
+                    .on_hover_text(
+                        "Books and messages touched by a recipe are rebuilt from vanilla data \
+                        and saved. The project is reloaded afterwards, so save your work first.",
+                    )
+                    .clicked()
+                {
+                    self.apply(project.clone().into());
+                }
+            });
+            ui.separator();
+
+            let mut removed = None;
+            let mut moved = None;
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    Grid::new("recipe_screen_grid")
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("Operations");
+                            ui.strong("Path");
+                            ui.label("");
+                            ui.end_row();
+                            for (index, loaded) in self.recipes.iter().enumerate() {
+                                match &loaded.recipe {
+                                    Ok(recipe) => {
+                                        let response = ui.label(&recipe.name);
+                                        if !recipe.description.is_empty() {
+                                            response.on_hover_text(&recipe.description);
+                                        }
+                                        ui.label(recipe.operations.len().to_string());
+                                    }
+                                    Err(err) => {
+                                        ui.colored_label(ui.visuals().error_fg_color, "Error")
+                                            .on_hover_text(err);
+                                        ui.label("");
+                                    }
+                                }
+                                ui.label(&loaded.path);
+                                ui.horizontal(|ui| {
+                                    if ui.add_enabled(index > 0, Button::new("⏶")).clicked() {
+                                        moved = Some((index, index - 1));
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            index + 1 < self.recipes.len(),
+                                            Button::new("⏷"),
+                                        )
+                                        .clicked()
+                                    {
+                                        moved = Some((index, index + 1));
+                                    }
+                                    if ui.button("✖").clicked() {
+                                        removed = Some(index);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some((from, to)) = moved {
+                project.recipes.swap(from, to);
+            }
+            if let Some(index) = removed {
+                project.recipes.remove(index);
+            }
+        });
+    }
+}
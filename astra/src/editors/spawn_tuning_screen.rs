@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use astra_core::{Astra, SpawnChange, SpawnFilter, SpawnRule};
+use egui::{Button, CentralPanel, DragValue, Grid, ScrollArea, SidePanel, TextEdit};
+use egui_notify::Toasts;
+use parking_lot::RwLock;
+
+use crate::widgets::{bitgrid_u16, force_drop_down};
+use crate::SPAWN_FLAG_LABELS;
+
+const PLACEHOLDER: &str = "# One rule per line, ex.\n\
+    # LevelH = LevelN + 2\n\
+    # HpStockCount = HpStockCount + 1\n\
+    # IID_鉄の剣 -> IID_鋼の剣";
+
+/// Apply formula-based changes to spawns across every chapter at once.
+pub struct SpawnTuningScreen {
+    astra: Arc<RwLock<Astra>>,
+    filter: SpawnFilter,
+    script: String,
+    preview: Vec<SpawnChange>,
+    error: Option<String>,
+}
+
+impl SpawnTuningScreen {
+    pub fn new(astra: Arc<RwLock<Astra>>) -> Self {
+        Self {
+            astra,
+            filter: SpawnFilter::default(),
+            script: String::new(),
+            preview: vec![],
+            error: None,
+        }
+    }
+
+    fn run(&mut self, apply: bool) -> Option<usize> {
+        let result = SpawnRule::parse_script(&self.script)
+            .and_then(|rules| self.astra.write().tune_spawns(&self.filter, &rules, apply));
+        match result {
+            Ok(changes) => {
+                let count = changes.len();
+                self.preview = if apply { vec![] } else { changes };
+                self.error = None;
+                Some(count)
+            }
+            Err(err) => {
+                self.preview.clear();
+                self.error = Some(format!("{:#}", err));
+                None
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, toasts: &mut Toasts) {
+        SidePanel::left("spawn_tuning_filter_panel")
+            .default_width(300.)
+            .show(ctx, |ui| {
+                ui.heading("Filter");
+                Grid::new("spawn_tuning_filter_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Force");
+                        ui.horizontal(|ui| {
+                            let mut any = self.filter.force.is_none();
+                            if ui.checkbox(&mut any, "Any").changed() {
+                                self.filter.force = if any { None } else { Some(1) };
+                            }
+                            if let Some(force) = &mut self.filter.force {
+                                ui.add(force_drop_down(force));
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Progress");
+                        ui.horizontal(|ui| {
+                            ui.add(DragValue::new(&mut self.filter.min_progress));
+                            ui.label("to");
+                            ui.add(DragValue::new(&mut self.filter.max_progress));
+                        });
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.label("Difficulty (any)");
+                ui.add(bitgrid_u16(
+                    &SPAWN_FLAG_LABELS[..3],
+                    3,
+                    &mut self.filter.difficulties,
+                ));
+                ui.separator();
+                ui.label("Flags (all)");
+                ui.add(bitgrid_u16(
+                    SPAWN_FLAG_LABELS,
+                    2,
+                    &mut self.filter.required_flags,
+                ));
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Batch Spawn Tuning");
+            ui.add(
+                TextEdit::multiline(&mut self.script)
+                    .code_editor()
+                    .hint_text(PLACEHOLDER)
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(6),
+            );
+            ui.horizontal(|ui| {
+                let has_rules = !self.script.trim().is_empty();
+                if ui.add_enabled(has_rules, Button::new("Preview")).clicked() {
+                    self.run(false);
+                }
+                if ui
+                    .add_enabled(has_rules, Button::new("Apply"))
+                    .on_hover_text("Changes are kept in memory until the project is saved.")
+                    .clicked()
+                {
+                    if let Some(count) = self.run(true) {
+                        toasts
+                            .success(format!("Changed {} field(s)", count))
+                            .set_duration(Some(Duration::from_secs(2)));
+                    }
+                }
+                if !self.preview.is_empty() {
+                    ui.label(format!("{} change(s)", self.preview.len()));
+                }
+            });
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    Grid::new("spawn_tuning_preview_grid")
+                        .striped(true)
+                        .num_columns(6)
+                        .show(ui, |ui| {
+                            ui.strong("Chapter");
+                            ui.strong("Spawn");
+                            ui.strong("Pid");
+                            ui.strong("Field");
+                            ui.strong("Old");
+                            ui.strong("New");
+                            ui.end_row();
+                            for change in &self.preview {
+                                ui.label(&change.chapter);
+                                ui.label(format!(
+                                    "{}/{}[{}]",
+                                    change.dispos, change.group, change.index
+                                ));
+                                ui.label(&change.pid);
+                                ui.label(&change.field);
+                                ui.label(&change.old);
+                                ui.label(&change.new);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+}
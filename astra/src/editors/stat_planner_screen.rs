@@ -0,0 +1,211 @@
+use egui::{Button, CentralPanel, DragValue, Grid, ScrollArea, SidePanel};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+use crate::{
+    model_drop_down, project_stats, ClassPlan, EditorState, ProjectedLevel, ViewItem,
+    STANDARD_STAT_LABELS,
+};
+
+/// Project a character's average stats through a chain of promotions.
+pub struct StatPlannerScreen {
+    pid: String,
+    plan: Vec<ClassPlan>,
+    shown_stats: [bool; 11],
+}
+
+impl Default for StatPlannerScreen {
+    fn default() -> Self {
+        Self {
+            pid: String::new(),
+            plan: vec![],
+            // Move and sight barely change, so leave them off the chart to start.
+            shown_stats: std::array::from_fn(|stat| stat < 9),
+        }
+    }
+}
+
+impl StatPlannerScreen {
+    /// Start the plan over from the character's own class.
+    fn reset_plan(&mut self, state: &EditorState) {
+        self.plan = state.person.read(|data| {
+            data.get(&self.pid)
+                .filter(|person| !person.jid.is_empty())
+                .map(|person| {
+                    vec![ClassPlan {
+                        jid: person.jid.clone(),
+                        target_level: state.job.read(|data| {
+                            data.get(&person.jid)
+                                .map(|job| job.max_level)
+                                .filter(|level| *level > 0)
+                                .unwrap_or(20)
+                        }),
+                    }]
+                })
+                .unwrap_or_default()
+        });
+    }
+
+    fn class_name(state: &EditorState, jid: &str) -> String {
+        state.job.read(|data| {
+            data.get(jid)
+                .map(|job| job.text(state).to_string())
+                .unwrap_or_else(|| jid.to_string())
+        })
+    }
+
+    /// Classes the last class in the plan can promote into.
+    fn promotions(&self, state: &EditorState) -> Vec<String> {
+        let Some(last) = self.plan.last() else {
+            return vec![];
+        };
+        state.job.read(|data| {
+            data.get(&last.jid)
+                .map(|job| {
+                    [&job.high_job_1, &job.high_job_2]
+                        .into_iter()
+                        .filter(|jid| !jid.is_empty() && data.contains_key(jid.as_str()))
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn plan_panel(&mut self, ui: &mut egui::Ui, state: &EditorState) {
+        ui.heading("Character");
+        let changed = state
+            .person
+            .read(|data| ui.add(model_drop_down(data, state, &mut self.pid)))
+            .changed();
+        if changed {
+            self.reset_plan(state);
+        }
+        ui.separator();
+
+        ui.heading("Classes");
+        Grid::new("stat_planner_plan_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for class in &mut self.plan {
+                    ui.label(Self::class_name(state, &class.jid));
+                    ui.horizontal(|ui| {
+                        ui.label("to Lv");
+                        ui.add(DragValue::new(&mut class.target_level).clamp_range(1..=99));
+                    });
+                    ui.end_row();
+                }
+            });
+        ui.horizontal(|ui| {
+            let promotions = self.promotions(state);
+            ui.add_enabled_ui(!promotions.is_empty(), |ui| {
+                ui.menu_button("Promote", |ui| {
+                    for jid in promotions {
+                        if ui.button(Self::class_name(state, &jid)).clicked() {
+                            let target_level = state.job.read(|data| {
+                                data.get(&jid)
+                                    .map(|job| job.max_level)
+                                    .filter(|level| *level > 0)
+                                    .unwrap_or(20)
+                            });
+                            self.plan.push(ClassPlan { jid, target_level });
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+            if ui
+                .add_enabled(self.plan.len() > 1, Button::new("Remove Last"))
+                .clicked()
+            {
+                self.plan.pop();
+            }
+        });
+    }
+
+    fn stats_chart(&self, ui: &mut egui::Ui, projection: &[ProjectedLevel]) {
+        Plot::new("stat_planner_chart")
+            .legend(Legend::default())
+            .height(ui.available_height() / 2.)
+            .x_axis_label("Levels gained")
+            .show(ui, |plot_ui| {
+                for (stat, label) in STANDARD_STAT_LABELS.iter().enumerate() {
+                    if !self.shown_stats[stat] {
+                        continue;
+                    }
+                    let points: Vec<[f64; 2]> = projection
+                        .iter()
+                        .enumerate()
+                        .map(|(step, level)| [step as f64, level.stats[stat] as f64])
+                        .collect();
+                    plot_ui.line(Line::new(PlotPoints::from(points)).name(label));
+                }
+            });
+    }
+
+    fn stats_table(ui: &mut egui::Ui, state: &EditorState, projection: &[ProjectedLevel]) {
+        ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                Grid::new("stat_planner_table")
+                    .striped(true)
+                    .num_columns(STANDARD_STAT_LABELS.len() + 2)
+                    .show(ui, |ui| {
+                        ui.strong("Class");
+                        ui.strong("Lv");
+                        for label in STANDARD_STAT_LABELS {
+                            ui.strong(label);
+                        }
+                        ui.end_row();
+                        let mut last_jid = "";
+                        for level in projection {
+                            if level.jid != last_jid {
+                                ui.label(Self::class_name(state, &level.jid));
+                                last_jid = &level.jid;
+                            } else {
+                                ui.label("");
+                            }
+                            ui.label(level.level.to_string());
+                            for (stat, value) in level.stats.iter().enumerate() {
+                                let text = format!("{:.1}", value);
+                                // Bold stats that have hit their cap.
+                                if level.caps[stat] > 0 && *value >= level.caps[stat] as f32 {
+                                    ui.strong(text);
+                                } else {
+                                    ui.label(text);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context, state: &EditorState) {
+        SidePanel::left("stat_planner_plan_panel")
+            .default_width(300.)
+            .show(ctx, |ui| self.plan_panel(ui, state));
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Stat Planner");
+            if self.plan.is_empty() {
+                ui.label("Pick a character to start planning.");
+                return;
+            }
+            let Some(projection) = project_stats(state, &self.pid, &self.plan) else {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    "The character or one of their classes doesn't exist.",
+                );
+                return;
+            };
+            ui.horizontal_wrapped(|ui| {
+                for (shown, label) in self.shown_stats.iter_mut().zip(STANDARD_STAT_LABELS) {
+                    ui.checkbox(shown, label);
+                }
+            });
+            self.stats_chart(ui, &projection);
+            ui.separator();
+            Self::stats_table(ui, state, &projection);
+        });
+    }
+}
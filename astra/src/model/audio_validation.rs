@@ -0,0 +1,192 @@
+use std::collections::BTreeSet;
+
+use crate::{EditorState, Screens};
+
+/// What an audio field is expected to name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioReferenceKind {
+    /// An entry in the music table.
+    Music,
+    /// An event from the sound event or music tables.
+    SoundEvent,
+    /// A movie the sound event row belongs to.
+    Movie,
+}
+
+impl AudioReferenceKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioReferenceKind::Music => "Music",
+            AudioReferenceKind::SoundEvent => "Sound Event",
+            AudioReferenceKind::Movie => "Movie",
+        }
+    }
+}
+
+/// An audio field naming something that isn't in any table.
+#[derive(Debug, Clone)]
+pub struct AudioIssue {
+    pub kind: AudioReferenceKind,
+    /// Where to open the row, if it has an editor that supports selection.
+    pub screen: Option<Screens>,
+    pub index: usize,
+    /// The row's key, ex. a CID.
+    pub row: String,
+    pub field: &'static str,
+    pub value: String,
+}
+
+/// Every event name the sound event and music tables know about.
+pub fn known_sound_events(state: &EditorState) -> BTreeSet<String> {
+    let mut events: BTreeSet<String> = state.sound_events.read(|data| {
+        data.values()
+            .flat_map(|row| {
+                [
+                    &row.event_name_1,
+                    &row.event_name_2,
+                    &row.event_name_3,
+                    &row.event_name_4,
+                ]
+            })
+            .filter(|event| !event.is_empty())
+            .cloned()
+            .collect()
+    });
+    state.music.read(|data| {
+        for music in data.values() {
+            events.insert(music.event_name.clone());
+            if !music.change_event_name.is_empty() {
+                events.insert(music.change_event_name.clone());
+            }
+        }
+    });
+    events
+}
+
+struct Checker {
+    music: BTreeSet<String>,
+    sound_events: BTreeSet<String>,
+    movies: BTreeSet<String>,
+    issues: Vec<AudioIssue>,
+}
+
+impl Checker {
+    fn check(
+        &mut self,
+        kind: AudioReferenceKind,
+        screen: Option<Screens>,
+        index: usize,
+        row: &str,
+        field: &'static str,
+        value: &str,
+    ) {
+        let known = match kind {
+            AudioReferenceKind::Music => &self.music,
+            AudioReferenceKind::SoundEvent => &self.sound_events,
+            AudioReferenceKind::Movie => &self.movies,
+        };
+        if !value.is_empty() && !known.contains(value) {
+            self.issues.push(AudioIssue {
+                kind,
+                screen,
+                index,
+                row: row.to_string(),
+                field,
+                value: value.to_string(),
+            });
+        }
+    }
+}
+
+/// Cross-check every audio field against the music, sound event and movie tables.
+pub fn validate_audio(state: &EditorState) -> Vec<AudioIssue> {
+    use AudioReferenceKind::{Movie, Music, SoundEvent};
+
+    let mut checker = Checker {
+        music: state.music.read(|data| data.keys().cloned().collect()),
+        sound_events: known_sound_events(state),
+        movies: state.movies.read(|data| data.keys().cloned().collect()),
+        issues: vec![],
+    };
+
+    state.chapter.read(|data| {
+        for (index, (cid, chapter)) in data.iter().enumerate() {
+            for (field, value) in [
+                ("Player Phase BGM", &chapter.player_phase_bgm),
+                ("Enemy Phase BGM", &chapter.enemy_phase_bgm),
+                ("Ally Phase BGM", &chapter.ally_phase_bgm),
+                ("Player Encount BGM", &chapter.player_encount_bgm),
+                ("Enemy Encount BGM", &chapter.enemy_encount_bgm),
+                ("Sortie BGM", &chapter.sortie_bgm),
+                ("Kizuna BGM", &chapter.kizuna_bgm),
+            ] {
+                checker.check(Music, Some(Screens::Chapter), index, cid, field, value);
+            }
+        }
+    });
+    state.person.read(|data| {
+        for (index, (pid, person)) in data.iter().enumerate() {
+            checker.check(
+                Music,
+                Some(Screens::Person),
+                index,
+                pid,
+                "Combat Music",
+                &person.combat_bgm,
+            );
+        }
+    });
+    state.music.read(|data| {
+        for (index, (event_name, music)) in data.iter().enumerate() {
+            let (field, value) = ("Change Event Name", &music.change_event_name);
+            checker.check(Music, Some(Screens::Music), index, event_name, field, value);
+        }
+    });
+    state.jukebox_data.read(|data| {
+        for (index, event_name) in data.keys().enumerate() {
+            checker.check(Music, None, index, event_name, "Event Name", event_name);
+        }
+    });
+    state.map_editor_objects.read(|data| {
+        for (index, (name, object)) in data.iter().enumerate() {
+            checker.check(
+                SoundEvent,
+                Some(Screens::MapEditor),
+                index,
+                name,
+                "Sound Event",
+                &object.sound_event,
+            );
+        }
+    });
+    state.movies.read(|data| {
+        for (index, (name, movie)) in data.iter().enumerate() {
+            for (field, value) in [
+                (
+                    "Before Sound Event Name 1",
+                    &movie.before_sound_event_name_1,
+                ),
+                (
+                    "Before Sound Event Name 2",
+                    &movie.before_sound_event_name_2,
+                ),
+                (
+                    "Before Sound Event Name 3",
+                    &movie.before_sound_event_name_3,
+                ),
+                ("After Sound Event Name 1", &movie.after_sound_event_name_1),
+                ("After Sound Event Name 2", &movie.after_sound_event_name_2),
+                ("After Sound Event Name 3", &movie.after_sound_event_name_3),
+            ] {
+                checker.check(SoundEvent, Some(Screens::Movie), index, name, field, value);
+            }
+        }
+    });
+    state.sound_events.read(|data| {
+        for (index, movie_file_name) in data.keys().enumerate() {
+            let field = "Movie File Name";
+            checker.check(Movie, None, index, movie_file_name, field, movie_file_name);
+        }
+    });
+    checker.issues
+}
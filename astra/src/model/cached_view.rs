@@ -141,3 +141,31 @@ where
         self.cache.model()
     }
 }
+
+/// A value derived from editor data that is only rebuilt when its key changes.
+/// Keys are usually the revision numbers of the data along with any view options.
+pub struct CachedValue<K, V>(Option<(K, V)>);
+
+impl<K, V> Default for CachedValue<K, V> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<K, V> CachedValue<K, V>
+where
+    K: PartialEq,
+{
+    /// Retrieve the value, rebuilding it if it was built for a different key.
+    pub fn get(&mut self, key: K, build: impl FnOnce() -> V) -> &V {
+        if !matches!(&self.0, Some((cached, _)) if *cached == key) {
+            self.0 = Some((key, build()));
+        }
+        &self.0.as_ref().unwrap().1
+    }
+
+    /// Drop the value so the next [CachedValue::get] rebuilds it.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+}
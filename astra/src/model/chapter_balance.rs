@@ -0,0 +1,114 @@
+use astra_core::{spawn_items, weapon_rank, Difficulty, WEAPON_KINDS};
+use astra_types::{Item, Job, Spawn};
+use indexmap::IndexMap;
+
+use crate::{average_level, effective_level, unit_job, EditorState, ENEMY_FORCE};
+
+/// Weapon types in item kind order, starting from kind 1.
+pub const WEAPON_TYPE_LABELS: [&str; 9] = [
+    "Sword", "Lance", "Axe", "Bow", "Dagger", "Magic", "Rod", "Fist", "Special",
+];
+
+/// The first [Job::attrs] bits, which skill efficacy targets the same way.
+pub const EFFECTIVENESS_LABELS: [&str; 5] = ["Infantry", "Mounted", "Armored", "Flier", "Dragon"];
+
+const GOLD_KIND: i8 = 18;
+
+/// Aggregate figures for one chapter dispos.
+#[derive(Debug, Clone, Default)]
+pub struct ChapterBalance {
+    /// Indexed Normal, Hard, Lunatic.
+    pub enemy_count: [usize; 3],
+    pub average_level: [Option<f32>; 3],
+    pub max_level: [Option<u8>; 3],
+    /// Enemies per [WEAPON_TYPE_LABELS] entry, with unarmed enemies at the end.
+    pub weapon_types: [usize; 10],
+    /// Enemies carrying a weapon effective against each [EFFECTIVENESS_LABELS] entry.
+    pub effective_threats: [usize; 5],
+    /// Gold items store their amount in the price field.
+    pub gold: i64,
+    /// IIDs of every dropped item other than gold.
+    pub drops: Vec<String>,
+}
+
+/// The equipped weapon's type, or the class's first weapon type for unarmed spawns.
+fn weapon_type(spawn: &Spawn, items: &IndexMap<String, Item>, job: Option<&Job>) -> Option<usize> {
+    let equipped = spawn_items(spawn)
+        .into_iter()
+        .filter_map(|(iid, _)| items.get(iid))
+        .find(|item| WEAPON_KINDS.contains(&item.kind))
+        .map(|item| item.kind);
+    equipped
+        .or_else(|| {
+            let job = job?;
+            WEAPON_KINDS.find(|kind| weapon_rank(job, *kind) > 0)
+        })
+        .map(|kind| kind as usize - 1)
+}
+
+/// Summarize the enemies in a dispos. Counts and levels cover every difficulty,
+/// everything else only the spawns on `difficulty`.
+pub fn chapter_balance(
+    state: &EditorState,
+    dispos: &IndexMap<String, Vec<Spawn>>,
+    difficulty: Difficulty,
+) -> ChapterBalance {
+    let enemies: Vec<&Spawn> = dispos
+        .values()
+        .flatten()
+        .filter(|spawn| spawn.force == ENEMY_FORCE)
+        .collect();
+    let mut balance = ChapterBalance::default();
+    state.person.read(|persons| {
+        state.job.read(|jobs| {
+            state.item.read(|items| {
+                state.skill.read(|skills| {
+                    for (slot, level_difficulty) in Difficulty::FLAGGED.into_iter().enumerate() {
+                        let levels: Vec<u8> = enemies
+                            .iter()
+                            .filter(|spawn| level_difficulty.includes(spawn))
+                            .map(|spawn| {
+                                let level = [spawn.level_n, spawn.level_h, spawn.level_l][slot];
+                                effective_level(level, &spawn.pid, &spawn.jid, persons, jobs)
+                            })
+                            .collect();
+                        balance.enemy_count[slot] = levels.len();
+                        balance.average_level[slot] = average_level(&levels);
+                        balance.max_level[slot] = levels.iter().max().copied();
+                    }
+
+                    for spawn in enemies.iter().filter(|spawn| difficulty.includes(spawn)) {
+                        let job = unit_job(&spawn.pid, &spawn.jid, persons, jobs);
+                        let weapon = weapon_type(spawn, items, job);
+                        balance.weapon_types[weapon.unwrap_or(WEAPON_TYPE_LABELS.len())] += 1;
+
+                        let efficacy = spawn_items(spawn)
+                            .into_iter()
+                            .filter_map(|(iid, _)| items.get(iid))
+                            .flat_map(|item| &item.equip_sids)
+                            .filter_map(|sid| skills.get(sid))
+                            .fold(0, |efficacy, skill| efficacy | skill.efficacy);
+                        for (bit, threats) in balance.effective_threats.iter_mut().enumerate() {
+                            if efficacy & (1 << bit) != 0 {
+                                *threats += 1;
+                            }
+                        }
+
+                        for (iid, drop) in spawn_items(spawn) {
+                            if iid.is_empty() || drop == 0 {
+                                continue;
+                            }
+                            match items.get(iid) {
+                                Some(item) if item.kind == GOLD_KIND => {
+                                    balance.gold += item.price as i64
+                                }
+                                _ => balance.drops.push(iid.clone()),
+                            }
+                        }
+                    }
+                })
+            })
+        })
+    });
+    balance
+}
@@ -0,0 +1,234 @@
+use std::collections::{HashSet, VecDeque};
+
+use astra_types::{Challenge, Chapter};
+use indexmap::IndexMap;
+
+/// How one chapter leads into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChapterLinkKind {
+    /// `Chapter.next_chapter`.
+    Next,
+    /// The target's world map spot opens once the source is cleared.
+    MapSpot,
+    /// The source unlocks a challenge containing the target as a stage.
+    Challenge,
+}
+
+impl ChapterLinkKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ChapterLinkKind::Next => "Next Chapter",
+            ChapterLinkKind::MapSpot => "Map Spot",
+            ChapterLinkKind::Challenge => "Challenge",
+        }
+    }
+}
+
+/// An edge between two rows of the chapter sheet.
+#[derive(Debug, Clone)]
+pub struct ChapterLink {
+    pub from: usize,
+    pub to: usize,
+    pub kind: ChapterLinkKind,
+}
+
+/// A routing problem found by [build_chapter_graph].
+#[derive(Debug, Clone)]
+pub struct ChapterGraphIssue {
+    /// Index of the chapter at fault, or `None` for problems that aren't tied to a chapter.
+    pub chapter: Option<usize>,
+    pub message: String,
+}
+
+/// The campaign route. Nodes are indices into the chapter sheet.
+#[derive(Debug, Clone, Default)]
+pub struct ChapterGraph {
+    pub links: Vec<ChapterLink>,
+    /// Steps from the start chapter, or `None` if the chapter can't be reached.
+    pub depths: Vec<Option<usize>>,
+    pub issues: Vec<ChapterGraphIssue>,
+}
+
+/// Find a chapter by CID, accepting references that leave off the `CID_` prefix.
+fn resolve_cid(chapters: &IndexMap<String, Chapter>, cid: &str) -> Option<usize> {
+    chapters
+        .get_index_of(cid)
+        .or_else(|| chapters.get_index_of(&format!("CID_{}", cid)))
+}
+
+fn find_cycles(node_count: usize, links: &[ChapterLink]) -> Vec<Vec<usize>> {
+    fn visit(
+        node: usize,
+        adjacency: &[Vec<usize>],
+        state: &mut [u8],
+        path: &mut Vec<usize>,
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        state[node] = 1;
+        path.push(node);
+        for &next in &adjacency[node] {
+            match state[next] {
+                0 => visit(next, adjacency, state, path, cycles),
+                1 => {
+                    let start = path.iter().position(|n| *n == next).unwrap_or_default();
+                    cycles.push(path[start..].to_vec());
+                }
+                _ => {}
+            }
+        }
+        path.pop();
+        state[node] = 2;
+    }
+
+    let mut adjacency = vec![vec![]; node_count];
+    for link in links {
+        adjacency[link.from].push(link.to);
+    }
+    // 0 = unvisited, 1 = on the current path, 2 = done.
+    let mut state = vec![0; node_count];
+    let mut cycles = vec![];
+    for node in 0..node_count {
+        if state[node] == 0 {
+            visit(node, &adjacency, &mut state, &mut vec![], &mut cycles);
+        }
+    }
+    cycles
+}
+
+/// Build the route through the campaign from `start` and check it for breaks.
+///
+/// Chapters that are only opened from scripts have no link in the data, so they are reported
+/// as unreachable along with genuinely orphaned chapters.
+pub fn build_chapter_graph(
+    chapters: &IndexMap<String, Chapter>,
+    challenges: &[Challenge],
+    start: &str,
+) -> ChapterGraph {
+    let start_index = resolve_cid(chapters, start);
+    let mut links = vec![];
+    let mut issues = vec![];
+    for (index, chapter) in chapters.values().enumerate() {
+        if !chapter.next_chapter.is_empty() {
+            match resolve_cid(chapters, &chapter.next_chapter) {
+                Some(to) => links.push(ChapterLink {
+                    from: index,
+                    to,
+                    kind: ChapterLinkKind::Next,
+                }),
+                None => issues.push(ChapterGraphIssue {
+                    chapter: Some(index),
+                    message: format!(
+                        "{}: next chapter '{}' doesn't exist",
+                        chapter.cid, chapter.next_chapter
+                    ),
+                }),
+            }
+        }
+        if !chapter.gmap_spot_open_condition.is_empty() {
+            match resolve_cid(chapters, &chapter.gmap_spot_open_condition) {
+                Some(from) => links.push(ChapterLink {
+                    from,
+                    to: index,
+                    kind: ChapterLinkKind::MapSpot,
+                }),
+                None => issues.push(ChapterGraphIssue {
+                    chapter: Some(index),
+                    message: format!(
+                        "{}: map spot '{}' opens on unknown chapter '{}'",
+                        chapter.cid, chapter.gmap_spot, chapter.gmap_spot_open_condition
+                    ),
+                }),
+            }
+        }
+    }
+    for challenge in challenges {
+        // Challenges without an unlock chapter are open from the start.
+        let unlock = if challenge.unlock_cid.is_empty() {
+            start_index
+        } else {
+            resolve_cid(chapters, &challenge.unlock_cid)
+        };
+        let Some(from) = unlock else {
+            if !challenge.unlock_cid.is_empty() {
+                issues.push(ChapterGraphIssue {
+                    chapter: None,
+                    message: format!(
+                        "Challenge {}: unlock chapter '{}' doesn't exist",
+                        challenge.cid, challenge.unlock_cid
+                    ),
+                });
+            }
+            continue;
+        };
+        for stage in challenge
+            .stage_1
+            .iter()
+            .chain(&challenge.stage_2)
+            .chain(&challenge.stage_3)
+            .filter(|stage| !stage.is_empty())
+        {
+            match resolve_cid(chapters, stage) {
+                Some(to) => links.push(ChapterLink {
+                    from,
+                    to,
+                    kind: ChapterLinkKind::Challenge,
+                }),
+                None => issues.push(ChapterGraphIssue {
+                    chapter: Some(from),
+                    message: format!(
+                        "Challenge {}: stage '{}' doesn't exist",
+                        challenge.cid, stage
+                    ),
+                }),
+            }
+        }
+    }
+
+    let mut depths = vec![None; chapters.len()];
+    if let Some(start_index) = start_index {
+        let mut queue = VecDeque::from([start_index]);
+        depths[start_index] = Some(0);
+        while let Some(node) = queue.pop_front() {
+            let depth = depths[node].unwrap_or_default();
+            for link in links.iter().filter(|link| link.from == node) {
+                if depths[link.to].is_none() {
+                    depths[link.to] = Some(depth + 1);
+                    queue.push_back(link.to);
+                }
+            }
+        }
+        for (index, chapter) in chapters.values().enumerate() {
+            if depths[index].is_none() {
+                issues.push(ChapterGraphIssue {
+                    chapter: Some(index),
+                    message: format!("{} can't be reached from {}", chapter.cid, start),
+                });
+            }
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for cycle in find_cycles(chapters.len(), &links) {
+        let mut key = cycle.clone();
+        key.sort_unstable();
+        if !reported.insert(key) {
+            continue;
+        }
+        let route = cycle
+            .iter()
+            .chain(cycle.first())
+            .filter_map(|index| chapters.get_index(*index).map(|(cid, _)| cid.as_str()))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        issues.push(ChapterGraphIssue {
+            chapter: cycle.first().copied(),
+            message: format!("Cycle: {}", route),
+        });
+    }
+
+    ChapterGraph {
+        links,
+        depths,
+        issues,
+    }
+}
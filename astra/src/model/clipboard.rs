@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use astra_formats::{FromSheetDataParam, ToSheetDataParam};
+use egui::{Event, Ui};
+use egui_modal::{Icon, Modal};
+use indexmap::IndexMap;
+use quick_xml::events::{BytesStart, Event as XmlEvent};
+use quick_xml::{Reader, Writer};
+
+/// A clipboard action requested by the user while no widget had focus.
+pub enum ClipboardRequest {
+    Copy,
+    Paste(String),
+}
+
+/// Where pasted rows ended up.
+#[derive(Debug, Default)]
+pub struct PasteResult {
+    /// Index of the last pasted row.
+    pub last: Option<usize>,
+    /// Keys that were already taken, along with the key the pasted row got instead.
+    pub renamed: Vec<(String, String)>,
+}
+
+impl PasteResult {
+    /// A note for the user if any rows had to be renamed.
+    pub fn message(&self) -> Option<String> {
+        (!self.renamed.is_empty()).then(|| {
+            let renamed: Vec<String> = self
+                .renamed
+                .iter()
+                .map(|(from, to)| format!("{} → {}", from, to))
+                .collect();
+            format!(
+                "These keys already existed, so the pasted rows were renamed:\n{}",
+                renamed.join("\n")
+            )
+        })
+    }
+}
+
+/// Show the outcome of a copy or paste until the user dismisses it.
+pub fn clipboard_message_modal(ctx: &egui::Context, id: &str, message: &mut Option<String>) {
+    let Some(text) = message.clone() else {
+        return;
+    };
+    let modal = Modal::new(ctx, id);
+    modal.show(|ui| {
+        modal.title(ui, "Clipboard");
+        modal.body_and_icon(ui, &text, Icon::Info);
+        modal.buttons(ui, |ui| {
+            if modal.button(ui, "Close").clicked() {
+                *message = None;
+            }
+        });
+    });
+    modal.open();
+}
+
+/// Consume copy / paste events so they can be applied to the selected rows.
+/// Only call this when no widget is focused, otherwise text fields lose their clipboard support.
+pub fn consume_clipboard_request(ui: &mut Ui) -> Option<ClipboardRequest> {
+    ui.input_mut(|input| {
+        let mut request = None;
+        input.events.retain(|event| match event {
+            Event::Copy if request.is_none() => {
+                request = Some(ClipboardRequest::Copy);
+                false
+            }
+            Event::Paste(text) if request.is_none() => {
+                request = Some(ClipboardRequest::Paste(text.clone()));
+                false
+            }
+            _ => true,
+        });
+        request
+    })
+}
+
+/// Serialize rows as `<Param .../>` fragments matching the book XML format.
+pub fn rows_to_xml<'a, I>(rows: impl IntoIterator<Item = &'a I>) -> Result<String>
+where
+    I: ToSheetDataParam + 'a,
+{
+    let mut lines = vec![];
+    for row in rows {
+        let mut writer = Writer::new(Vec::new());
+        let mut element = BytesStart::new("Param");
+        for (key, value) in row.to_sheet_data_param_values() {
+            element.push_attribute((key.trim_start_matches('@'), value.as_str()));
+        }
+        writer.write_event(XmlEvent::Empty(element))?;
+        lines.push(String::from_utf8(writer.into_inner())?);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Parse `<Param .../>` fragments produced by [rows_to_xml] (or copied out of a book).
+/// Attributes missing from the fragment keep their default value so snippets from older
+/// versions of a sheet still paste. Unknown attributes are rejected since they usually
+/// mean the snippet came from a different sheet.
+pub fn rows_from_xml<I>(text: &str) -> Result<Vec<I>>
+where
+    I: FromSheetDataParam + ToSheetDataParam + Default,
+{
+    let defaults = I::default().to_sheet_data_param_values();
+    let mut reader = Reader::from_str(text);
+    reader.trim_text(true);
+    let mut rows = vec![];
+    loop {
+        match reader.read_event()? {
+            XmlEvent::Empty(element) | XmlEvent::Start(element)
+                if element.name().as_ref() == b"Param" =>
+            {
+                let mut values: IndexMap<String, String> = defaults.clone();
+                for attribute in element.attributes() {
+                    let attribute = attribute?;
+                    let key = format!("@{}", std::str::from_utf8(attribute.key.as_ref())?);
+                    if !values.contains_key(&key) {
+                        bail!("unknown attribute '{}'", key.trim_start_matches('@'));
+                    }
+                    values.insert(key, attribute.unescape_value()?.into_owned());
+                }
+                rows.push(I::from_sheet_data_param(values)?);
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+    }
+    if rows.is_empty() {
+        bail!("clipboard does not contain any rows");
+    }
+    Ok(rows)
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use astra_core::{AstraProject, PathLocalizer, RomSource};
+use astra_core::{AstraProject, PathLocalizer, RandomizerOptions, RomSource};
 use directories::ProjectDirs;
 use egui::Color32;
 use maplit::hashmap;
@@ -146,6 +146,10 @@ pub struct ProjectDef {
     pub output_mode: ProjectOutputMode,
     pub active_country_dir_name: String,
     pub active_language_dir_name: String,
+    #[serde(default)]
+    pub recipes: Vec<String>,
+    #[serde(default)]
+    pub randomizer: RandomizerOptions,
 }
 
 impl ProjectDef {
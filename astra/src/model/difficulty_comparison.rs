@@ -0,0 +1,118 @@
+use astra_core::{spawn_items, Difficulty};
+use astra_types::Spawn;
+use indexmap::IndexMap;
+
+/// How a unit is set up on one difficulty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifficultySpawn {
+    /// Index of the spawn in its group.
+    pub index: usize,
+    pub level: u8,
+    pub jid: String,
+    /// IIDs of the spawn's items, with dropped items marked by a trailing `*`.
+    pub items: Vec<String>,
+}
+
+/// One unit of a dispos across Normal, Hard and Lunatic.
+/// Units are matched by group, character and position, since harder difficulties
+/// often swap in a separate spawn for the same unit instead of sharing one.
+#[derive(Debug, Clone)]
+pub struct SpawnComparison {
+    pub group: String,
+    pub pid: String,
+    pub position: (i8, i8),
+    /// Indexed Normal, Hard, Lunatic.
+    pub spawns: [Option<DifficultySpawn>; 3],
+}
+
+impl SpawnComparison {
+    /// Whether the unit is missing from at least one difficulty.
+    pub fn is_partial(&self) -> bool {
+        self.spawns.iter().any(Option::is_none)
+    }
+
+    pub fn levels_differ(&self) -> bool {
+        self.differs(|spawn| spawn.level)
+    }
+
+    pub fn items_differ(&self) -> bool {
+        self.differs(|spawn| spawn.items.clone())
+    }
+
+    pub fn jobs_differ(&self) -> bool {
+        self.differs(|spawn| spawn.jid.clone())
+    }
+
+    pub fn has_differences(&self) -> bool {
+        self.is_partial() || self.levels_differ() || self.items_differ() || self.jobs_differ()
+    }
+
+    fn differs<T: PartialEq>(&self, value: impl Fn(&DifficultySpawn) -> T) -> bool {
+        let mut values = self.spawns.iter().flatten().map(value);
+        values
+            .next()
+            .is_some_and(|first| values.any(|value| value != first))
+    }
+}
+
+fn item_labels(spawn: &Spawn) -> Vec<String> {
+    spawn_items(spawn)
+        .into_iter()
+        .filter(|(iid, _)| !iid.is_empty())
+        .map(|(iid, drop)| {
+            if drop != 0 {
+                format!("{}*", iid)
+            } else {
+                iid.clone()
+            }
+        })
+        .collect()
+}
+
+/// Line up every unit in the dispos across difficulties using the spawn flag bits.
+/// Units come out in the order they first appear in the dispos.
+pub fn compare_difficulties(dispos: &IndexMap<String, Vec<Spawn>>) -> Vec<SpawnComparison> {
+    let mut comparisons: Vec<SpawnComparison> = vec![];
+    for (group, spawns) in dispos {
+        for (index, spawn) in spawns.iter().enumerate() {
+            if !Difficulty::FLAGGED
+                .iter()
+                .any(|difficulty| difficulty.includes(spawn))
+            {
+                continue;
+            }
+            let position = (spawn.dispos_x, spawn.dispos_y);
+            let existing = comparisons.iter().position(|comparison| {
+                comparison.group == *group
+                    && comparison.pid == spawn.pid
+                    && comparison.position == position
+            });
+            let slot = match existing {
+                Some(slot) => slot,
+                None => {
+                    comparisons.push(SpawnComparison {
+                        group: group.clone(),
+                        pid: spawn.pid.clone(),
+                        position,
+                        spawns: Default::default(),
+                    });
+                    comparisons.len() - 1
+                }
+            };
+            let levels = [spawn.level_n, spawn.level_h, spawn.level_l];
+            for (difficulty_index, difficulty) in Difficulty::FLAGGED.into_iter().enumerate() {
+                let entry = &mut comparisons[slot].spawns[difficulty_index];
+                // Keep the first spawn if two rows claim the same unit on one difficulty.
+                if entry.is_none() && difficulty.includes(spawn) {
+                    *entry = Some(DifficultySpawn {
+                        index,
+                        level: levels[difficulty_index],
+                        jid: spawn.jid.clone(),
+                        items: item_labels(spawn),
+                    });
+                }
+            }
+        }
+    }
+    comparisons
+}
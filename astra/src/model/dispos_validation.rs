@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use astra_core::{
+    chapter_file_name, spawn_items, Difficulty, SPAWN_BOSS_FLAG, SPAWN_DEPLOYMENT_SLOT_FLAG,
+    SPAWN_EDGE_FLAG, SPAWN_MUST_DEPLOY_FLAG,
+};
+use astra_formats::TerrainData;
+use astra_types::{Chapter, Spawn};
+use indexmap::IndexMap;
+
+use crate::{spawn_movement, spawn_tile, CoordinateKind, EditorState, MovementCosts, Tile};
+
+const SORTIE_CHAPTER_FLAG: i32 = 1;
+
+/// A placement problem found by [validate_dispos].
+#[derive(Debug, Clone)]
+pub struct DisposIssue {
+    /// The `(group, index)` of the spawn at fault, or `None` for problems with the whole chapter.
+    pub spawn: Option<(String, usize)>,
+    pub message: String,
+}
+
+impl DisposIssue {
+    fn spawn(group: &str, index: usize, spawn: &Spawn, message: String) -> Self {
+        Self {
+            spawn: Some((group.to_string(), index)),
+            message: format!("{}[{}] {}: {}", group, index, spawn.pid, message),
+        }
+    }
+}
+
+/// The placement issues found in one chapter by [validate_all_dispos].
+#[derive(Debug, Clone)]
+pub struct ChapterIssues {
+    pub cid: String,
+    pub issues: Vec<DisposIssue>,
+}
+
+fn on_map(x: i8, y: i8, width: i32, height: i32) -> bool {
+    (0..width).contains(&(x as i32)) && (0..height).contains(&(y as i32))
+}
+
+/// Check a chapter's spawns for placement mistakes that the game won't report on its own.
+pub fn validate_dispos(
+    terrain: &TerrainData,
+    state: &EditorState,
+    chapter: &Chapter,
+    dispos: &IndexMap<String, Vec<Spawn>>,
+) -> Vec<DisposIssue> {
+    let (width, height) = (terrain.width, terrain.height);
+    let mut costs_by_move_type: HashMap<i8, MovementCosts> = HashMap::new();
+    let mut issues = vec![];
+    for (group, spawns) in dispos {
+        for (index, spawn) in spawns.iter().enumerate() {
+            let dispos_on_map = on_map(spawn.dispos_x, spawn.dispos_y, width, height);
+            let appear_on_map = on_map(spawn.appear_x, spawn.appear_y, width, height);
+            if !dispos_on_map {
+                issues.push(DisposIssue::spawn(
+                    group,
+                    index,
+                    spawn,
+                    format!(
+                        "dispos position ({}, {}) is outside the {}x{} map",
+                        spawn.dispos_x, spawn.dispos_y, width, height
+                    ),
+                ));
+            }
+            // Edge spawns walk in from off the map, so their appear position can be anywhere.
+            if !appear_on_map && spawn.flag & SPAWN_EDGE_FLAG == 0 {
+                issues.push(DisposIssue::spawn(
+                    group,
+                    index,
+                    spawn,
+                    format!(
+                        "appear position ({}, {}) is outside the {}x{} map",
+                        spawn.appear_x, spawn.appear_y, width, height
+                    ),
+                ));
+            }
+            if spawn.flag & SPAWN_BOSS_FLAG != 0
+                && spawn_items(spawn).iter().all(|(iid, _)| iid.is_empty())
+            {
+                issues.push(DisposIssue::spawn(
+                    group,
+                    index,
+                    spawn,
+                    "boss has no items".to_string(),
+                ));
+            }
+
+            // Deployment slots hold whichever unit the player picks, so there's no class to check.
+            if spawn.flag & SPAWN_DEPLOYMENT_SLOT_FLAG != 0 || !dispos_on_map {
+                continue;
+            }
+            let (move_type, movement) = spawn_movement(state, spawn, Difficulty::All);
+            if move_type == 0 {
+                continue;
+            }
+            let costs = costs_by_move_type
+                .entry(move_type)
+                .or_insert_with(|| MovementCosts::new(terrain, state, move_type));
+            let dispos_tile: Tile = (spawn.dispos_x as usize, spawn.dispos_y as usize);
+            let appear_tile: Tile = (spawn.appear_x as usize, spawn.appear_y as usize);
+            if costs.cost(dispos_tile).is_none() {
+                issues.push(DisposIssue::spawn(
+                    group,
+                    index,
+                    spawn,
+                    format!(
+                        "dispos position ({}, {}) is impassable for its class",
+                        spawn.dispos_x, spawn.dispos_y
+                    ),
+                ));
+                continue;
+            }
+            // Only positions farther apart than the unit can move in a turn count as far apart.
+            let distance = spawn.appear_x.abs_diff(spawn.dispos_x) as u16
+                + spawn.appear_y.abs_diff(spawn.dispos_y) as u16;
+            if appear_on_map
+                && distance > movement as u16
+                && !costs.path_exists(appear_tile, dispos_tile)
+            {
+                issues.push(DisposIssue::spawn(
+                    group,
+                    index,
+                    spawn,
+                    format!(
+                        "no path from appear ({}, {}) to dispos ({}, {}), {} tiles apart",
+                        spawn.appear_x, spawn.appear_y, spawn.dispos_x, spawn.dispos_y, distance
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Overlaps are grouped by pair of spawns so each pair is only reported once.
+    let mut overlaps: IndexMap<((&str, usize), (&str, usize)), Vec<&str>> = IndexMap::new();
+    for difficulty in Difficulty::FLAGGED {
+        let label = difficulty.label();
+        let mut occupants: HashMap<Tile, (&str, usize)> = HashMap::new();
+        let spawns = dispos.iter().flat_map(|(group, spawns)| {
+            spawns
+                .iter()
+                .enumerate()
+                .map(move |(index, spawn)| (group.as_str(), index, spawn))
+        });
+        for (group, index, spawn) in spawns {
+            if !difficulty.includes(spawn) {
+                continue;
+            }
+            let tile = spawn_tile(spawn, CoordinateKind::Dispos);
+            match occupants.get(&tile) {
+                Some(first) => overlaps
+                    .entry((*first, (group, index)))
+                    .or_default()
+                    .push(label),
+                None => {
+                    occupants.insert(tile, (group, index));
+                }
+            }
+        }
+    }
+    for (((first_group, first_index), (group, index)), labels) in overlaps {
+        let spawn = &dispos[group][index];
+        let first = &dispos[first_group][first_index];
+        issues.push(DisposIssue::spawn(
+            group,
+            index,
+            spawn,
+            format!(
+                "overlaps {}[{}] {} on {}",
+                first_group,
+                first_index,
+                first.pid,
+                labels.join(", ")
+            ),
+        ));
+    }
+
+    let sortie = chapter.flag & SORTIE_CHAPTER_FLAG != 0;
+    for difficulty in Difficulty::FLAGGED {
+        let label = difficulty.label();
+        let (mut slots, mut must_deploy) = (0, 0);
+        for spawn in dispos.values().flatten() {
+            if spawn.force != 0 || !difficulty.includes(spawn) {
+                continue;
+            }
+            if spawn.flag & SPAWN_DEPLOYMENT_SLOT_FLAG != 0 {
+                slots += 1;
+            }
+            if spawn.flag & SPAWN_MUST_DEPLOY_FLAG != 0 {
+                must_deploy += 1;
+            }
+        }
+        let message = if sortie && slots == 0 {
+            format!(
+                "{}: the chapter has a sortie but no deployment slots",
+                label
+            )
+        } else if !sortie && slots > 0 {
+            format!(
+                "{}: {} deployment slot(s) but the chapter has no sortie",
+                label, slots
+            )
+        } else if slots < must_deploy {
+            format!(
+                "{}: {} unit(s) must deploy but there are only {} deployment slot(s)",
+                label, must_deploy, slots
+            )
+        } else {
+            continue;
+        };
+        issues.push(DisposIssue {
+            spawn: None,
+            message,
+        });
+    }
+    issues
+}
+
+/// Run [validate_dispos] on the main dispos of every chapter that has one, in chapter order.
+/// Chapters whose dispos or terrain can't be loaded get a single issue saying so.
+/// Chapters without any issues are left out.
+pub fn validate_all_dispos(state: &EditorState) -> Vec<ChapterIssues> {
+    let chapters: Vec<Chapter> = state.chapter.read(|data| {
+        data.values()
+            .filter(|chapter| !chapter.dispos.is_empty())
+            .cloned()
+            .collect()
+    });
+    chapters
+        .into_iter()
+        .filter_map(|chapter| {
+            let dispos_name = chapter_file_name(&chapter.cid, &chapter.dispos);
+            let terrain_name = chapter_file_name(&chapter.cid, &chapter.terrain);
+            let dispos = state.astra.write().get_dispos(&dispos_name);
+            let terrain = state.astra.write().get_chapter_terrain(&terrain_name);
+            let issues = match (dispos, terrain) {
+                (Some(dispos), Some(terrain)) => terrain.read(|terrain| {
+                    dispos.read(|book| validate_dispos(terrain, state, &chapter, &book.spawns.data))
+                }),
+                (None, _) => vec![DisposIssue {
+                    spawn: None,
+                    message: format!("couldn't load dispos '{}'", dispos_name),
+                }],
+                (_, None) => vec![DisposIssue {
+                    spawn: None,
+                    message: format!("couldn't load terrain '{}'", terrain_name),
+                }],
+            };
+            (!issues.is_empty()).then_some(ChapterIssues {
+                cid: chapter.cid,
+                issues,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,194 @@
+use astra_core::Difficulty;
+use astra_types::{Job, Person, Spawn};
+
+use crate::EditorState;
+
+/// Stats in the same order as [standard_stat_column_headers](crate::standard_stat_column_headers).
+pub type Stats = [i16; 11];
+
+macro_rules! stat_array {
+    ($target:ident, $prefix:ident) => {
+        paste::paste! {
+            [
+                $target.[<$prefix _hp>] as i16,
+                $target.[<$prefix _str>] as i16,
+                $target.[<$prefix _def>] as i16,
+                $target.[<$prefix _tech>] as i16,
+                $target.[<$prefix _quick>] as i16,
+                $target.[<$prefix _luck>] as i16,
+                $target.[<$prefix _magic>] as i16,
+                $target.[<$prefix _mdef>] as i16,
+                $target.[<$prefix _phys>] as i16,
+                $target.[<$prefix _move>] as i16,
+                $target.[<$prefix _sight>] as i16,
+            ]
+        }
+    };
+}
+
+/// What a spawn's stats work out to in game on one difficulty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveStats {
+    pub level: u8,
+    pub stats: Stats,
+    pub caps: Stats,
+}
+
+fn add(a: Stats, b: Stats) -> Stats {
+    std::array::from_fn(|index| a[index] + b[index])
+}
+
+/// Work out a spawn's stats the way the game auto levels units:
+/// class bases plus the character's offsets for the difficulty, then one level of class
+/// difficulty growths for every level past 1. Promoted classes count their internal level
+/// and `auto_grow_offset_*` adds extra levels. Stats are clamped to the class caps
+/// plus the character's cap modifiers.
+///
+/// Returns `None` if the spawn's character or class doesn't exist.
+pub fn effective_stats(
+    state: &EditorState,
+    spawn: &Spawn,
+    difficulty: Difficulty,
+) -> Option<EffectiveStats> {
+    state.person.read(|persons| {
+        let person = persons.get(&spawn.pid)?;
+        let jid = if spawn.jid.is_empty() {
+            &person.jid
+        } else {
+            &spawn.jid
+        };
+        state
+            .job
+            .read(|jobs| Some(compute(spawn, person, jobs.get(jid)?, difficulty)))
+    })
+}
+
+fn compute(spawn: &Spawn, person: &Person, job: &Job, difficulty: Difficulty) -> EffectiveStats {
+    let (level, auto_grow_offset, offsets, difficulty_growths) = match difficulty {
+        Difficulty::Hard => (
+            spawn.level_h,
+            person.auto_grow_offset_h,
+            stat_array!(person, offset_h),
+            stat_array!(job, diff_grow_hard),
+        ),
+        Difficulty::Lunatic => (
+            spawn.level_l,
+            person.auto_grow_offset_l,
+            stat_array!(person, offset_l),
+            stat_array!(job, diff_grow_lunatic),
+        ),
+        Difficulty::All | Difficulty::Normal => (
+            spawn.level_n,
+            person.auto_grow_offset_n,
+            stat_array!(person, offset_n),
+            stat_array!(job, diff_grow_normal),
+        ),
+    };
+    // A level of zero leaves the character at their own level.
+    let level = if level == 0 { person.level } else { level };
+    // Worked in i32 since high levels times high growths don't fit in a stat.
+    let levels_gained =
+        (level as i32 - 1 + job.internal_level.max(0) as i32 + auto_grow_offset as i32).max(0);
+
+    let growths = add(stat_array!(job, diff_grow), difficulty_growths);
+    let bases = add(stat_array!(job, base), offsets);
+    let caps = add(stat_array!(job, limit), stat_array!(person, limit));
+    let stats = std::array::from_fn(|index| {
+        let stat = bases[index] as i32 + levels_gained * growths[index].max(0) as i32 / 100;
+        // Caps of zero, ex. sight on some classes, are treated as uncapped.
+        let cap = if caps[index] > 0 {
+            caps[index]
+        } else {
+            i16::MAX
+        };
+        stat.clamp(0, cap as i32) as i16
+    });
+    EffectiveStats { level, stats, caps }
+}
+
+/// One class in a [project_stats] plan and the level to train it to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassPlan {
+    pub jid: String,
+    pub target_level: u8,
+}
+
+/// A character's average stats at one level of a plan.
+#[derive(Debug, Clone)]
+pub struct ProjectedLevel {
+    pub jid: String,
+    pub level: u8,
+    pub stats: [f32; 11],
+    pub caps: Stats,
+}
+
+/// Average stats for every level of a plan, starting from the character's level in the first class.
+/// Each level adds the character's growths plus the class's growths, capped at the class caps
+/// plus the character's cap modifiers. Changing class starts over at level 1 and swaps
+/// the old class's bases for the new one's.
+///
+/// Returns `None` if the character or any class in the plan doesn't exist.
+pub fn project_stats(
+    state: &EditorState,
+    pid: &str,
+    plan: &[ClassPlan],
+) -> Option<Vec<ProjectedLevel>> {
+    state.person.read(|persons| {
+        let person = persons.get(pid)?;
+        state.job.read(|jobs| {
+            let person_growths = stat_array!(person, grow);
+            let person_caps = stat_array!(person, limit);
+            let mut projection = vec![];
+            let mut stats = [0.; 11];
+            let mut previous_bases: Option<Stats> = None;
+            for (step, class) in plan.iter().enumerate() {
+                let job = jobs.get(&class.jid)?;
+                let bases = stat_array!(job, base);
+                let caps = add(stat_array!(job, limit), person_caps);
+                let growths = add(stat_array!(job, base_grow), person_growths);
+                let clamp = |stat: f32, index: usize| {
+                    if caps[index] > 0 {
+                        stat.clamp(0., caps[index] as f32)
+                    } else {
+                        stat.max(0.)
+                    }
+                };
+                match previous_bases {
+                    Some(previous) => {
+                        for (index, stat) in stats.iter_mut().enumerate() {
+                            *stat = clamp(*stat + (bases[index] - previous[index]) as f32, index);
+                        }
+                    }
+                    None => {
+                        let offsets = stat_array!(person, offset_n);
+                        for (index, stat) in stats.iter_mut().enumerate() {
+                            *stat = clamp((bases[index] + offsets[index]) as f32, index);
+                        }
+                    }
+                }
+                previous_bases = Some(bases);
+
+                let start = if step == 0 { person.level.max(1) } else { 1 };
+                let end = if job.max_level > 0 {
+                    class.target_level.min(job.max_level)
+                } else {
+                    class.target_level
+                };
+                for level in start..=end.max(start) {
+                    if level > start {
+                        for (index, stat) in stats.iter_mut().enumerate() {
+                            *stat = clamp(*stat + growths[index] as f32 / 100., index);
+                        }
+                    }
+                    projection.push(ProjectedLevel {
+                        jid: class.jid.clone(),
+                        level,
+                        stats,
+                        caps,
+                    });
+                }
+            }
+            Some(projection)
+        })
+    })
+}
@@ -0,0 +1,209 @@
+use astra_core::{chapter_file_name, Difficulty};
+use astra_types::{Job, Person};
+use indexmap::IndexMap;
+
+use crate::EditorState;
+
+pub const ENEMY_FORCE: i8 = 1;
+
+fn difficulty_slot(difficulty: Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Hard => 1,
+        Difficulty::Lunatic => 2,
+        Difficulty::All | Difficulty::Normal => 0,
+    }
+}
+
+/// Expected player strength and actual enemy levels for one chapter.
+/// Per-difficulty values are indexed Normal, Hard, Lunatic and, unless noted otherwise, count
+/// promoted classes' internal levels, so a level 5 unit in a promoted class counts as 25.
+#[derive(Debug, Clone)]
+pub struct ChapterLevels {
+    pub cid: String,
+    pub recommended_level: u8,
+    pub hold_level: u8,
+    /// Average level of the chart's player units.
+    pub expected: [Option<f32>; 3],
+    pub enemy_average: [Option<f32>; 3],
+    /// Average enemy level as the game shows it, on the same scale as the recommended level.
+    pub enemy_displayed_average: [Option<f32>; 3],
+    pub enemy_max: [Option<u8>; 3],
+}
+
+impl ChapterLevels {
+    pub fn recommended_level(&self) -> Option<f32> {
+        (self.recommended_level > 0).then_some(self.recommended_level as f32)
+    }
+
+    /// How far the average enemy sits above (or below) the chart average, or the recommended
+    /// level if the chart doesn't cover the chapter. The recommended level doesn't count
+    /// internal levels, so it's measured against the displayed enemy average instead.
+    pub fn gap(&self, difficulty: Difficulty) -> Option<f32> {
+        let slot = difficulty_slot(difficulty);
+        match self.expected[slot] {
+            Some(expected) => Some(self.enemy_average[slot]? - expected),
+            None => Some(self.enemy_displayed_average[slot]? - self.recommended_level()?),
+        }
+    }
+
+    pub fn enemy_average(&self, difficulty: Difficulty) -> Option<f32> {
+        self.enemy_average[difficulty_slot(difficulty)]
+    }
+
+    pub fn enemy_displayed_average(&self, difficulty: Difficulty) -> Option<f32> {
+        self.enemy_displayed_average[difficulty_slot(difficulty)]
+    }
+
+    pub fn enemy_max(&self, difficulty: Difficulty) -> Option<u8> {
+        self.enemy_max[difficulty_slot(difficulty)]
+    }
+
+    pub fn expected(&self, difficulty: Difficulty) -> Option<f32> {
+        self.expected[difficulty_slot(difficulty)]
+    }
+}
+
+/// Change in [ChapterLevels::gap] from the previous chapter that has one.
+/// Positive values are difficulty jumps, negative values are sags.
+pub fn gap_swings(levels: &[ChapterLevels], difficulty: Difficulty) -> Vec<Option<f32>> {
+    let mut previous = None;
+    levels
+        .iter()
+        .map(|chapter| {
+            let gap = chapter.gap(difficulty)?;
+            let swing = previous.map(|previous| gap - previous);
+            previous = Some(gap);
+            swing
+        })
+        .collect()
+}
+
+/// A unit's level as the game shows it.
+pub fn displayed_level(level: u8, pid: &str, persons: &IndexMap<String, Person>) -> u8 {
+    // A level of zero leaves the character at their own level.
+    if level == 0 {
+        persons
+            .get(pid)
+            .map(|person| person.level)
+            .unwrap_or_default()
+    } else {
+        level
+    }
+}
+
+/// A unit's level counting its class's internal level. Empty fields fall back to the character's.
+pub fn effective_level(
+    level: u8,
+    pid: &str,
+    jid: &str,
+    persons: &IndexMap<String, Person>,
+    jobs: &IndexMap<String, Job>,
+) -> u8 {
+    let level = displayed_level(level, pid, persons);
+    let internal_level = unit_job(pid, jid, persons, jobs)
+        .map(|job| job.internal_level.max(0) as u8)
+        .unwrap_or_default();
+    level.saturating_add(internal_level)
+}
+
+/// The class a unit spawns as. An empty class falls back to the character's.
+pub fn unit_job<'a>(
+    pid: &str,
+    jid: &str,
+    persons: &IndexMap<String, Person>,
+    jobs: &'a IndexMap<String, Job>,
+) -> Option<&'a Job> {
+    if jid.is_empty() {
+        jobs.get(&persons.get(pid)?.jid)
+    } else {
+        jobs.get(jid)
+    }
+}
+
+pub fn average_level(levels: &[u8]) -> Option<f32> {
+    (!levels.is_empty())
+        .then(|| levels.iter().map(|level| *level as f32).sum::<f32>() / levels.len() as f32)
+}
+
+/// Collect [ChapterLevels] for every chapter with a main dispos, in chapter order.
+/// Chart groups are matched to chapters by CID, with or without the `CID_` prefix.
+pub fn level_curve(state: &EditorState) -> Vec<ChapterLevels> {
+    let chapters: Vec<(String, String, u8, u8)> = state.chapter.read(|data| {
+        data.values()
+            .filter(|chapter| !chapter.dispos.is_empty())
+            .map(|chapter| {
+                (
+                    chapter.cid.clone(),
+                    chapter_file_name(&chapter.cid, &chapter.dispos),
+                    chapter.recommended_level,
+                    chapter.hold_level,
+                )
+            })
+            .collect()
+    });
+    state.person.read(|persons| {
+        state.job.read(|jobs| {
+            state.chart.read(|chart| {
+                chapters
+                    .into_iter()
+                    .filter_map(|(cid, dispos, recommended_level, hold_level)| {
+                        let dispos = state.astra.write().get_dispos(&dispos)?;
+                        let mut enemies: [Vec<u8>; 3] = Default::default();
+                        let mut displayed: [Vec<u8>; 3] = Default::default();
+                        dispos.read(|book| {
+                            for spawn in book.spawns.data.values().flatten() {
+                                if spawn.force != ENEMY_FORCE {
+                                    continue;
+                                }
+                                for (difficulty, level) in [
+                                    (Difficulty::Normal, spawn.level_n),
+                                    (Difficulty::Hard, spawn.level_h),
+                                    (Difficulty::Lunatic, spawn.level_l),
+                                ] {
+                                    if !difficulty.includes(spawn) {
+                                        continue;
+                                    }
+                                    let slot = difficulty_slot(difficulty);
+                                    enemies[slot].push(effective_level(
+                                        level, &spawn.pid, &spawn.jid, persons, jobs,
+                                    ));
+                                    displayed[slot]
+                                        .push(displayed_level(level, &spawn.pid, persons));
+                                }
+                            }
+                        });
+                        let mut expected: [Vec<u8>; 3] = Default::default();
+                        let chart_entries = chart
+                            .get(&cid)
+                            .or_else(|| chart.get(cid.trim_start_matches("CID_")));
+                        for entry in chart_entries.into_iter().flatten() {
+                            for (slot, level) in [entry.level_n, entry.level_h, entry.level_l]
+                                .into_iter()
+                                .enumerate()
+                            {
+                                expected[slot].push(effective_level(
+                                    level, &entry.pid, &entry.jid, persons, jobs,
+                                ));
+                            }
+                        }
+                        Some(ChapterLevels {
+                            cid,
+                            recommended_level,
+                            hold_level,
+                            expected: std::array::from_fn(|slot| average_level(&expected[slot])),
+                            enemy_average: std::array::from_fn(|slot| {
+                                average_level(&enemies[slot])
+                            }),
+                            enemy_displayed_average: std::array::from_fn(|slot| {
+                                average_level(&displayed[slot])
+                            }),
+                            enemy_max: std::array::from_fn(|slot| {
+                                enemies[slot].iter().max().copied()
+                            }),
+                        })
+                    })
+                    .collect()
+            })
+        })
+    })
+}
@@ -1,11 +1,31 @@
+mod audio_validation;
 mod cached_view;
+mod chapter_balance;
+mod chapter_graph;
+mod clipboard;
 mod config;
+mod difficulty_comparison;
+mod dispos_validation;
+mod effective_stats;
+mod level_curve;
+mod movement;
+mod reinforcements;
 mod sheet;
 mod shortcuts;
 mod theme;
 
+pub use audio_validation::*;
 pub use cached_view::*;
+pub use chapter_balance::*;
+pub use chapter_graph::*;
+pub use clipboard::*;
 pub use config::*;
+pub use difficulty_comparison::*;
+pub use dispos_validation::*;
+pub use effective_stats::*;
+pub use level_curve::*;
+pub use movement::*;
+pub use reinforcements::*;
 pub use sheet::*;
 pub use shortcuts::*;
 pub use theme::*;
@@ -100,6 +120,10 @@ pub trait ListModel<I> {
     /// Copy the contents of index `a` to index `b`.
     fn copy(&mut self, a: usize, b: usize);
 
+    /// Insert items from outside the model (ex. the clipboard) starting at `index`.
+    /// Keyed models rename items whose key is already taken instead of overwriting.
+    fn paste(&mut self, index: usize, items: Vec<I>) -> PasteResult;
+
     /// Convert a row number to its index in the underlying collection.
     fn row_to_index(&self, row_number: usize) -> Option<usize>;
 }
@@ -148,6 +172,17 @@ where
         }
     }
 
+    fn paste(&mut self, index: usize, items: Vec<I>) -> PasteResult {
+        let mut index = index.min(self.len());
+        let mut result = PasteResult::default();
+        for item in items {
+            self.insert(index, item);
+            result.last = Some(index);
+            index += 1;
+        }
+        result
+    }
+
     fn row_to_index(&self, row_number: usize) -> Option<usize> {
         (0..self.len()).contains(&row_number).then_some(row_number)
     }
@@ -204,6 +239,28 @@ where
         }
     }
 
+    fn paste(&mut self, index: usize, items: Vec<I>) -> PasteResult {
+        let mut index = index.min(self.len());
+        let mut result = PasteResult::default();
+        for mut item in items {
+            let mut key = item.key().into_owned();
+            if self.contains_key(&key) {
+                let renamed = (2..)
+                    .map(|suffix| format!("{}_{}", key, suffix))
+                    .find(|candidate| !self.contains_key(candidate))
+                    .unwrap_or_default();
+                item.set_key(renamed.clone());
+                result.renamed.push((key, renamed.clone()));
+                key = renamed;
+            }
+            self.insert(key, item);
+            self.move_index(self.len() - 1, index);
+            result.last = Some(index);
+            index += 1;
+        }
+        result
+    }
+
     fn row_to_index(&self, row_number: usize) -> Option<usize> {
         (0..self.len()).contains(&row_number).then_some(row_number)
     }
@@ -260,6 +317,11 @@ impl FilterProxyBuilder {
             .and_then(|real_index| (*real_index < source_model.len()).then_some(*real_index))
     }
 
+    /// Source model indices of every item that passed the filter, in order.
+    pub fn source_indices(&self) -> &[usize] {
+        &self.proxy_indices
+    }
+
     /// Retrieve the proxy index of an item from its index in the source model.
     pub fn proxy_index(&self, source_index: usize) -> Option<usize> {
         self.proxy_indices
@@ -375,6 +437,10 @@ where
         unimplemented!("modify the source model instead")
     }
 
+    fn paste(&mut self, _: usize, _: Vec<I>) -> PasteResult {
+        unimplemented!("modify the source model instead")
+    }
+
     fn row_to_index(&self, row_number: usize) -> Option<usize> {
         self.proxy_indices
             .get(row_number)
@@ -0,0 +1,288 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use astra_core::{is_attack_kind, spawn_items, Difficulty, TERRAIN_STRIDE};
+use astra_formats::TerrainData;
+use astra_types::{Spawn, TerrainCostData};
+use indexmap::IndexMap;
+
+use crate::{CoordinateKind, EditorState};
+
+/// A map position as `(col, row)`, the same order spawns store their coordinates in.
+pub type Tile = (usize, usize);
+
+pub fn spawn_tile(spawn: &Spawn, coordinate_kind: CoordinateKind) -> Tile {
+    match coordinate_kind {
+        CoordinateKind::Dispos => (spawn.dispos_x as usize, spawn.dispos_y as usize),
+        CoordinateKind::Appear => (spawn.appear_x as usize, spawn.appear_y as usize),
+    }
+}
+
+fn cost_for_move_type(cost: &TerrainCostData, move_type: i8) -> u8 {
+    match move_type {
+        1 => cost.foot,
+        2 => cost.horse,
+        3 => cost.fly,
+        4 => cost.dragon,
+        5 => cost.pad,
+        _ => cost.none,
+    }
+}
+
+/// The cost of entering each tile of a map for one move type.
+pub struct MovementCosts {
+    width: usize,
+    height: usize,
+    /// `None` for tiles the move type can't enter.
+    costs: Vec<Option<u8>>,
+}
+
+impl MovementCosts {
+    pub fn new(terrain: &TerrainData, state: &EditorState, move_type: i8) -> Self {
+        let (width, height) = (terrain.width as usize, terrain.height as usize);
+        let cost_names: HashMap<String, u8> = state.terrain_cost.read(|data| {
+            data.iter()
+                .map(|cost| (cost.name.clone(), cost_for_move_type(cost, move_type)))
+                .collect()
+        });
+        let costs = state.terrain.read(|data| {
+            (0..height)
+                .flat_map(|row| (0..width).map(move |col| row * TERRAIN_STRIDE + col))
+                .map(|index| {
+                    terrain
+                        .terrains
+                        .get(index)
+                        .and_then(|tid| data.get(tid.as_str()))
+                        .and_then(|tile| cost_names.get(&tile.cost_name))
+                        .copied()
+                        // Zero is what the game uses for walls, water for foot units, etc.
+                        .filter(|cost| *cost > 0)
+                })
+                .collect()
+        });
+        Self {
+            width,
+            height,
+            costs,
+        }
+    }
+
+    pub fn cost(&self, (col, row): Tile) -> Option<u8> {
+        if col < self.width && row < self.height {
+            self.costs[row * self.width + col]
+        } else {
+            None
+        }
+    }
+
+    fn neighbors(&self, (col, row): Tile) -> impl Iterator<Item = Tile> + '_ {
+        [
+            col.checked_sub(1).map(|col| (col, row)),
+            Some((col + 1, row)),
+            row.checked_sub(1).map(|row| (col, row)),
+            Some((col, row + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|(col, row)| *col < self.width && *row < self.height)
+    }
+
+    /// Tiles on the map within `min..=max` steps of the given tile.
+    fn in_range(&self, (col, row): Tile, min: u8, max: u8) -> impl Iterator<Item = Tile> + '_ {
+        let max = (max as isize).min((self.width + self.height) as isize);
+        (-max..=max).flat_map(move |dy| {
+            let remaining = max - dy.abs();
+            (-remaining..=remaining).filter_map(move |dx| {
+                let distance = dx.abs() + dy.abs();
+                let (col, row) = (col as isize + dx, row as isize + dy);
+                (distance >= min as isize
+                    && (0..self.width as isize).contains(&col)
+                    && (0..self.height as isize).contains(&row))
+                .then_some((col as usize, row as usize))
+            })
+        })
+    }
+
+    /// Whether a unit could walk from `start` to `end` given unlimited turns, ignoring other units.
+    pub fn path_exists(&self, start: Tile, end: Tile) -> bool {
+        let mut visited = HashSet::from([start]);
+        let mut queue = vec![start];
+        while let Some(tile) = queue.pop() {
+            if tile == end {
+                return true;
+            }
+            for next in self.neighbors(tile) {
+                if self.cost(next).is_some() && visited.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Every tile a unit at `start` can end its move on.
+    /// `blocked` tiles hold hostile units and can't be entered at all.
+    /// `occupied` tiles hold allies, which can be passed through but not stopped on.
+    pub fn reachable(
+        &self,
+        start: Tile,
+        movement: u8,
+        blocked: &HashSet<Tile>,
+        occupied: &HashSet<Tile>,
+    ) -> HashSet<Tile> {
+        let mut best = HashMap::from([(start, 0u32)]);
+        let mut queue = BinaryHeap::from([Reverse((0u32, start))]);
+        while let Some(Reverse((spent, tile))) = queue.pop() {
+            if best.get(&tile).is_some_and(|best| *best < spent) {
+                continue;
+            }
+            for next in self.neighbors(tile) {
+                if blocked.contains(&next) {
+                    continue;
+                }
+                let Some(cost) = self.cost(next) else {
+                    continue;
+                };
+                let total = spent + cost as u32;
+                if total > movement as u32 || best.get(&next).is_some_and(|best| *best <= total) {
+                    continue;
+                }
+                best.insert(next, total);
+                queue.push(Reverse((total, next)));
+            }
+        }
+        best.into_keys()
+            .filter(|tile| *tile == start || !occupied.contains(tile))
+            .collect()
+    }
+}
+
+/// The spawn's move type and movement stat: its class's base move plus the character's offset.
+pub fn spawn_movement(state: &EditorState, spawn: &Spawn, difficulty: Difficulty) -> (i8, u8) {
+    let person = state.person.read(|data| data.get(&spawn.pid).cloned());
+    let jid = if spawn.jid.is_empty() {
+        person
+            .as_ref()
+            .map(|person| person.jid.clone())
+            .unwrap_or_default()
+    } else {
+        spawn.jid.clone()
+    };
+    let (move_type, base_move) = state.job.read(|data| {
+        data.get(&jid)
+            .map(|job| (job.move_type, job.base_move))
+            .unwrap_or_default()
+    });
+    let offset = person
+        .map(|person| match difficulty {
+            Difficulty::Hard => person.offset_h_move,
+            Difficulty::Lunatic => person.offset_l_move,
+            _ => person.offset_n_move,
+        })
+        .unwrap_or_default();
+    (
+        move_type,
+        (base_move as i16 + offset as i16).clamp(0, u8::MAX as i16) as u8,
+    )
+}
+
+/// Units on the same side block each other's destinations, units on opposing sides block paths.
+fn hostile(a: i8, b: i8) -> bool {
+    (a == 1) != (b == 1)
+}
+
+/// The tiles of every other spawn on the difficulty, split into hostile and friendly units.
+fn blockers(
+    dispos: &IndexMap<String, Vec<Spawn>>,
+    spawn: &Spawn,
+    coordinate_kind: CoordinateKind,
+    difficulty: Difficulty,
+) -> (HashSet<Tile>, HashSet<Tile>) {
+    let start = spawn_tile(spawn, coordinate_kind);
+    let mut blocked = HashSet::new();
+    let mut occupied = HashSet::new();
+    for other in dispos.values().flatten() {
+        let tile = spawn_tile(other, coordinate_kind);
+        if tile == start || !difficulty.includes(other) {
+            continue;
+        }
+        if hostile(spawn.force, other.force) {
+            blocked.insert(tile);
+        } else {
+            occupied.insert(tile);
+        }
+    }
+    (blocked, occupied)
+}
+
+/// Tiles the spawn can move to, taking every other spawn on the difficulty into account.
+pub fn spawn_movement_range(
+    terrain: &TerrainData,
+    state: &EditorState,
+    dispos: &IndexMap<String, Vec<Spawn>>,
+    spawn: &Spawn,
+    coordinate_kind: CoordinateKind,
+    difficulty: Difficulty,
+) -> HashSet<Tile> {
+    let start = spawn_tile(spawn, coordinate_kind);
+    let (blocked, occupied) = blockers(dispos, spawn, coordinate_kind, difficulty);
+    let (move_type, movement) = spawn_movement(state, spawn, difficulty);
+    MovementCosts::new(terrain, state, move_type).reachable(start, movement, &blocked, &occupied)
+}
+
+/// The distinct `(min, max)` ranges of the weapons in the spawn's inventory.
+fn weapon_ranges(state: &EditorState, spawn: &Spawn) -> Vec<(u8, u8)> {
+    state.item.read(|data| {
+        let mut ranges = vec![];
+        for (iid, _) in spawn_items(spawn) {
+            let Some(item) = data.get(iid) else {
+                continue;
+            };
+            let range = (item.range_i.max(1), item.range_o);
+            if is_attack_kind(item.kind) && range.1 > 0 && !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+        ranges
+    })
+}
+
+/// Tiles any enemy can attack next turn: its movement range grown by each of its weapons' ranges.
+/// Spawns in `hidden_groups` are left out.
+pub fn danger_zone(
+    terrain: &TerrainData,
+    state: &EditorState,
+    dispos: &IndexMap<String, Vec<Spawn>>,
+    coordinate_kind: CoordinateKind,
+    difficulty: Difficulty,
+    hidden_groups: &HashSet<String>,
+) -> HashSet<Tile> {
+    let mut costs_by_move_type: HashMap<i8, MovementCosts> = HashMap::new();
+    let mut zone = HashSet::new();
+    for (group, spawns) in dispos {
+        if hidden_groups.contains(group) {
+            continue;
+        }
+        for spawn in spawns {
+            if spawn.force != 1 || !difficulty.includes(spawn) {
+                continue;
+            }
+            let ranges = weapon_ranges(state, spawn);
+            if ranges.is_empty() {
+                continue;
+            }
+            let (move_type, movement) = spawn_movement(state, spawn, difficulty);
+            let costs = costs_by_move_type
+                .entry(move_type)
+                .or_insert_with(|| MovementCosts::new(terrain, state, move_type));
+            let (blocked, occupied) = blockers(dispos, spawn, coordinate_kind, difficulty);
+            let start = spawn_tile(spawn, coordinate_kind);
+            for tile in costs.reachable(start, movement, &blocked, &occupied) {
+                for (min, max) in &ranges {
+                    zone.extend(costs.in_range(tile, *min, *max));
+                }
+            }
+        }
+    }
+    zone
+}
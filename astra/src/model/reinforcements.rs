@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+
+/// When a spawn group called from a chapter script arrives.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ArrivalTiming {
+    /// Registered with `EventEntryTurn`. Both ends of the range are inclusive.
+    Turn { from: u32, to: u32 },
+    /// Registered with another `EventEntry*` call, ex. `Pos` or `Destroy`.
+    Event(String),
+    /// The calling function isn't registered as an event, ex. map setup or a helper.
+    Unknown,
+}
+
+impl ArrivalTiming {
+    pub fn label(&self) -> String {
+        match self {
+            ArrivalTiming::Turn { from, to } if from == to => format!("Turn {}", from),
+            ArrivalTiming::Turn { from, to } => format!("Turns {}-{}", from, to),
+            ArrivalTiming::Event(kind) => format!("{} event", kind),
+            ArrivalTiming::Unknown => "Unknown".to_string(),
+        }
+    }
+
+    /// The earliest turn of a turn based arrival.
+    pub fn first_turn(&self) -> Option<u32> {
+        match self {
+            ArrivalTiming::Turn { from, .. } => Some(*from),
+            _ => None,
+        }
+    }
+}
+
+/// A dispos group referenced by a chapter script.
+#[derive(Debug, Clone)]
+pub struct ScriptedSpawn {
+    pub group: String,
+    /// The script function that references the group, if any.
+    pub function: Option<String>,
+    pub timing: ArrivalTiming,
+    /// 1-based line of the reference.
+    pub line: usize,
+}
+
+struct EventEntry {
+    kind: String,
+    args: Vec<String>,
+}
+
+fn function_name(line: &str) -> Option<String> {
+    let header = line
+        .strip_prefix("local function ")
+        .or_else(|| line.strip_prefix("function "))?;
+    let name = header.split('(').next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn string_literals(line: &str) -> Vec<&str> {
+    let mut literals = vec![];
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let body = &rest[start + 1..];
+        let Some(end) = body.find(quote) else {
+            break;
+        };
+        literals.push(&body[..end]);
+        rest = &body[end + 1..];
+    }
+    literals
+}
+
+fn event_entries(line: &str) -> Vec<EventEntry> {
+    let mut entries = vec![];
+    let mut rest = line;
+    while let Some(start) = rest.find("EventEntry") {
+        let call = &rest[start + "EventEntry".len()..];
+        let kind: String = call
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        let call = &call[kind.len()..];
+        rest = call;
+        let Some(args) = call
+            .trim_start()
+            .strip_prefix('(')
+            .and_then(|args| args.split(')').next())
+        else {
+            continue;
+        };
+        entries.push(EventEntry {
+            kind,
+            args: args
+                .split(',')
+                .map(|arg| arg.trim().trim_matches(['"', '\'']).to_string())
+                .collect(),
+        });
+    }
+    entries
+}
+
+fn timing(entry: &EventEntry) -> ArrivalTiming {
+    let turn = |index: usize| entry.args.get(index).and_then(|arg| arg.parse().ok());
+    match (entry.kind.as_str(), turn(1)) {
+        ("Turn", Some(from)) => ArrivalTiming::Turn {
+            from,
+            to: turn(2).unwrap_or(from).max(from),
+        },
+        _ => ArrivalTiming::Event(entry.kind.clone()),
+    }
+}
+
+/// Find where a chapter script calls into the given dispos groups and when those calls happen.
+/// This is a line based scan, not a Lua parser: a group reference is a string literal naming
+/// the group, and its timing comes from the `EventEntry*` calls that register the enclosing
+/// function. Results are sorted by timing, then by line.
+pub fn scan_reinforcements<'a>(
+    script: &str,
+    groups: impl IntoIterator<Item = &'a str>,
+) -> Vec<ScriptedSpawn> {
+    let groups: HashSet<&str> = groups.into_iter().collect();
+    let mut registrations: HashMap<String, Vec<ArrivalTiming>> = HashMap::new();
+    let mut references: Vec<(String, Option<String>, usize)> = vec![];
+    let mut function = None;
+    for (index, line) in script.lines().enumerate() {
+        let code = line.split("--").next().unwrap_or_default();
+        if let Some(name) = function_name(code.trim_start()) {
+            function = Some(name);
+        } else if code.trim_end() == "end" {
+            // An unindented end closes the function, anything else is a nested block.
+            function = None;
+        }
+        for entry in event_entries(code) {
+            if let Some(target) = entry.args.first() {
+                registrations
+                    .entry(target.clone())
+                    .or_default()
+                    .push(timing(&entry));
+            }
+        }
+        for literal in string_literals(code) {
+            if groups.contains(literal) {
+                references.push((literal.to_string(), function.clone(), index + 1));
+            }
+        }
+    }
+
+    let mut spawns: Vec<ScriptedSpawn> = references
+        .into_iter()
+        .flat_map(|(group, function, line)| {
+            let timings = function
+                .as_ref()
+                .and_then(|function| registrations.get(function))
+                .cloned()
+                .unwrap_or_else(|| vec![ArrivalTiming::Unknown]);
+            timings.into_iter().map(move |timing| ScriptedSpawn {
+                group: group.clone(),
+                function: function.clone(),
+                timing,
+                line,
+            })
+        })
+        .collect();
+    spawns.sort_by(|a, b| a.timing.cmp(&b.timing).then(a.line.cmp(&b.line)));
+    spawns
+}
+
+/// One row of a reinforcement timeline.
+#[derive(Debug, Clone)]
+pub struct TimelineRow {
+    pub label: String,
+    pub spawns: Vec<ScriptedSpawn>,
+}
+
+/// Lay scripted spawns out by arrival. Every turn up to the last scripted one gets a row so
+/// quiet turns show up as gaps. Spawns registered for a range of turns are listed on the first.
+/// Event and unregistered calls come after the turns, one row per kind.
+pub fn reinforcement_timeline(spawns: &[ScriptedSpawn]) -> Vec<TimelineRow> {
+    let turns: Vec<u32> = spawns
+        .iter()
+        .filter_map(|spawn| spawn.timing.first_turn())
+        .collect();
+    let first = turns.iter().min().map_or(1, |first| (*first).min(1));
+    let last = turns.iter().max().copied().unwrap_or_default();
+    let mut rows: Vec<TimelineRow> = (first..=last)
+        .map(|turn| TimelineRow {
+            label: format!("Turn {}", turn),
+            spawns: spawns
+                .iter()
+                .filter(|spawn| spawn.timing.first_turn() == Some(turn))
+                .cloned()
+                .collect(),
+        })
+        .collect();
+    for spawn in spawns {
+        if spawn.timing.first_turn().is_some() {
+            continue;
+        }
+        let label = spawn.timing.label();
+        match rows.iter_mut().find(|row| row.label == label) {
+            Some(row) => row.spawns.push(spawn.clone()),
+            None => rows.push(TimelineRow {
+                label,
+                spawns: vec![spawn.clone()],
+            }),
+        }
+    }
+    rows
+}
@@ -11,8 +11,8 @@ use astra_types::{
     Accessory, AccessoryShopInventory, AnimSet, AnimSetBook, AssetDef, AssetTableBook, Chapter,
     ChapterBook, DisposBook, ForgeEvolveData, ForgeExchangeData, ForgeImproveData, GameParam,
     GodBondLevelData, GodBook, GodData, GodLevelData, Item, ItemBook, Job, JobBook, ParamsBook,
-    Person, PersonBook, RelianceBonusData, RelianceBook, RelianceData, RelianceExpData, ShopBook,
-    ShopInventory, Skill, SkillBook, Spawn, TerrainBook, TerrainData,
+    Person, PersonBook, RelianceBonusData, RelianceBook, RelianceData, RelianceExpData, RewardData,
+    ShopBook, ShopInventory, Skill, SkillBook, Spawn, TerrainBook, TerrainCostData, TerrainData,
 };
 use egui::TextureHandle;
 use indexmap::IndexMap;
@@ -21,7 +21,7 @@ use parking_lot::RwLock;
 
 use crate::{
     AchievementSheet, AiSheet, AmiiboSheet, AnimalSheet, ArenaSheet, BelongSheet, CalculatorSheet,
-    ChartGodDataSheet, ChartParamSheet, ChartSheet, CookSheet, DecorationKind,
+    ChallengeSheet, ChartGodDataSheet, ChartParamSheet, ChartSheet, CookSheet, DecorationKind,
     DragonRidePresetParamSheet, DragonRidePrizeSheet, DragonRideTargetPatternSheet,
     EffectSequenceSheet, EffectSheet, EncountEnemyTypeSheet, EncountEquipmentSheet,
     EncountRarityConfigSheet, EncountWeaponCategorySheet, EndRollDataSheet, ExpTableSheet,
@@ -70,6 +70,7 @@ pub struct EditorState {
     pub asset_table: AssetTableSheet,
     pub belong: BelongSheet,
     pub calculator: CalculatorSheet,
+    pub challenge: ChallengeSheet,
     pub chapter: ChapterSheet,
     pub chart: ChartSheet,
     pub chart_god: ChartGodDataSheet,
@@ -175,6 +176,7 @@ pub struct EditorState {
     pub reliance: RelianceDataSheet,
     pub reliance_exp_data: RelianceExpDataSheet,
     pub reliance_bonus_data: RelianceBonusDataSheet,
+    pub reward_data: RewardDataSheet,
     pub ring_data: RingDataSheet,
     pub ring_polish_voice: RingPolishVoiceDataSheet,
     pub skill: SkillSheet,
@@ -186,6 +188,7 @@ pub struct EditorState {
     pub tips: TipDataSheet,
     pub tutorials: TutorialDataSheet,
     pub terrain: TerrainDataSheet,
+    pub terrain_cost: TerrainCostDataSheet,
     pub vibration_data: VibrationDefineDataSheet,
 }
 
@@ -661,6 +664,8 @@ impl KeyedViewItem for Item {
     }
 }
 
+sheet_retriever!(RewardData, ItemBook, reward_data, IndexMap<String, Vec<RewardData>>);
+
 sheet_retriever!(Job, JobBook, jobs, IndexMap<String, Job>);
 
 impl ViewItem for Job {
@@ -1036,6 +1041,12 @@ impl ViewItem for Spawn {
 }
 
 sheet_retriever!(TerrainData, TerrainBook, terrain_data, IndexMap<String, TerrainData>);
+sheet_retriever!(
+    TerrainCostData,
+    TerrainBook,
+    terrain_cost_data,
+    Vec<TerrainCostData>
+);
 
 impl ViewItem for TerrainData {
     type Dependencies = EditorState;
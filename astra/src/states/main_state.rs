@@ -11,13 +11,15 @@ use astra_core::{Astra, RomSource};
 use crate::widgets::{about_modal, config_editor_modal};
 use crate::{
     AccessoryEditor, AchieveEditor, AiEditor, AnimSetEditor, AnimalEditor, AppConfig, AppState,
-    ArenaEditor, AssetTableEditor, CalculatorEditor, ChapterEditor, ChartEditor, CookEditor,
-    DragonRideEditor, EditorState, EffectEditor, EncountEditor, FishingFishEditor, ForgeEditor,
-    FriendListEditor, GameParamEditor, GodDataSheetRetriever, GodEditor, HubAreaEditor, ItemEditor,
-    JobEditor, KillBonusEditor, LaterTalkEditor, MapEditorEditor, MascotEditor, MessageDb,
-    MessageDbWrapper, MiscEditor, MovieEditor, MuscleExerciseDataEditor, MusicEditor, PersonEditor,
-    PhotographSpotEditor, ProfileCardEditor, RelayEditor, RelianceEditor, RingEditor, SaveScreen,
-    ScriptManager, SheetHandle, ShopEditor, SkillEditor, TerrainDataEditor, TextDataEditor,
+    ArenaEditor, AssetTableEditor, AudioCheckScreen, CalculatorEditor, ChallengeEditor,
+    ChapterEditor, ChapterGraphScreen, ChartEditor, CompareScreen, CookEditor, DragonRideEditor,
+    EditorState, EffectEditor, EncountEditor, FishingFishEditor, ForgeEditor, FriendListEditor,
+    GameParamEditor, GodDataSheetRetriever, GodEditor, HubAreaEditor, ItemEditor, ItemReportScreen,
+    JobEditor, KillBonusEditor, LaterTalkEditor, LevelCurveScreen, MapEditorEditor, MascotEditor,
+    MessageDb, MessageDbWrapper, MiscEditor, MovieEditor, MuscleExerciseDataEditor, MusicEditor,
+    PersonEditor, PhotographSpotEditor, ProfileCardEditor, RandomizerScreen, RecipeScreen,
+    RelayEditor, RelianceEditor, RingEditor, SaveScreen, ScriptManager, SheetHandle, ShopEditor,
+    SkillEditor, SpawnTuningScreen, StatPlannerScreen, TerrainDataEditor, TextDataEditor,
     TextureCache, Theme, TitleEditor, TutorialEditor, NEXT_TAB_SHORTCUT, PREV_TAB_SHORTCUT,
 };
 
@@ -49,6 +51,9 @@ impl Transition {
             Screens::God => state.god_editor.select(index),
             Screens::Item => state.item_editor.select(index),
             Screens::Job => state.job_editor.select(index),
+            Screens::MapEditor => state.map_editor_editor.select(index),
+            Screens::Movie => state.movie_editor.select(index),
+            Screens::Music => state.music_editor.select(index),
             Screens::Person => state.person_editor.select(index),
             Screens::Skill => state.skill_editor.select(index),
             Screens::Terrain => state.terrain_editor.select(index),
@@ -66,9 +71,13 @@ pub enum Screens {
     Animal,
     Arena,
     AssetTable,
+    AudioCheck,
     Calculator,
+    Challenge,
     Chart,
     Chapter,
+    ChapterGraph,
+    Compare,
     Cook,
     DragonRide,
     Effect,
@@ -79,9 +88,11 @@ pub enum Screens {
     God,
     Hub,
     Item,
+    ItemReport,
     Job,
     KillBonus,
     LaterTalk,
+    LevelCurve,
     MapEditor,
     Mascot,
     Misc,
@@ -92,6 +103,8 @@ pub enum Screens {
     Person,
     Photograph,
     ProfileCard,
+    Randomizer,
+    Recipes,
     Relay,
     Reliance,
     Ring,
@@ -99,6 +112,8 @@ pub enum Screens {
     Scripts,
     Shop,
     Skill,
+    SpawnTuning,
+    StatPlanner,
     Terrain,
     Text,
     Title,
@@ -116,41 +131,42 @@ impl Screens {
             5 => Some(Screens::Arena),
             6 => Some(Screens::AssetTable),
             7 => Some(Screens::Calculator),
-            8 => Some(Screens::Chapter),
-            9 => Some(Screens::Person),
-            10 => Some(Screens::Chart),
-            11 => Some(Screens::Job),
-            12 => Some(Screens::Cook),
-            13 => Some(Screens::Effect),
-            14 => Some(Screens::Encount),
-            15 => Some(Screens::MuscleExercise),
-            16 => Some(Screens::Fishing),
-            17 => Some(Screens::Forge),
-            18 => Some(Screens::FriendList),
-            19 => Some(Screens::God),
-            20 => Some(Screens::Hub),
-            21 => Some(Screens::Item),
-            22 => Some(Screens::KillBonus),
-            23 => Some(Screens::MapEditor),
-            24 => Some(Screens::Mascot),
-            25 => Some(Screens::Misc),
-            26 => Some(Screens::Movie),
-            27 => Some(Screens::Music),
-            28 => Some(Screens::Param),
-            29 => Some(Screens::Photograph),
-            30 => Some(Screens::LaterTalk),
-            31 => Some(Screens::ProfileCard),
-            32 => Some(Screens::Relay),
-            33 => Some(Screens::Reliance),
-            34 => Some(Screens::Ring),
-            35 => Some(Screens::Scripts),
-            36 => Some(Screens::Shop),
-            37 => Some(Screens::Skill),
-            38 => Some(Screens::Terrain),
-            39 => Some(Screens::Text),
-            40 => Some(Screens::Title),
-            41 => Some(Screens::Tutorial),
-            42 => Some(Screens::DragonRide),
+            8 => Some(Screens::Challenge),
+            9 => Some(Screens::Chapter),
+            10 => Some(Screens::Person),
+            11 => Some(Screens::Chart),
+            12 => Some(Screens::Job),
+            13 => Some(Screens::Cook),
+            14 => Some(Screens::Effect),
+            15 => Some(Screens::Encount),
+            16 => Some(Screens::MuscleExercise),
+            17 => Some(Screens::Fishing),
+            18 => Some(Screens::Forge),
+            19 => Some(Screens::FriendList),
+            20 => Some(Screens::God),
+            21 => Some(Screens::Hub),
+            22 => Some(Screens::Item),
+            23 => Some(Screens::KillBonus),
+            24 => Some(Screens::MapEditor),
+            25 => Some(Screens::Mascot),
+            26 => Some(Screens::Misc),
+            27 => Some(Screens::Movie),
+            28 => Some(Screens::Music),
+            29 => Some(Screens::Param),
+            30 => Some(Screens::Photograph),
+            31 => Some(Screens::LaterTalk),
+            32 => Some(Screens::ProfileCard),
+            33 => Some(Screens::Relay),
+            34 => Some(Screens::Reliance),
+            35 => Some(Screens::Ring),
+            36 => Some(Screens::Scripts),
+            37 => Some(Screens::Shop),
+            38 => Some(Screens::Skill),
+            39 => Some(Screens::Terrain),
+            40 => Some(Screens::Text),
+            41 => Some(Screens::Title),
+            42 => Some(Screens::Tutorial),
+            43 => Some(Screens::DragonRide),
             _ => None,
         }
     }
@@ -165,53 +181,63 @@ impl Screens {
             Screens::Arena => Some(5),
             Screens::AssetTable => Some(6),
             Screens::Calculator => Some(7),
-            Screens::Chapter => Some(8),
-            Screens::Person => Some(9),
-            Screens::Chart => Some(10),
-            Screens::Job => Some(11),
-            Screens::Cook => Some(12),
-            Screens::Effect => Some(13),
-            Screens::Encount => Some(14),
-            Screens::MuscleExercise => Some(15),
-            Screens::Fishing => Some(16),
-            Screens::Forge => Some(17),
-            Screens::FriendList => Some(18),
-            Screens::God => Some(19),
-            Screens::Hub => Some(20),
-            Screens::Item => Some(21),
-            Screens::KillBonus => Some(22),
-            Screens::MapEditor => Some(23),
-            Screens::Mascot => Some(24),
-            Screens::Misc => Some(25),
-            Screens::Movie => Some(26),
-            Screens::Music => Some(27),
-            Screens::Param => Some(28),
-            Screens::Photograph => Some(29),
-            Screens::LaterTalk => Some(30),
-            Screens::ProfileCard => Some(31),
-            Screens::Relay => Some(32),
-            Screens::Reliance => Some(33),
-            Screens::Ring => Some(34),
+            Screens::Challenge => Some(8),
+            Screens::Chapter => Some(9),
+            Screens::Person => Some(10),
+            Screens::Chart => Some(11),
+            Screens::Job => Some(12),
+            Screens::Cook => Some(13),
+            Screens::Effect => Some(14),
+            Screens::Encount => Some(15),
+            Screens::MuscleExercise => Some(16),
+            Screens::Fishing => Some(17),
+            Screens::Forge => Some(18),
+            Screens::FriendList => Some(19),
+            Screens::God => Some(20),
+            Screens::Hub => Some(21),
+            Screens::Item => Some(22),
+            Screens::KillBonus => Some(23),
+            Screens::MapEditor => Some(24),
+            Screens::Mascot => Some(25),
+            Screens::Misc => Some(26),
+            Screens::Movie => Some(27),
+            Screens::Music => Some(28),
+            Screens::Param => Some(29),
+            Screens::Photograph => Some(30),
+            Screens::LaterTalk => Some(31),
+            Screens::ProfileCard => Some(32),
+            Screens::Randomizer => None,
+            Screens::Recipes => None,
+            Screens::SpawnTuning => None,
+            Screens::StatPlanner => None,
+            Screens::ChapterGraph => None,
+            Screens::ItemReport => None,
+            Screens::LevelCurve => None,
+            Screens::AudioCheck => None,
+            Screens::Relay => Some(33),
+            Screens::Reliance => Some(34),
+            Screens::Ring => Some(35),
+            Screens::Compare => None,
             Screens::Save => None,
-            Screens::Scripts => Some(35),
-            Screens::Shop => Some(36),
-            Screens::Skill => Some(37),
-            Screens::Terrain => Some(38),
-            Screens::Text => Some(39),
-            Screens::Title => Some(40),
-            Screens::Tutorial => Some(41),
-            Screens::DragonRide => Some(42),
+            Screens::Scripts => Some(36),
+            Screens::Shop => Some(37),
+            Screens::Skill => Some(38),
+            Screens::Terrain => Some(39),
+            Screens::Text => Some(40),
+            Screens::Title => Some(41),
+            Screens::Tutorial => Some(42),
+            Screens::DragonRide => Some(43),
         }
     }
 
     pub fn next_tab(&self) -> Option<Self> {
         self.get_tab_index()
-            .and_then(|index| Self::from_tab_index(if index + 1 < 43 { index + 1 } else { 0 }))
+            .and_then(|index| Self::from_tab_index(if index + 1 < 44 { index + 1 } else { 0 }))
     }
 
     pub fn prev_tab(&self) -> Option<Self> {
         self.get_tab_index()
-            .and_then(|index| Self::from_tab_index(if index > 0 { index - 1 } else { 42 }))
+            .and_then(|index| Self::from_tab_index(if index > 0 { index - 1 } else { 43 }))
     }
 }
 
@@ -228,8 +254,12 @@ pub struct MainState {
     arena_editor: ArenaEditor,
     asset_table_editor: AssetTableEditor,
     calculator_editor: CalculatorEditor,
+    challenge_editor: ChallengeEditor,
     chart_editor: ChartEditor,
     chapter_editor: ChapterEditor,
+    compare_screen: CompareScreen,
+    randomizer_screen: RandomizerScreen,
+    recipe_screen: RecipeScreen,
     cook_editor: CookEditor,
     dragon_ride_editor: DragonRideEditor,
     effect_editor: EffectEditor,
@@ -260,6 +290,12 @@ pub struct MainState {
     script_manager: ScriptManager,
     shop_editor: ShopEditor,
     skill_editor: SkillEditor,
+    spawn_tuning_screen: SpawnTuningScreen,
+    stat_planner_screen: StatPlannerScreen,
+    chapter_graph_screen: ChapterGraphScreen,
+    item_report_screen: ItemReportScreen,
+    level_curve_screen: LevelCurveScreen,
+    audio_check_screen: AudioCheckScreen,
     terrain_editor: TerrainDataEditor,
     text_data_editor: TextDataEditor,
     title_editor: TitleEditor,
@@ -284,6 +320,7 @@ impl MainState {
             asset_table: SheetHandle::new(astra.read().get_asset_table_book(), Default::default()),
             belong: SheetHandle::new(astra.read().get_achieve_book(), Default::default()),
             calculator: SheetHandle::new(astra.read().get_calculator_book(), Default::default()),
+            challenge: SheetHandle::new(astra.read().get_chapter_book(), Default::default()),
             chapter: SheetHandle::new(astra.read().get_chapter_book(), Default::default()),
             chart: SheetHandle::new(astra.read().get_chart_book(), Default::default()),
             chart_god: SheetHandle::new(astra.read().get_chart_book(), Default::default()),
@@ -576,6 +613,7 @@ impl MainState {
                 astra.read().get_reliance_book(),
                 Default::default(),
             ),
+            reward_data: SheetHandle::new(astra.read().get_item_book(), Default::default()),
             ring_data: SheetHandle::new(astra.read().get_ring_book(), Default::default()),
             ring_polish_voice: SheetHandle::new(
                 astra.read().get_ring_cleaning_voice_book(),
@@ -597,6 +635,7 @@ impl MainState {
             tips: SheetHandle::new(astra.read().get_tutorial_book(), Default::default()),
             tutorials: SheetHandle::new(astra.read().get_tutorial_book(), Default::default()),
             terrain: SheetHandle::new(astra.read().get_terrain_book(), Default::default()),
+            terrain_cost: SheetHandle::new(astra.read().get_terrain_book(), Default::default()),
             vibration_data: SheetHandle::new(astra.read().get_vibration_book(), Default::default()),
 
             spawns: Default::default(),
@@ -613,6 +652,7 @@ impl MainState {
             arena_editor: ArenaEditor::new(&state),
             asset_table_editor: AssetTableEditor::new(&state),
             calculator_editor: CalculatorEditor::new(&state),
+            challenge_editor: ChallengeEditor::new(&state),
             chart_editor: ChartEditor::new(&state),
             chapter_editor: ChapterEditor::new(&state),
             cook_editor: CookEditor::new(&state),
@@ -648,6 +688,15 @@ impl MainState {
             title_editor: TitleEditor::new(&state),
             tutorial_editor: TutorialEditor::new(&state),
             editor_state: state,
+            compare_screen: CompareScreen::new(astra.clone()),
+            spawn_tuning_screen: SpawnTuningScreen::new(astra.clone()),
+            stat_planner_screen: StatPlannerScreen::default(),
+            chapter_graph_screen: ChapterGraphScreen::default(),
+            item_report_screen: ItemReportScreen::default(),
+            level_curve_screen: LevelCurveScreen::default(),
+            audio_check_screen: AudioCheckScreen::default(),
+            randomizer_screen: RandomizerScreen::default(),
+            recipe_screen: RecipeScreen::default(),
             save_screen: SaveScreen::new(astra.clone()),
             script_manager: ScriptManager::new(astra),
             active_screen: Screens::Person,
@@ -690,6 +739,42 @@ pub fn main_window(
                     state.active_screen = Screens::Save;
                     ui.close_menu();
                 }
+                if ui.button("Compare With Project").clicked() {
+                    state.active_screen = Screens::Compare;
+                    ui.close_menu();
+                }
+                if ui.button("Apply Recipes").clicked() {
+                    state.active_screen = Screens::Recipes;
+                    ui.close_menu();
+                }
+                if ui.button("Randomizer").clicked() {
+                    state.active_screen = Screens::Randomizer;
+                    ui.close_menu();
+                }
+                if ui.button("Batch Spawn Tuning").clicked() {
+                    state.active_screen = Screens::SpawnTuning;
+                    ui.close_menu();
+                }
+                if ui.button("Stat Planner").clicked() {
+                    state.active_screen = Screens::StatPlanner;
+                    ui.close_menu();
+                }
+                if ui.button("Chapter Progression").clicked() {
+                    state.active_screen = Screens::ChapterGraph;
+                    ui.close_menu();
+                }
+                if ui.button("Item Report").clicked() {
+                    state.active_screen = Screens::ItemReport;
+                    ui.close_menu();
+                }
+                if ui.button("Level Curve").clicked() {
+                    state.active_screen = Screens::LevelCurve;
+                    ui.close_menu();
+                }
+                if ui.button("Audio Check").clicked() {
+                    state.active_screen = Screens::AudioCheck;
+                    ui.close_menu();
+                }
                 ui.separator();
                 ui.menu_button("Open", |ui| {
                     let astra = state.editor_state.astra.read();
@@ -791,6 +876,7 @@ pub fn main_window(
             ui.selectable_value(&mut state.active_screen, Screens::Arena, "Arena");
             ui.selectable_value(&mut state.active_screen, Screens::AssetTable, "Asset Table");
             ui.selectable_value(&mut state.active_screen, Screens::Calculator, "Calculator");
+            ui.selectable_value(&mut state.active_screen, Screens::Challenge, "Challenges");
             ui.selectable_value(&mut state.active_screen, Screens::Chapter, "Chapters");
             ui.selectable_value(&mut state.active_screen, Screens::Person, "Characters");
             ui.selectable_value(&mut state.active_screen, Screens::Chart, "Chart");
@@ -885,10 +971,12 @@ pub fn main_window(
         Screens::Arena => state.arena_editor.show(ctx, &state.editor_state),
         Screens::AssetTable => state.asset_table_editor.show(ctx, &state.editor_state),
         Screens::Calculator => state.calculator_editor.show(ctx),
+        Screens::Challenge => state.challenge_editor.show(ctx, &state.editor_state),
         Screens::Chapter => state
             .chapter_editor
             .show(ctx, &mut state.editor_state, config),
         Screens::Chart => state.chart_editor.show(ctx, &state.editor_state),
+        Screens::Compare => state.compare_screen.ui(ctx, config, &mut state.toasts),
         Screens::Cook => state.cook_editor.show(ctx, &state.editor_state),
         Screens::DragonRide => state.dragon_ride_editor.show(ctx, &state.editor_state),
         Screens::Effect => state.effect_editor.show(ctx, &state.editor_state),
@@ -912,6 +1000,14 @@ pub fn main_window(
         Screens::Person => state.person_editor.show(ctx, &mut state.editor_state),
         Screens::Photograph => state.photograph_editor.show(ctx, &state.editor_state),
         Screens::ProfileCard => state.profile_card_editor.show(ctx, &state.editor_state),
+        Screens::Randomizer => {
+            state
+                .randomizer_screen
+                .ui(ctx, config, next_state, &mut state.toasts)
+        }
+        Screens::Recipes => state
+            .recipe_screen
+            .ui(ctx, config, next_state, &mut state.toasts),
         Screens::Relay => state.relay_editor.show(ctx, &state.editor_state),
         Screens::Reliance => state.reliance_editor.show(ctx),
         Screens::Ring => state.ring_editor.show(ctx, &state.editor_state),
@@ -921,6 +1017,16 @@ pub fn main_window(
         Screens::Scripts => state.script_manager.ui(ctx, config, &mut state.toasts),
         Screens::Shop => state.shop_editor.show(ctx, &mut state.editor_state),
         Screens::Skill => state.skill_editor.show(ctx, &mut state.editor_state),
+        Screens::SpawnTuning => state.spawn_tuning_screen.ui(ctx, &mut state.toasts),
+        Screens::StatPlanner => state.stat_planner_screen.ui(ctx, &state.editor_state),
+        Screens::ChapterGraph => state.chapter_graph_screen.ui(ctx, &state.editor_state),
+        Screens::ItemReport => {
+            state
+                .item_report_screen
+                .ui(ctx, &state.editor_state, &mut state.toasts)
+        }
+        Screens::LevelCurve => state.level_curve_screen.ui(ctx, &state.editor_state),
+        Screens::AudioCheck => state.audio_check_screen.ui(ctx, &state.editor_state),
         Screens::Terrain => state.terrain_editor.show(ctx, &mut state.editor_state),
         Screens::Text => state
             .text_data_editor
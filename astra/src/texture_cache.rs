@@ -26,76 +26,72 @@ impl TextureCache {
         Self {
             system_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("system").unwrap_or_default(),
+                &astra.consume_sprite_atlas("system").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             godring_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("godring").unwrap_or_default(),
+                &astra.consume_sprite_atlas("godring").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             facethumb_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("facethumb").unwrap_or_default(),
+                &astra.consume_sprite_atlas("facethumb").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             item_icon_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("item").unwrap_or_default(),
+                &astra.consume_sprite_atlas("item").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             skill_icon_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("skill").unwrap_or_default(),
+                &astra.consume_sprite_atlas("skill").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
-            unit_icon_cache: Self::build_cache(
-                &ctx,
-                astra.consume_sprite_atlas("units").unwrap_or_default(),
-                TextureOptions::NEAREST,
-            ),
+            unit_icon_cache: Self::build_cache(&ctx, astra.unit_sprites(), TextureOptions::NEAREST),
             versus_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("versus").unwrap_or_default(),
+                &astra.consume_sprite_atlas("versus").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             hub_icons_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("hub_icons").unwrap_or_default(),
+                &astra.consume_sprite_atlas("hub_icons").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             hub_cafe_icons_cache: Self::build_cache(
                 &ctx,
-                astra
+                &astra
                     .consume_sprite_atlas("hub_cafe_icons")
                     .unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             fish_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("fishing").unwrap_or_default(),
+                &astra.consume_sprite_atlas("fishing").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             achievement_cache: Self::build_cache(
                 &ctx,
-                astra
+                &astra
                     .consume_sprite_atlas("achievement")
                     .unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             notebook_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("notebook").unwrap_or_default(),
+                &astra.consume_sprite_atlas("notebook").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             relay_stamp_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("relaystamp").unwrap_or_default(),
+                &astra.consume_sprite_atlas("relaystamp").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
             map_status_cache: Self::build_cache(
                 &ctx,
-                astra.consume_sprite_atlas("mapstatus").unwrap_or_default(),
+                &astra.consume_sprite_atlas("mapstatus").unwrap_or_default(),
                 TextureOptions::LINEAR,
             ),
         }
@@ -103,11 +99,11 @@ impl TextureCache {
 
     fn build_cache(
         ctx: &egui::Context,
-        backend_sprites: HashMap<String, DynamicImage>,
+        backend_sprites: &HashMap<String, DynamicImage>,
         texture_options: TextureOptions,
     ) -> HashMap<String, TextureHandle> {
         backend_sprites
-            .into_iter()
+            .iter()
             .map(|(key, image)| {
                 (
                     key.clone(),
@@ -1,15 +1,19 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use astra_core::{Difficulty, TERRAIN_STRIDE};
 use astra_formats::TerrainData;
 use astra_types::Spawn;
-use egui::{Button, Color32, Grid, ScrollArea, TextureHandle, Ui, Vec2};
+use egui::{Button, Color32, Grid, PointerButton, ScrollArea, Sense, TextureHandle, Ui, Vec2};
 use indexmap::IndexMap;
 use itertools::Itertools;
 
-use crate::editors::Difficulty;
 use crate::util::get_tile_color;
-use crate::{AppConfig, CoordinateKind, DecorationKind, EditorState, ViewItem};
+use crate::{spawn_tile, AppConfig, CoordinateKind, DecorationKind, EditorState, Tile, ViewItem};
+
+const MOVEMENT_RANGE_COLOR: Color32 = Color32::from_rgb(64, 128, 255);
+const DANGER_ZONE_COLOR: Color32 = Color32::from_rgb(255, 64, 64);
+const ARRIVAL_COLOR: Color32 = Color32::from_rgb(255, 200, 32);
 
 struct SpawnData<'a> {
     group: &'a str,
@@ -17,19 +21,22 @@ struct SpawnData<'a> {
     spawn: &'a mut Spawn,
 }
 
+/// Blend an overlay color halfway into a tile's fill.
+fn tint(fill: Color32, overlay: Color32) -> Color32 {
+    let blend = |a: u8, b: u8| ((a as u16 + b as u16) / 2) as u8;
+    Color32::from_rgb(
+        blend(fill.r(), overlay.r()),
+        blend(fill.g(), overlay.g()),
+        blend(fill.b(), overlay.b()),
+    )
+}
+
 pub struct DisposGridResult {
     pub changed: bool,
     pub hovered_tile: Option<String>,
     pub hovered_spawn: Option<String>,
 }
 
-fn get_position(spawn: &Spawn, coordinate_kind: CoordinateKind) -> (usize, usize) {
-    match coordinate_kind {
-        CoordinateKind::Dispos => (spawn.dispos_x as usize, spawn.dispos_y as usize),
-        CoordinateKind::Appear => (spawn.appear_x as usize, spawn.appear_y as usize),
-    }
-}
-
 fn set_position(spawn: &mut Spawn, coordinate_kind: CoordinateKind, row: usize, col: usize) {
     match coordinate_kind {
         CoordinateKind::Dispos => {
@@ -60,14 +67,9 @@ impl<'a> SpawnDataMap<'a> {
                     group
                         .iter_mut()
                         .enumerate()
-                        .filter(|(_, spawn)| match difficulty {
-                            Difficulty::All => true,
-                            Difficulty::Normal => spawn.flag & 1 != 0,
-                            Difficulty::Hard => spawn.flag & 2 != 0,
-                            Difficulty::Lunatic => spawn.flag & 4 != 0,
-                        })
+                        .filter(|(_, spawn)| difficulty.includes(spawn))
                         .map(|(index, spawn)| {
-                            let position = get_position(spawn, coordinate_kind);
+                            let position = spawn_tile(spawn, coordinate_kind);
                             (
                                 position,
                                 SpawnData {
@@ -100,28 +102,88 @@ impl<'a> SpawnDataMap<'a> {
             .get(&(col, row))
             .and_then(|group| group.last())
     }
+
+    /// Every spawn inside the rectangle between two `(col, row)` corners, including stacked ones.
+    pub fn spawns_in_rect(&self, a: Tile, b: Tile) -> Vec<(String, usize)> {
+        let cols = a.0.min(b.0)..=a.0.max(b.0);
+        let rows = a.1.min(b.1)..=a.1.max(b.1);
+        self.spawns_by_position
+            .iter()
+            .filter(|(position, _)| cols.contains(&position.0) && rows.contains(&position.1))
+            .flat_map(|(_, spawns)| spawns)
+            .map(|spawn_data| (spawn_data.group.to_string(), spawn_data.index))
+            .sorted()
+            .collect()
+    }
+}
+
+/// The selected spawn and every spawn selected along with it.
+pub struct DisposSelection<'a> {
+    pub primary: &'a mut Option<(String, usize)>,
+    pub spawns: &'a mut Vec<(String, usize)>,
+}
+
+/// Which spawns to show and the tiles to tint on top of the terrain.
+pub struct DisposGridOptions<'a> {
+    pub coordinate_kind: CoordinateKind,
+    pub difficulty: Difficulty,
+    pub movement_range: &'a HashSet<Tile>,
+    pub danger_zone: &'a HashSet<Tile>,
+    pub arrivals: &'a HashSet<Tile>,
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn dispos_grid(
     ui: &mut Ui,
     terrain: &TerrainData,
     state: &EditorState,
     dispos: &mut IndexMap<String, Vec<Spawn>>,
-    selected_spawn: &mut Option<(String, usize)>,
-    coordinate_kind: CoordinateKind,
-    difficulty: Difficulty,
+    selection: DisposSelection,
+    options: DisposGridOptions,
     config: &AppConfig,
 ) -> DisposGridResult {
+    let DisposSelection {
+        primary: selected_spawn,
+        spawns: selected_spawns,
+    } = selection;
+    let DisposGridOptions {
+        coordinate_kind,
+        difficulty,
+        movement_range,
+        danger_zone,
+        arrivals,
+    } = options;
+    // Keep the multi-selection in line with selections made elsewhere, ex. the group list.
+    match selected_spawn.as_ref() {
+        Some(primary) if !selected_spawns.contains(primary) => {
+            *selected_spawns = vec![primary.clone()];
+        }
+        None => selected_spawns.clear(),
+        _ => {}
+    }
+    selected_spawns.retain(|(group, index)| {
+        dispos
+            .get(group)
+            .is_some_and(|spawns| *index < spawns.len())
+    });
     let selected_spawn_position = selected_spawn
         .as_ref()
         .and_then(|(group, index)| dispos.get(group).and_then(|group| group.get(*index)))
-        .map(|spawn| get_position(spawn, coordinate_kind));
+        .map(|spawn| spawn_tile(spawn, coordinate_kind));
+    let selected_positions: HashSet<Tile> = selected_spawns
+        .iter()
+        .map(|(group, index)| spawn_tile(&dispos[group][*index], coordinate_kind))
+        .collect();
     let spawn_data = SpawnDataMap::new(dispos, coordinate_kind, difficulty);
+    let shift = ui.input(|input| input.modifiers.shift);
     let mut changed = false;
     let mut move_pos = None;
     let mut hovered_tile = None;
     let mut hovered_spawn = None;
+    let mut clicked_spawn = None;
+    let mut formation_drag_origin = None;
+    let mut band_origin = None;
+    let mut band_preview = None;
+    let mut pointer_tile = None;
     ScrollArea::both()
         .id_source("spawn_grid_scroll")
         .show(ui, |ui| {
@@ -134,14 +196,32 @@ pub fn dispos_grid(
                             let sprite = spawn_data.get_sprite(state, row, col);
                             let (tile_name, fill) = terrain
                                 .terrains
-                                .get(row * 32 + col)
+                                .get(row * TERRAIN_STRIDE + col)
                                 .and_then(|tid| data.get(tid.as_str()))
                                 .map(|tile| (tile.text(state), get_tile_color(tile, config)))
                                 .unwrap_or_else(|| (Cow::Borrowed("???"), Color32::from_gray(0)));
+                            let fill = if danger_zone.contains(&(col, row)) {
+                                tint(fill, DANGER_ZONE_COLOR)
+                            } else {
+                                fill
+                            };
+                            let fill = if movement_range.contains(&(col, row)) {
+                                tint(fill, MOVEMENT_RANGE_COLOR)
+                            } else {
+                                fill
+                            };
+                            let fill = if arrivals.contains(&(col, row)) {
+                                tint(fill, ARRIVAL_COLOR)
+                            } else {
+                                fill
+                            };
 
                             // Put these in a container to please egui's grid.
-                            let mut button = Button::new("").rounding(0.).fill(fill);
-                            if Some((col, row)) == selected_spawn_position {
+                            let mut button = Button::new("")
+                                .sense(Sense::click_and_drag())
+                                .rounding(0.)
+                                .fill(fill);
+                            if selected_positions.contains(&(col, row)) {
                                 button = button.stroke(ui.visuals().widgets.active.fg_stroke)
                             }
                             ui.vertical(|ui| {
@@ -158,13 +238,23 @@ pub fn dispos_grid(
                                             Some(spawn_data.spawn.text(state).into_owned());
                                     }
                                 }
+                                if response.contains_pointer() {
+                                    pointer_tile = Some(((col, row), response.rect));
+                                }
                                 if response.clicked() {
                                     move_pos = Some((row, col));
                                 } else if response.secondary_clicked() {
-                                    if let Some(spawn_data) = spawn_data.get_spawn(row, col) {
-                                        *selected_spawn =
-                                            Some((spawn_data.group.to_string(), spawn_data.index));
-                                    }
+                                    clicked_spawn = spawn_data
+                                        .get_spawn(row, col)
+                                        .map(|data| (data.group.to_string(), data.index));
+                                }
+                                if response.dragged_by(PointerButton::Secondary) {
+                                    band_preview = Some(response.rect);
+                                }
+                                if response.drag_released_by(PointerButton::Primary) {
+                                    formation_drag_origin = Some((col, row));
+                                } else if response.drag_released_by(PointerButton::Secondary) {
+                                    band_origin = Some((col, row));
                                 }
                             });
                         }
@@ -172,13 +262,75 @@ pub fn dispos_grid(
                     }
                 });
             });
+            if let (Some(origin), Some((_, end))) = (band_preview, pointer_tile) {
+                ui.painter()
+                    .rect_stroke(origin.union(end), 0., ui.visuals().selection.stroke);
+            }
         });
-    if let (Some((row, col)), Some((group, index))) = (move_pos, selected_spawn) {
-        let spawn = dispos
-            .get_mut(group)
-            .and_then(|group| group.get_mut(*index));
-        if let Some(spawn) = spawn {
-            set_position(spawn, coordinate_kind, row, col);
+
+    // Right click picks a spawn, Shift adds it to (or removes it from) the selection.
+    if let Some(spawn) = clicked_spawn {
+        if !shift {
+            *selected_spawns = vec![spawn.clone()];
+            *selected_spawn = Some(spawn);
+        } else if let Some(position) = selected_spawns.iter().position(|s| *s == spawn) {
+            selected_spawns.remove(position);
+            if selected_spawn.as_ref() == Some(&spawn) {
+                *selected_spawn = selected_spawns.last().cloned();
+            }
+        } else {
+            selected_spawns.push(spawn.clone());
+            *selected_spawn = Some(spawn);
+        }
+    }
+    // Right dragging selects every spawn in the rectangle.
+    if let (Some(origin), Some((end, _))) = (band_origin, pointer_tile) {
+        let spawns = spawn_data.spawns_in_rect(origin, end);
+        if shift {
+            for spawn in spawns {
+                if !selected_spawns.contains(&spawn) {
+                    selected_spawns.push(spawn);
+                }
+            }
+        } else {
+            *selected_spawns = spawns;
+        }
+        if !selected_spawn
+            .as_ref()
+            .is_some_and(|spawn| selected_spawns.contains(spawn))
+        {
+            *selected_spawn = selected_spawns.first().cloned();
+        }
+    }
+
+    // Clicking moves the selection so the primary spawn lands on the tile, dragging moves it
+    // by however far the pointer traveled. Selected spawns keep their formation either way.
+    let offset = match (move_pos, formation_drag_origin, pointer_tile) {
+        (Some((row, col)), _, _) => selected_spawn_position.map(|from| (from, (col, row))),
+        (_, Some(origin), Some((end, _))) if selected_positions.contains(&origin) => {
+            Some((origin, end))
+        }
+        _ => None,
+    }
+    .map(|(from, to)| (to.0 as i32 - from.0 as i32, to.1 as i32 - from.1 as i32));
+    if let Some((dx, dy)) = offset.filter(|offset| *offset != (0, 0)) {
+        let (width, height) = (terrain.width, terrain.height);
+        let moved: Vec<((String, usize), (i32, i32))> = selected_spawns
+            .iter()
+            .map(|(group, index)| {
+                let (col, row) = spawn_tile(&dispos[group][*index], coordinate_kind);
+                ((group.clone(), *index), (col as i32 + dx, row as i32 + dy))
+            })
+            .collect();
+        // Don't break up the formation by clamping some of it to the edge.
+        let fits = moved
+            .iter()
+            .all(|(_, (col, row))| (0..width).contains(col) && (0..height).contains(row));
+        if fits {
+            for ((group, index), (col, row)) in moved {
+                let spawn = &mut dispos[&group][index];
+                set_position(spawn, coordinate_kind, row as usize, col as usize);
+            }
             changed = true;
         }
     }
@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+
 use egui::{AboveOrBelow, Grid, Image, Response, ScrollArea, Sense, Ui, Widget};
+use indexmap::IndexMap;
 
 use crate::{
     queue_transition, DecorationKind, KeyedListModel, KeyedViewItem, ListModel, Transition,
@@ -29,6 +32,59 @@ where
     move |ui: &mut Ui| ModelDropDown::default().show_indexed(ui, model, dependencies, selected)
 }
 
+/// A name offered by [string_drop_down]. The empty name stands for no value.
+#[derive(Debug, Clone)]
+pub struct NameOption(pub String);
+
+impl ViewItem for NameOption {
+    type Dependencies = ();
+
+    fn text(&self, _: &Self::Dependencies) -> Cow<'_, str> {
+        if self.0.is_empty() {
+            Cow::Borrowed("None")
+        } else {
+            Cow::Borrowed(&self.0)
+        }
+    }
+}
+
+impl KeyedViewItem for NameOption {
+    fn key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn set_key(&mut self, key: String) {
+        self.0 = key;
+    }
+}
+
+/// Build the options for [string_drop_down], starting with "None".
+pub fn name_options<'a>(
+    names: impl IntoIterator<Item = &'a String>,
+) -> IndexMap<String, NameOption> {
+    std::iter::once(String::new())
+        .chain(names.into_iter().cloned())
+        .map(|name| (name.clone(), NameOption(name)))
+        .collect()
+}
+
+/// Pick from a set of names that aren't backed by a model, ex. sound events.
+/// A name outside the set is kept and flagged on hover.
+pub fn string_drop_down<'a>(
+    options: &'a IndexMap<String, NameOption>,
+    selected: &'a mut String,
+) -> impl Widget + 'a {
+    move |ui: &mut Ui| {
+        let known = options.contains_key(selected.as_str());
+        let response = ui.add(model_drop_down(options, &(), selected));
+        if known {
+            response
+        } else {
+            response.on_hover_text(format!("Unknown name '{}'", selected))
+        }
+    }
+}
+
 fn drop_down_item_ui<I, D>(
     ui: &mut Ui,
     dependencies: &D,
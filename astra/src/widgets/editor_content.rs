@@ -1,12 +1,14 @@
 use std::marker::PhantomData;
 
+use astra_formats::{FromSheetDataParam, ToSheetDataParam};
 use egui::panel::Side;
 use egui::{Button, CentralPanel, Id, SidePanel, TextEdit, Ui};
 use egui_modal::Modal;
 
 use crate::model::{SheetHandle, SheetRetriever};
 use crate::{
-    blank_slate, list_view, AddModalRenderer, FilterProxyBuilder, ListModel, ViewItem,
+    blank_slate, clipboard_message_modal, consume_clipboard_request, list_view, rows_from_xml,
+    rows_to_xml, AddModalRenderer, ClipboardRequest, FilterProxyBuilder, ListModel, ViewItem,
     ADD_SHORTCUT, COPY_TO_SHORTCUT, DELETE_SHORTCUT, DUPLICATE_SHORTCUT, INSERT_SHORTCUT,
     MOVE_DOWN_SHORTCUT, MOVE_UP_SHORTCUT,
 };
@@ -22,12 +24,13 @@ pub struct ListEditorContent<M, I, D> {
     prev_model_revision: Option<usize>,
     add_command: Option<AddModalCommand>,
     copy_index: Option<usize>,
+    clipboard_message: Option<String>,
 }
 
 impl<M, I, D> ListEditorContent<M, I, D>
 where
     M: ListModel<I>,
-    I: ViewItem<Dependencies = D> + Default + Clone,
+    I: ViewItem<Dependencies = D> + Default + Clone + FromSheetDataParam + ToSheetDataParam,
 {
     pub fn new(id_source: &'static str) -> Self {
         Self {
@@ -37,6 +40,7 @@ where
             prev_model_revision: None,
             add_command: None,
             copy_index: None,
+            clipboard_message: None,
             filter_proxy: FilterProxyBuilder::new(),
             phantom: Default::default(),
         }
@@ -105,6 +109,12 @@ where
             });
         }
 
+        clipboard_message_modal(
+            ctx,
+            &format!("{}_clipboard_modal", self.id_source),
+            &mut self.clipboard_message,
+        );
+
         SidePanel::new(Side::Left, Id::new(self.id_source).with("side_panel"))
             .default_width(300.)
             .show(ctx, |ui| {
@@ -139,6 +149,22 @@ where
                             copy_modal.open();
                             ui.close_menu();
                         }
+                        if ui
+                            .add_enabled(has_selection, Button::new("📋 Copy as XML"))
+                            .on_hover_text("Paste into any project with Ctrl+V.")
+                            .clicked()
+                        {
+                            self.copy_item_to_clipboard(ui, model);
+                            ui.close_menu();
+                        }
+                        if ui
+                            .button("📋 Copy Filtered as XML")
+                            .on_hover_text("Copy every row matching the filter.")
+                            .clicked()
+                        {
+                            self.copy_filtered_to_clipboard(ui, model);
+                            ui.close_menu();
+                        }
                         ui.separator();
                         if ui
                             .add_enabled(has_selection, Button::new("⏶ Move Up"))
@@ -221,6 +247,11 @@ where
                     if ui.input_mut(|input| input.consume_shortcut(&DELETE_SHORTCUT)) {
                         self.delete_item(model);
                     }
+                    match consume_clipboard_request(ui) {
+                        Some(ClipboardRequest::Copy) => self.copy_item_to_clipboard(ui, model),
+                        Some(ClipboardRequest::Paste(text)) => self.paste_items(model, &text),
+                        None => {}
+                    }
                 }
             });
     }
@@ -278,6 +309,60 @@ where
         }
     }
 
+    pub fn copy_item_to_clipboard<R, B>(&self, ui: &Ui, model: &SheetHandle<R, B, M>)
+    where
+        R: SheetRetriever<B, M>,
+    {
+        if let Some(selection) = self.selection {
+            let text = model.read(|data| data.item(selection).map(|item| rows_to_xml([item])));
+            if let Some(Ok(text)) = text {
+                ui.output_mut(|out| out.copied_text = text);
+            }
+        }
+    }
+
+    /// Copy every row that matches the current filter, in list order.
+    pub fn copy_filtered_to_clipboard<R, B>(&self, ui: &Ui, model: &SheetHandle<R, B, M>)
+    where
+        R: SheetRetriever<B, M>,
+    {
+        let text = model.read(|data| {
+            rows_to_xml(
+                self.filter_proxy
+                    .source_indices()
+                    .iter()
+                    .filter_map(|index| data.item(*index)),
+            )
+        });
+        if let Ok(text) = text {
+            ui.output_mut(|out| out.copied_text = text);
+        }
+    }
+
+    pub fn paste_items<R, B>(&mut self, model: &SheetHandle<R, B, M>, text: &str)
+    where
+        R: SheetRetriever<B, M>,
+    {
+        let items = match rows_from_xml::<I>(text) {
+            Ok(items) => items,
+            Err(error) => {
+                self.clipboard_message = Some(format!("Nothing was pasted: {}", error));
+                return;
+            }
+        };
+        let index = self
+            .selection
+            .map(|selection| selection + 1)
+            .unwrap_or(usize::MAX);
+        model.write(|data| {
+            let result = data.paste(index, items);
+            self.selection = result.last.or(self.selection);
+            self.clipboard_message = result.message();
+            self.filter_proxy.request_refresh();
+            true
+        });
+    }
+
     pub fn move_item_up<R, B>(&mut self, model: &SheetHandle<R, B, M>)
     where
         R: SheetRetriever<B, M>,
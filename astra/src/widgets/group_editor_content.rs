@@ -1,3 +1,4 @@
+use astra_formats::{FromSheetDataParam, ToSheetDataParam};
 use egui::collapsing_header::CollapsingState;
 use egui::{Button, CentralPanel, Id, ScrollArea, SidePanel, TextEdit, Ui};
 use egui_modal::Modal;
@@ -5,8 +6,10 @@ use indexmap::IndexMap;
 
 use crate::model::{DecorationKind, GroupViewItem};
 use crate::{
-    blank_slate, SheetHandle, SheetRetriever, ViewItem, ADD_SHORTCUT, COPY_TO_SHORTCUT,
-    DELETE_SHORTCUT, DUPLICATE_SHORTCUT, INSERT_SHORTCUT, MOVE_DOWN_SHORTCUT, MOVE_UP_SHORTCUT,
+    blank_slate, clipboard_message_modal, consume_clipboard_request, rows_from_xml, rows_to_xml,
+    ClipboardRequest, ListModel, SheetHandle, SheetRetriever, ViewItem, ADD_SHORTCUT,
+    COPY_TO_SHORTCUT, DELETE_SHORTCUT, DUPLICATE_SHORTCUT, INSERT_SHORTCUT, MOVE_DOWN_SHORTCUT,
+    MOVE_UP_SHORTCUT,
 };
 
 use super::{group_add_modal_content, group_copy_modal_content, optional_image, GroupModalCommand};
@@ -140,6 +143,7 @@ pub struct GroupEditorContent {
     search: String,
     modal_command: Option<GroupModalCommand>,
     copy_source: Option<(String, usize)>,
+    clipboard_message: Option<String>,
     id_source: &'static str,
 }
 
@@ -150,6 +154,7 @@ impl GroupEditorContent {
             selection: None,
             modal_command: None,
             copy_source: None,
+            clipboard_message: None,
             search: String::new(),
         }
     }
@@ -166,7 +171,7 @@ impl GroupEditorContent {
     ) where
         Group<I>: GroupViewItem<Dependencies = D>,
         R: SheetRetriever<B, Group<I>>,
-        I: ViewItem<Dependencies = D> + Default + Clone,
+        I: ViewItem<Dependencies = D> + Default + Clone + FromSheetDataParam + ToSheetDataParam,
     {
         let modal = Modal::new(ctx, self.id_source);
         if let Some(modal_command) = self.modal_command {
@@ -194,6 +199,12 @@ impl GroupEditorContent {
             });
         }
 
+        clipboard_message_modal(
+            ctx,
+            &format!("{}_clipboard_modal", self.id_source),
+            &mut self.clipboard_message,
+        );
+
         SidePanel::left(Id::new(self.id_source).with("left_panel"))
             .default_width(300.)
             .show(ctx, |ui| {
@@ -239,8 +250,8 @@ impl GroupEditorContent {
                                         }
                                         ui.label(name);
                                         if group_command.is_none() {
-                                            group_command =
-                                                self.group_command_menu(&modal, ui, group, i);
+                                            group_command = self
+                                                .group_command_menu(&modal, ui, group, i, items);
                                         }
                                     })
                                     .body(|ui| {
@@ -266,6 +277,9 @@ impl GroupEditorContent {
                             self.process_group_entry_hot_keys(&copy_modal, ui, group, index);
                     }
                 }
+                if no_widgets_focused {
+                    self.process_clipboard_request(ui, model);
+                }
 
                 model.write(|data| {
                     let mut changed = false;
@@ -305,7 +319,7 @@ impl GroupEditorContent {
         items: &[I],
     ) -> Option<GroupEntryCommand>
     where
-        I: ViewItem + Default + Clone,
+        I: ViewItem + Default + Clone + ToSheetDataParam,
     {
         let mut command = None;
         for (i, entry) in items.iter().enumerate() {
@@ -325,20 +339,24 @@ impl GroupEditorContent {
                     self.selection = Some((group.to_owned(), i));
                 }
                 if command.is_none() {
-                    command = self.group_entry_command_menu(ui, copy_modal, group, i);
+                    command = self.group_entry_command_menu(ui, copy_modal, group, i, entry);
                 }
             });
         }
         command
     }
 
-    fn group_command_menu(
+    fn group_command_menu<I>(
         &mut self,
         modal: &Modal,
         ui: &mut Ui,
         group: &str,
         index: usize,
-    ) -> Option<GroupCommand> {
+        items: &[I],
+    ) -> Option<GroupCommand>
+    where
+        I: ToSheetDataParam,
+    {
         let mut command_kind = None;
         ui.menu_button("…", |ui| {
             if ui.button("✏ Edit Name").clicked() {
@@ -361,6 +379,16 @@ impl GroupEditorContent {
                 modal.open();
                 ui.close_menu();
             }
+            if ui
+                .button("📋 Copy Entries as XML")
+                .on_hover_text("Paste into any project with Ctrl+V.")
+                .clicked()
+            {
+                if let Ok(text) = rows_to_xml(items) {
+                    ui.output_mut(|out| out.copied_text = text);
+                }
+                ui.close_menu();
+            }
             ui.separator();
             if ui.button("⏶ Move Up").clicked() {
                 command_kind = Some(GroupCommandKind::MoveUp);
@@ -383,13 +411,17 @@ impl GroupEditorContent {
         })
     }
 
-    fn group_entry_command_menu(
+    fn group_entry_command_menu<I>(
         &mut self,
         ui: &mut Ui,
         copy_modal: &Modal,
         group: &str,
         index: usize,
-    ) -> Option<GroupEntryCommand> {
+        entry: &I,
+    ) -> Option<GroupEntryCommand>
+    where
+        I: ToSheetDataParam,
+    {
         let mut command_kind = None;
         ui.menu_button("…", |ui| {
             if ui.button("➕ Add Entry").clicked() {
@@ -410,6 +442,16 @@ impl GroupEditorContent {
                 copy_modal.open();
                 ui.close_menu();
             }
+            if ui
+                .button("📋 Copy as XML")
+                .on_hover_text("Paste into any project with Ctrl+V.")
+                .clicked()
+            {
+                if let Ok(text) = rows_to_xml([entry]) {
+                    ui.output_mut(|out| out.copied_text = text);
+                }
+                ui.close_menu();
+            }
             ui.separator();
             if ui.button("⏶ Move Up").clicked() {
                 command_kind = Some(GroupEntryCommandKind::MoveUp);
@@ -471,6 +513,49 @@ impl GroupEditorContent {
         command_kind.map(|kind| GroupEntryCommand { group, index, kind })
     }
 
+    fn process_clipboard_request<R, B, I>(
+        &mut self,
+        ui: &mut Ui,
+        model: &SheetHandle<R, B, Group<I>>,
+    ) where
+        R: SheetRetriever<B, Group<I>>,
+        I: ViewItem + FromSheetDataParam + ToSheetDataParam + Default,
+    {
+        let (group, index) = match &self.selection {
+            Some((group, index)) => (group.clone(), *index),
+            None => return,
+        };
+        match consume_clipboard_request(ui) {
+            Some(ClipboardRequest::Copy) => {
+                let text = model.read(|data| {
+                    data.get(&group)
+                        .and_then(|items| items.get(index))
+                        .map(|item| rows_to_xml([item]))
+                });
+                if let Some(Ok(text)) = text {
+                    ui.output_mut(|out| out.copied_text = text);
+                }
+            }
+            Some(ClipboardRequest::Paste(text)) => match rows_from_xml::<I>(&text) {
+                Ok(items) => {
+                    model.write(|data| {
+                        let pasted = data
+                            .get_mut(&group)
+                            .and_then(|group_items| group_items.paste(index + 1, items).last);
+                        if let Some(pasted) = pasted {
+                            self.selection = Some((group, pasted));
+                        }
+                        pasted.is_some()
+                    });
+                }
+                Err(error) => {
+                    self.clipboard_message = Some(format!("Nothing was pasted: {}", error));
+                }
+            },
+            None => {}
+        }
+    }
+
     pub fn content<I>(
         &self,
         ctx: &egui::Context,
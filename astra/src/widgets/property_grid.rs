@@ -113,6 +113,11 @@ impl<'a, D> PropertyGrid<'a, D> {
         self
     }
 
+    /// Adds fields built by a shared builder, like the fields several editors have in common.
+    pub fn fields(self, add_fields: impl FnOnce(Self) -> Self) -> Self {
+        add_fields(self)
+    }
+
     pub fn default_field<F>(
         mut self,
         label: &'a str,
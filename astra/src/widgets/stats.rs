@@ -1,5 +1,10 @@
 use egui::Ui;
 
+/// Stat names in the order used by [standard_stat_column_headers].
+pub const STANDARD_STAT_LABELS: [&str; 11] = [
+    "HP", "Str", "Def", "Skl", "Spd", "Lck", "Mag", "Res", "Con", "Mov", "Sight",
+];
+
 pub fn standard_stat_column_headers(ui: &mut Ui) {
     ui.label("");
     ui.label("HP");
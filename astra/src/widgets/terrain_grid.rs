@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 use std::collections::HashSet;
 
+use astra_core::TERRAIN_STRIDE;
 use astra_formats::{TerrainData, UString};
-use egui::{Button, Color32, Grid, ScrollArea, Sense, Ui, Vec2};
+use egui::{Button, Color32, Grid, ScrollArea, Sense, Stroke, Ui, Vec2};
 
 use crate::model::ViewItem;
 use crate::util::get_tile_color;
@@ -14,6 +15,154 @@ pub enum TerrainBrush {
     Stamp,
     Fill,
     Box,
+    Line,
+    Ellipse,
+    /// Swap every tile with the clicked TID for the selected one.
+    Replace,
+    Select,
+    Paste,
+}
+
+impl TerrainBrush {
+    fn drags(self) -> bool {
+        matches!(
+            self,
+            TerrainBrush::Box | TerrainBrush::Line | TerrainBrush::Ellipse | TerrainBrush::Select
+        )
+    }
+}
+
+/// Repeat every stroke on the opposite side(s) of the map, for mirrored layouts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainSymmetry {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    FourWay,
+    Rotational,
+}
+
+impl TerrainSymmetry {
+    pub fn label(self) -> &'static str {
+        match self {
+            TerrainSymmetry::None => "None",
+            TerrainSymmetry::Horizontal => "Left / Right",
+            TerrainSymmetry::Vertical => "Top / Bottom",
+            TerrainSymmetry::FourWay => "Four Way",
+            TerrainSymmetry::Rotational => "Rotational",
+        }
+    }
+
+    fn mirror(
+        self,
+        (row, col): (usize, usize),
+        width: usize,
+        height: usize,
+    ) -> Vec<(usize, usize)> {
+        let (mirror_row, mirror_col) = (height - 1 - row, width - 1 - col);
+        match self {
+            TerrainSymmetry::None => vec![(row, col)],
+            TerrainSymmetry::Horizontal => vec![(row, col), (row, mirror_col)],
+            TerrainSymmetry::Vertical => vec![(row, col), (mirror_row, col)],
+            TerrainSymmetry::FourWay => vec![
+                (row, col),
+                (row, mirror_col),
+                (mirror_row, col),
+                (mirror_row, mirror_col),
+            ],
+            TerrainSymmetry::Rotational => vec![(row, col), (mirror_row, mirror_col)],
+        }
+    }
+}
+
+/// A rectangle of tiles given by two opposite `(row, col)` corners.
+pub type TerrainSelection = ((usize, usize), (usize, usize));
+
+fn selection_bounds(((row_a, col_a), (row_b, col_b)): TerrainSelection) -> [usize; 4] {
+    [
+        row_a.min(row_b),
+        row_a.max(row_b),
+        col_a.min(col_b),
+        col_a.max(col_b),
+    ]
+}
+
+/// Tiles copied out of a [TerrainSelection], stored top row first like the grid shows them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerrainClipboard {
+    width: usize,
+    height: usize,
+    tids: Vec<String>,
+}
+
+impl TerrainClipboard {
+    pub fn copy(terrain: &TerrainData, selection: TerrainSelection) -> Self {
+        let [min_row, max_row, min_col, max_col] = selection_bounds(selection);
+        let tids = (min_row..=max_row)
+            .rev()
+            .flat_map(|row| (min_col..=max_col).map(move |col| row * TERRAIN_STRIDE + col))
+            .map(|index| {
+                terrain
+                    .terrains
+                    .get(index)
+                    .map(|tid| tid.to_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        Self {
+            width: max_col - min_col + 1,
+            height: max_row - min_row + 1,
+            tids,
+        }
+    }
+
+    fn get(&self, line: usize, col: usize) -> &str {
+        &self.tids[line * self.width + col]
+    }
+
+    /// Rotate a quarter turn clockwise.
+    pub fn rotate(&mut self) {
+        let tids = (0..self.width)
+            .flat_map(|col| (0..self.height).rev().map(move |line| (line, col)))
+            .map(|(line, col)| self.get(line, col).to_string())
+            .collect();
+        *self = Self {
+            width: self.height,
+            height: self.width,
+            tids,
+        };
+    }
+
+    /// Flip left to right.
+    pub fn mirror_horizontal(&mut self) {
+        for line in self.tids.chunks_mut(self.width) {
+            line.reverse();
+        }
+    }
+
+    /// Flip top to bottom.
+    pub fn mirror_vertical(&mut self) {
+        let lines: Vec<Vec<String>> = self
+            .tids
+            .chunks(self.width)
+            .map(|line| line.to_vec())
+            .collect();
+        self.tids = lines.into_iter().rev().flatten().collect();
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+}
+
+/// Brush settings and clipboard shared by every frame of the terrain editor.
+#[derive(Debug, Default)]
+pub struct TerrainTools {
+    pub brush: TerrainBrush,
+    pub symmetry: TerrainSymmetry,
+    pub selection: Option<TerrainSelection>,
+    pub clipboard: Option<TerrainClipboard>,
 }
 
 pub struct TerrainGridResult {
@@ -28,38 +177,47 @@ pub fn terrain_grid(
     selected_tile_index: Option<usize>,
     state: &EditorState,
     config: &AppConfig,
-    brush: TerrainBrush,
+    tools: &mut TerrainTools,
 ) -> TerrainGridResult {
+    let brush = tools.brush;
     let mut changed = vec![];
+    let mut paste_origin = None;
     let mut hovered_tile = None;
     let mut selected_tile = None;
     let mut drag_origin = None;
     let mut drag_end = None;
+    let selection = tools.selection.map(selection_bounds);
     ScrollArea::both()
         .id_source("chapter_terrain_scroll")
         .show(ui, |ui| {
             ui.spacing_mut().button_padding = Vec2::ZERO;
             ui.spacing_mut().item_spacing = Vec2::new(1., 1.);
+            let selection_stroke = Stroke::new(2., ui.visuals().selection.stroke.color);
             Grid::new("chapter_terrain_grid").show(ui, |ui| {
                 state.terrain.read(|data| {
                     for row in (0..(terrain.height as usize)).rev() {
                         for col in 0..(terrain.width as usize) {
-                            let tid = terrain.terrains.get(row * 32 + col);
+                            let tid = terrain.terrains.get(row * TERRAIN_STRIDE + col);
                             let (tile_name, fill) = tid
                                 .and_then(|tid| data.get(tid.as_str()))
                                 .map(|tile| (tile.text(state), get_tile_color(tile, config)))
                                 .unwrap_or_else(|| (Cow::Borrowed("???"), Color32::from_gray(0)));
 
-                            let sense = if let TerrainBrush::Box = brush {
+                            let sense = if brush.drags() {
                                 Sense::click_and_drag()
                             } else {
                                 Sense::click()
                             };
 
-                            let response = ui.add_sized(
-                                [48., 48.],
-                                Button::new("").sense(sense).rounding(0.).fill(fill),
-                            );
+                            let mut button = Button::new("").sense(sense).rounding(0.).fill(fill);
+                            if let Some([min_row, max_row, min_col, max_col]) = selection {
+                                if (min_row..=max_row).contains(&row)
+                                    && (min_col..=max_col).contains(&col)
+                                {
+                                    button = button.stroke(selection_stroke);
+                                }
+                            }
+                            let response = ui.add_sized([48., 48.], button);
                             if response.clicked() {
                                 if let Some(tid) = tid {
                                     match brush {
@@ -71,7 +229,16 @@ pub fn terrain_grid(
                                             row,
                                             col,
                                         ),
-                                        TerrainBrush::Box => {}
+                                        TerrainBrush::Replace => {
+                                            get_matching_tiles(&mut changed, terrain, tid.as_str())
+                                        }
+                                        TerrainBrush::Select => {
+                                            tools.selection = Some(((row, col), (row, col)))
+                                        }
+                                        TerrainBrush::Paste => paste_origin = Some((row, col)),
+                                        TerrainBrush::Box
+                                        | TerrainBrush::Line
+                                        | TerrainBrush::Ellipse => {}
                                     }
                                 }
                             }
@@ -97,41 +264,135 @@ pub fn terrain_grid(
             });
         });
 
+    if let (Some(origin), Some(end)) = (drag_origin, drag_end) {
+        match brush {
+            TerrainBrush::Box => {
+                let [min_row, max_row, min_col, max_col] = selection_bounds((origin, end));
+                for row in min_row..=max_row {
+                    for col in min_col..=max_col {
+                        changed.push((row, col));
+                    }
+                }
+            }
+            TerrainBrush::Line => get_line_tiles(&mut changed, origin, end),
+            TerrainBrush::Ellipse => get_ellipse_tiles(&mut changed, (origin, end)),
+            TerrainBrush::Select => tools.selection = Some((origin, end)),
+            _ => {}
+        }
+    }
+
+    let (width, height) = (
+        terrain.width.max(0) as usize,
+        terrain.height.max(0) as usize,
+    );
+    let mut edits: Vec<((usize, usize), String)> = vec![];
     let new_tid = state.terrain.read(|data| {
         selected_tile_index
             .and_then(|index| data.item(index))
             .map(|tile| tile.tid.clone())
     });
     if let Some(tid) = new_tid {
-        if let (Some((drag_start_row, drag_start_col)), Some((drag_end_row, drag_end_col))) =
-            (drag_origin, drag_end)
-        {
-            let min_row = drag_start_row.min(drag_end_row);
-            let max_row = drag_start_row.max(drag_end_row);
-            let min_col = drag_start_col.min(drag_end_col);
-            let max_col = drag_start_col.max(drag_end_col);
-            for row in min_row..=max_row {
-                for col in min_col..=max_col {
-                    changed.push((row, col));
+        // Replace already covers the whole map, so mirroring it would only repeat tiles.
+        let symmetry = if brush == TerrainBrush::Replace {
+            TerrainSymmetry::None
+        } else {
+            tools.symmetry
+        };
+        for tile in changed {
+            if tile.0 < height && tile.1 < width {
+                for tile in symmetry.mirror(tile, width, height) {
+                    edits.push((tile, tid.clone()));
                 }
             }
         }
-        if !changed.is_empty() {
-            for (row, col) in &changed {
-                let index = row * 32 + col;
-                if index < terrain.terrains.len() {
-                    terrain.terrains[index] = UString(tid.clone());
+    }
+    if let (Some((row, col)), Some(clipboard)) = (paste_origin, &tools.clipboard) {
+        // The clicked tile becomes the top left corner of the pasted region.
+        for line in 0..clipboard.height {
+            for offset in 0..clipboard.width {
+                if let Some(target_row) = row.checked_sub(line) {
+                    edits.push((
+                        (target_row, col + offset),
+                        clipboard.get(line, offset).to_string(),
+                    ));
                 }
             }
         }
     }
+
+    let mut any_changed = false;
+    for ((row, col), tid) in edits {
+        let index = row * TERRAIN_STRIDE + col;
+        if row < height && col < width && index < terrain.terrains.len() {
+            terrain.terrains[index] = UString(tid);
+            any_changed = true;
+        }
+    }
     TerrainGridResult {
-        changed: changed.is_empty(),
+        changed: any_changed,
         hovered_tile,
         selected_tile,
     }
 }
 
+fn get_matching_tiles(output: &mut Vec<(usize, usize)>, terrain: &TerrainData, target_tid: &str) {
+    for row in 0..(terrain.height.max(0) as usize) {
+        for col in 0..(terrain.width.max(0) as usize) {
+            if terrain
+                .terrains
+                .get(row * TERRAIN_STRIDE + col)
+                .map(|tid| tid.as_str())
+                == Some(target_tid)
+            {
+                output.push((row, col));
+            }
+        }
+    }
+}
+
+/// Bresenham's line between two tiles, both ends included.
+fn get_line_tiles(output: &mut Vec<(usize, usize)>, start: (usize, usize), end: (usize, usize)) {
+    let (mut row, mut col) = (start.0 as isize, start.1 as isize);
+    let (end_row, end_col) = (end.0 as isize, end.1 as isize);
+    let (delta_row, delta_col) = (-(end_row - row).abs(), (end_col - col).abs());
+    let (step_row, step_col) = ((end_row - row).signum(), (end_col - col).signum());
+    let mut error = delta_col + delta_row;
+    loop {
+        output.push((row as usize, col as usize));
+        if row == end_row && col == end_col {
+            break;
+        }
+        let doubled = error * 2;
+        if doubled >= delta_row {
+            error += delta_row;
+            col += step_col;
+        }
+        if doubled <= delta_col {
+            error += delta_col;
+            row += step_row;
+        }
+    }
+}
+
+/// Every tile inside the ellipse that fits the rectangle.
+fn get_ellipse_tiles(output: &mut Vec<(usize, usize)>, bounds: TerrainSelection) {
+    let [min_row, max_row, min_col, max_col] = selection_bounds(bounds);
+    let center_row = (min_row + max_row) as f32 / 2.;
+    let center_col = (min_col + max_col) as f32 / 2.;
+    // Measuring to the far edge of the outer tiles keeps thin ellipses from vanishing.
+    let radius_row = (max_row - min_row) as f32 / 2. + 0.5;
+    let radius_col = (max_col - min_col) as f32 / 2. + 0.5;
+    for row in min_row..=max_row {
+        for col in min_col..=max_col {
+            let y = (row as f32 - center_row) / radius_row;
+            let x = (col as f32 - center_col) / radius_col;
+            if x * x + y * y <= 1. {
+                output.push((row, col));
+            }
+        }
+    }
+}
+
 fn get_bucket_fill_tiles(
     output: &mut Vec<(usize, usize)>,
     tiles: &[UString],
@@ -162,7 +423,7 @@ fn get_bucket_fill_tiles_recursive(
     checked.insert((row, col));
 
     // Base case: out of bounds
-    if let Some(tid) = tiles.get(row * 32 + col) {
+    if let Some(tid) = tiles.get(row * TERRAIN_STRIDE + col) {
         // Base case: different tile
         if tid.as_str() == target_tid {
             // General case: note the tile and recurse to adjacent tiles